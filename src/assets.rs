@@ -1,6 +1,8 @@
 //! This module groups all the async loading stuff.
 
-use std::{collections::HashMap, hash::Hash};
+use std::{collections::HashMap, hash::Hash, path::Path};
+
+use log::{info, warn};
 
 use mq::{
     file::load_file,
@@ -14,14 +16,14 @@ use crate::{
     core::{
         battle::{
             ability::Ability,
-            component::{ObjType, Prototypes, WeaponType},
+            component::{ObjType, PrototypeDefs, Prototypes, WeaponType},
             effect,
             scenario::Scenario,
         },
         campaign,
     },
     error::ZError,
-    ZResult,
+    input, lang, settings, ZResult,
 };
 
 static INSTANCE: OnceCell<Assets> = OnceCell::new();
@@ -59,6 +61,121 @@ async fn load_map<Key: Hash + Eq + Copy>(
     Ok(map)
 }
 
+/// Mods are directories inside `mods/` (next to `assets/`) that are applied
+/// in the alphabetical order.
+///
+/// A mod may contain any of `objects.ron`, `sprites.ron` and `agent_campaign_info.ron`,
+/// whose entries are added to the game's ones or replace them,
+/// and any number of `scenario_*.ron` and `campaign_*.ron` files,
+/// which are added to the game's ones or replace the files with the same names.
+/// Sprite paths are relative to the mod's directory.
+const MODS_DIR: &str = "mods";
+
+#[cfg(not(target_arch = "wasm32"))]
+fn find_mods() -> ZResult<Vec<String>> {
+    let entries = match std::fs::read_dir(MODS_DIR) {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(error.into()),
+    };
+    let mut names = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            names.push(entry.file_name().to_string_lossy().into_owned());
+        }
+    }
+    names.sort();
+    Ok(names)
+}
+
+// There's no file system access in WASM.
+#[cfg(target_arch = "wasm32")]
+fn find_mods() -> ZResult<Vec<String>> {
+    Ok(Vec::new())
+}
+
+/// A path to the mod's file relative to the assets directory.
+fn mod_path(mod_name: &str, path: &str) -> String {
+    format!("../{}/{}/{}", MODS_DIR, mod_name, path)
+}
+
+fn mod_has_file(mod_name: &str, file_name: &str) -> bool {
+    Path::new(MODS_DIR).join(mod_name).join(file_name).exists()
+}
+
+/// Sorted names of the mod's `<prefix>*.ron` files.
+fn mod_files(mod_name: &str, prefix: &str) -> ZResult<Vec<String>> {
+    let mut names = Vec::new();
+    for entry in std::fs::read_dir(Path::new(MODS_DIR).join(mod_name))? {
+        let name = entry?.file_name().to_string_lossy().into_owned();
+        if name.starts_with(prefix) && name.ends_with(".ron") {
+            names.push(name);
+        }
+    }
+    names.sort();
+    Ok(names)
+}
+
+/// Remembers which mod has changed what to report the overridden entries:
+/// both the game's ones and the ones of the previous mods.
+#[derive(Default)]
+struct ModChanges {
+    owners: HashMap<String, String>,
+}
+
+impl ModChanges {
+    fn add(&mut self, mod_name: &str, file_name: &str, entry: &str, is_overridden: bool) {
+        let key = format!("{}: {}", file_name, entry);
+        if let Some(previous) = self.owners.insert(key.clone(), mod_name.into()) {
+            warn!(
+                "Mods conflict: '{}' overrides '{}' of '{}'",
+                mod_name, key, previous
+            );
+        } else if is_overridden {
+            warn!("Mod '{}' overrides the game's '{}'", mod_name, key);
+        }
+    }
+
+    fn merge<V>(
+        &mut self,
+        mod_name: &str,
+        file_name: &str,
+        table: &mut HashMap<ObjType, V>,
+        entries: HashMap<ObjType, V>,
+    ) {
+        for (typename, value) in entries {
+            let is_overridden = table.contains_key(&typename);
+            self.add(mod_name, file_name, &typename.0, is_overridden);
+            table.insert(typename, value);
+        }
+    }
+
+    /// Adds the mod's `<prefix>*.ron` files to the list,
+    /// replacing the files with the same names.
+    async fn merge_files<D: DeserializeOwned>(
+        &mut self,
+        mod_name: &str,
+        prefix: &str,
+        list: &mut Vec<(String, D)>,
+    ) -> ZResult {
+        for file_name in mod_files(mod_name, prefix)? {
+            let value = deserialize_from_file(&mod_path(mod_name, &file_name)).await?;
+            match list.iter_mut().find(|(name, _)| *name == file_name) {
+                Some(entry) => {
+                    self.add(mod_name, &file_name, "whole file", true);
+                    entry.1 = value;
+                }
+                None => {
+                    self.add(mod_name, &file_name, "whole file", false);
+                    list.push((file_name, value));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct SpriteInfo {
     pub paths: HashMap<String, String>,
@@ -83,14 +200,66 @@ pub struct Assets {
     pub sprites_info: SpritesInfo,
     pub sprite_frames: HashMap<ObjType, HashMap<String, Texture2D>>,
     pub prototypes: Prototypes,
-    pub demo_scenario: Scenario,
-    pub campaign_plan: campaign::Plan,
+
+    /// Demo battle scenarios by their file names, the game's one goes first.
+    pub scenarios: Vec<(String, Scenario)>,
+
+    /// Campaign plans by their file names, the game's one goes first.
+    pub campaigns: Vec<(String, campaign::Plan)>,
+
     pub agent_campaign_info: HashMap<ObjType, campaign::AgentInfo>,
+
+    /// Names of the active mods in the order they were applied.
+    pub mods: Vec<String>,
+
+    pub language_list: lang::LanguageList,
+    pub languages: HashMap<String, lang::Language>,
+
+    pub bindings: input::Bindings,
 }
 
 impl Assets {
     pub async fn load() -> ZResult<Self> {
-        let sprites_info: SpritesInfo = deserialize_from_file("sprites.ron").await?;
+        let mods = find_mods()?;
+        let mut changes = ModChanges::default();
+        let mut sprites_info: SpritesInfo = deserialize_from_file("sprites.ron").await?;
+        let mut prototype_defs = PrototypeDefs::parse(&read_file("objects.ron").await?)?;
+        let file_name = "scenario_01.ron";
+        let mut scenarios = vec![(file_name.into(), deserialize_from_file(file_name).await?)];
+        let file_name = "campaign_01.ron";
+        let mut campaigns = vec![(file_name.into(), deserialize_from_file(file_name).await?)];
+        let mut agent_campaign_info = deserialize_from_file("agent_campaign_info.ron").await?;
+        for name in &mods {
+            info!("Loading mod '{}'", name);
+            let file_name = "sprites.ron";
+            if mod_has_file(name, file_name) {
+                let mut entries: SpritesInfo =
+                    deserialize_from_file(&mod_path(name, file_name)).await?;
+                for info in entries.values_mut() {
+                    for path in info.paths.values_mut() {
+                        *path = mod_path(name, path);
+                    }
+                }
+                changes.merge(name, file_name, &mut sprites_info, entries);
+            }
+            let file_name = "objects.ron";
+            if mod_has_file(name, file_name) {
+                let s = read_file(&mod_path(name, file_name)).await?;
+                let entries = PrototypeDefs::parse(&s)?.0;
+                changes.merge(name, file_name, &mut prototype_defs.0, entries);
+            }
+            let file_name = "agent_campaign_info.ron";
+            if mod_has_file(name, file_name) {
+                let entries = deserialize_from_file(&mod_path(name, file_name)).await?;
+                changes.merge(name, file_name, &mut agent_campaign_info, entries);
+            }
+            changes
+                .merge_files(name, "scenario_", &mut scenarios)
+                .await?;
+            changes
+                .merge_files(name, "campaign_", &mut campaigns)
+                .await?;
+        }
         let sprite_frames = {
             let mut sprite_frames = HashMap::new();
             for (obj_type, SpriteInfo { paths, .. }) in sprites_info.iter() {
@@ -102,15 +271,26 @@ impl Assets {
             }
             sprite_frames
         };
+        let language_list: lang::LanguageList = deserialize_from_file("lang/languages.ron").await?;
+        let mut languages = HashMap::new();
+        for name in &language_list.languages {
+            let path = format!("lang/{}.ron", name);
+            languages.insert(name.clone(), deserialize_from_file(&path).await?);
+        }
+        let bindings = input::Bindings::new(deserialize_from_file("bindings.ron").await?);
         Ok(Self {
             textures: Textures::load().await?,
-            font: text::load_ttf_font("OpenSans-Regular.ttf").await?,
+            font: text::load_ttf_font(&settings::get().font).await?,
             sprites_info,
             sprite_frames,
-            prototypes: Prototypes::from_str(&read_file("objects.ron").await?),
-            demo_scenario: deserialize_from_file("scenario_01.ron").await?,
-            campaign_plan: deserialize_from_file("campaign_01.ron").await?,
-            agent_campaign_info: deserialize_from_file("agent_campaign_info.ron").await?,
+            prototypes: prototype_defs.resolve()?,
+            scenarios,
+            campaigns,
+            agent_campaign_info,
+            mods,
+            language_list,
+            languages,
+            bindings,
         })
     }
 }
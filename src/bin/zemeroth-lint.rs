@@ -0,0 +1,109 @@
+//! Checks the game data and reports all the found problems at once.
+//!
+//! Usage: `zemeroth-lint [--assets <dir>] [<scenario or campaign file>...]`
+
+use std::{collections::HashMap, env, fs, path::Path, process};
+
+use serde::de::DeserializeOwned;
+use zemeroth::core::{
+    battle::{component::Prototypes, scenario::Scenario},
+    campaign::Plan,
+    lint::{Data, Problem},
+};
+
+const USAGE: &str = "Usage: zemeroth-lint [--assets <dir>] [<scenario or campaign file>...]";
+
+type Result<T> = std::result::Result<T, String>;
+
+fn read(path: &Path) -> Result<String> {
+    fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn deserialize<D: DeserializeOwned>(path: &Path) -> Result<D> {
+    ron::de::from_str(&read(path)?).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn load_data(assets: &Path) -> Result<Data> {
+    let path = assets.join("objects.ron");
+    let prototypes: Prototypes = read(&path)?
+        .parse()
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+    // Only the names of the sprites matter here.
+    let sprites: HashMap<_, ron::Value> = deserialize(&assets.join("sprites.ron"))?;
+    let agent_info = deserialize(&assets.join("agent_campaign_info.ron"))?;
+    Ok(Data {
+        prototypes,
+        sprites: sprites.into_keys().collect(),
+        agent_info,
+    })
+}
+
+fn lint_file(data: &Data, path: &Path) -> Result<Vec<(String, Problem)>> {
+    let s = read(path)?;
+    // A campaign plan has required fields, while every scenario field has a default,
+    // so a campaign file can be mistaken for a scenario but not the other way around.
+    if let Ok(plan) = ron::de::from_str::<Plan>(&s) {
+        return Ok(data.lint_campaign(&plan));
+    }
+    let scenario: Scenario =
+        ron::de::from_str(&s).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let problems = data.lint_scenario(&scenario);
+    Ok(problems
+        .into_iter()
+        .map(|p| ("scenario".into(), p))
+        .collect())
+}
+
+fn run() -> Result<usize> {
+    let mut assets = "assets".to_string();
+    let mut files = Vec::new();
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--assets" => assets = args.next().ok_or_else(|| USAGE.to_string())?,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return Ok(0);
+            }
+            _ => files.push(arg),
+        }
+    }
+    let assets = Path::new(&assets);
+    let data = load_data(assets)?;
+    let mut problems_count = 0;
+    for problem in data.lint_agent_info() {
+        println!("agent_campaign_info.ron: {}", problem);
+        problems_count += 1;
+    }
+    for file in &files {
+        let path = Path::new(file);
+        // A broken file is just one more problem, the rest are still checked.
+        let problems = match lint_file(&data, path) {
+            Ok(problems) => problems,
+            Err(error) => {
+                println!("{}", error);
+                problems_count += 1;
+                continue;
+            }
+        };
+        for (location, problem) in problems {
+            println!("{}: {}: {}", path.display(), location, problem);
+            problems_count += 1;
+        }
+    }
+    Ok(problems_count)
+}
+
+fn main() {
+    match run() {
+        Ok(0) => {}
+        Ok(problems_count) => {
+            eprintln!("Found {} problem(s)", problems_count);
+            process::exit(1);
+        }
+        Err(error) => {
+            eprintln!("{}", error);
+            process::exit(2);
+        }
+    }
+}
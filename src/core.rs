@@ -1,4 +1,5 @@
 pub mod battle;
 pub mod campaign;
+pub mod lint;
 pub mod map;
 pub mod utils;
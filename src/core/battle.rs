@@ -1,6 +1,7 @@
 use std::{default::Default, fmt};
 
 use serde::{Deserialize, Serialize};
+use zcomponents::DenseId;
 
 pub use crate::core::{
     battle::{check::check, execute::execute, movement::MovePoints, state::State},
@@ -9,16 +10,21 @@ pub use crate::core::{
 
 pub mod ability;
 pub mod ai;
+pub mod attack;
 pub mod command;
 pub mod component;
 pub mod effect;
 pub mod event;
+pub mod event_log;
 pub mod execute;
 pub mod movement;
+pub mod preview;
 pub mod scenario;
 pub mod state;
+pub mod threat;
 
 mod check;
+mod dice;
 
 #[cfg(test)]
 mod tests;
@@ -81,6 +87,12 @@ impl fmt::Display for Turns {
 )]
 pub struct Id(i32);
 
+impl DenseId for Id {
+    fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
 #[derive(Serialize, Deserialize, Default, Clone, Copy, Debug, PartialEq, PartialOrd)]
 pub struct Strength(pub i32);
 
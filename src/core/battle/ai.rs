@@ -1,12 +1,15 @@
+use std::fmt::Debug;
+
 use log::info;
+use serde::{Deserialize, Serialize};
 
 use crate::core::{
     battle::{
         ability::Ability,
-        check,
+        attack, check,
         command::{self, Command},
         effect,
-        movement::{self, Path, Pathfinder},
+        movement::{self, MovePoints, Path, Pathfinder, WeightedCost},
         state, Id, PlayerId, State,
     },
     map::{self, Distance, HexMap},
@@ -30,6 +33,29 @@ fn check_path_is_ok(state: &State, id: Id, path: &Path) -> bool {
     check(state, &command).is_ok()
 }
 
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Difficulty {
+    /// Attacks random targets and doesn't use special abilities.
+    Easy,
+
+    #[default]
+    Normal,
+
+    /// Even armored agents avoid passing near enemies.
+    Hard,
+}
+
+/// Everyone avoids hazards, but only unarmored agents fear passing near enemies.
+fn cost_policy(state: &State, id: Id, difficulty: Difficulty) -> WeightedCost {
+    let is_armored = state::get_armor(state, id).0 > 0;
+    let fears_enemies = !is_armored || difficulty == Difficulty::Hard;
+    WeightedCost {
+        hazard: MovePoints(4),
+        rocks: MovePoints(0),
+        enemy_adjacency: MovePoints(if fears_enemies { 2 } else { 0 }),
+    }
+}
+
 #[derive(Clone, Debug)]
 enum PathfindingResult {
     Path(Path),
@@ -47,6 +73,8 @@ struct DistanceRange {
 pub struct Ai {
     id: PlayerId,
 
+    difficulty: Difficulty,
+
     distance_map: HexMap<bool>,
 
     /// Each AI has its own Pathfinder because it's not a part of the game state.
@@ -54,17 +82,23 @@ pub struct Ai {
 }
 
 impl Ai {
-    pub fn new(id: PlayerId, map_radius: Distance) -> Self {
+    pub fn new<T: Copy + Debug>(id: PlayerId, map: &HexMap<T>) -> Self {
         Self {
             id,
-            pathfinder: Pathfinder::new(map_radius),
-            distance_map: HexMap::new(map_radius),
+            difficulty: Difficulty::default(),
+            pathfinder: Pathfinder::new(map),
+            distance_map: HexMap::new_like(map),
         }
     }
 
-    /// Finds shortest path to some enemy.
+    pub fn with_difficulty(mut self, difficulty: Difficulty) -> Self {
+        self.difficulty = difficulty;
+        self
+    }
+
+    /// Finds the cheapest path to some enemy.
     fn find_path_to_nearest_enemy(&mut self, state: &State, agent_id: Id) -> Option<Path> {
-        self.pathfinder.fill_map(state, agent_id);
+        let policy = cost_policy(state, agent_id, self.difficulty);
         let mut best_path = None;
         let mut best_cost = movement::max_cost();
         for &target_id in &shuffle_vec(state::enemy_agent_ids(state, self.id)) {
@@ -74,11 +108,11 @@ impl Ai {
                 if !state.map().is_inboard(pos) {
                     continue;
                 }
-                let path = match self.pathfinder.path(pos) {
+                let path = match movement::find_path(state, agent_id, pos, &policy) {
                     Some(path) => path,
                     None => continue,
                 };
-                let cost = path.cost_for(state, agent_id);
+                let cost = path.cost_with_policy(state, agent_id, &policy);
                 if best_cost > cost {
                     best_cost = cost;
                     best_path = Some(path);
@@ -138,7 +172,8 @@ impl Ai {
     fn find_any_path(&mut self, state: &State, agent_id: Id) -> Option<Path> {
         self.pathfinder.fill_map(state, agent_id);
         let mut best_path = None;
-        let mut best_distance = state.map().radius();
+        // Any enemy will do, however far it is: the map may be wider than it's high.
+        let mut best_distance = Distance(i32::MAX);
         for pos in self.distance_map.iter() {
             let path = match self.pathfinder.path(pos) {
                 Some(path) => path,
@@ -220,7 +255,10 @@ impl Ai {
         None
     }
 
+    /// Picks the target with the best expected outcome, preferring likely kills.
+    /// An easy AI attacks the first target it can.
     fn try_to_attack(&self, state: &State, agent_id: Id) -> Option<Command> {
+        let mut best: Option<(f32, Id)> = None;
         for &target_id in &shuffle_vec(state::enemy_agent_ids(state, self.id)) {
             let attacker_id = agent_id;
             let command = command::Attack {
@@ -228,11 +266,29 @@ impl Ai {
                 target_id,
             }
             .into();
-            if check(state, &command).is_ok() {
+            if check(state, &command).is_err() {
+                continue;
+            }
+            if self.difficulty == Difficulty::Easy {
                 return Some(command);
             }
+            let distribution = attack::distribution(state, attacker_id, target_id);
+            let score = distribution.expected_damage() + distribution.kill_chance();
+            let is_better = match best {
+                Some((best_score, _)) => score > best_score,
+                None => true,
+            };
+            if is_better {
+                best = Some((score, target_id));
+            }
         }
-        None
+        best.map(|(_, target_id)| {
+            command::Attack {
+                attacker_id: agent_id,
+                target_id,
+            }
+            .into()
+        })
     }
 
     fn try_to_move_closer(&mut self, state: &State, id: Id) -> PathfindingResult {
@@ -345,22 +401,41 @@ impl Ai {
         let mut ids = state::players_agent_ids(state, self.id);
         state::sort_agent_ids_by_distance_to_enemies(state, &mut ids);
         for agent_id in ids {
-            if let Some(summon_command) = self.try_summon_imp(state, agent_id) {
-                return Some(summon_command);
-            }
-            if let Some(bloodlust_command) = self.try_bloodlust_imp(state, agent_id) {
-                return Some(bloodlust_command);
-            }
-            if let Some(bomb_command) = self.try_throw_bomb(state, agent_id) {
-                return Some(bomb_command);
-            }
-            if let Some(attack_command) = self.try_to_attack(state, agent_id) {
-                return Some(attack_command);
-            }
-            if let Some(move_command) = self.try_to_move(state, agent_id) {
-                return Some(move_command);
+            if let Some(command) = self.command_for_agent(state, agent_id) {
+                return Some(command);
             }
         }
         Some(command::EndTurn.into())
     }
+
+    /// Finds the best command for one specific agent of this AI's player.
+    ///
+    /// Returns `None` if the agent has nothing useful to do.
+    pub fn command_for_agent(&mut self, state: &State, agent_id: Id) -> Option<Command> {
+        if self.difficulty != Difficulty::Easy {
+            if let Some(command) = self.try_special_abilities(state, agent_id) {
+                return Some(command);
+            }
+        }
+        if let Some(attack_command) = self.try_to_attack(state, agent_id) {
+            return Some(attack_command);
+        }
+        if let Some(move_command) = self.try_to_move(state, agent_id) {
+            return Some(move_command);
+        }
+        None
+    }
+
+    fn try_special_abilities(&self, state: &State, agent_id: Id) -> Option<Command> {
+        if let Some(summon_command) = self.try_summon_imp(state, agent_id) {
+            return Some(summon_command);
+        }
+        if let Some(bloodlust_command) = self.try_bloodlust_imp(state, agent_id) {
+            return Some(bloodlust_command);
+        }
+        if let Some(bomb_command) = self.try_throw_bomb(state, agent_id) {
+            return Some(bomb_command);
+        }
+        None
+    }
 }
@@ -0,0 +1,132 @@
+use crate::core::{
+    battle::{state, Id, State, Strength},
+    utils,
+};
+
+/// Attack rolls are uniformly distributed in `[0, DICE_SIDES)`.
+pub const DICE_SIDES: i32 = 11;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HitChance {
+    /// The attack hits at a full force if the roll is not greater than this.
+    pub k_min: i32,
+
+    /// The attack misses if the roll is greater than this.
+    pub k_max: i32,
+}
+
+// TODO: Return a `Result` or an `Option` (check that attack is possible at all?).
+pub fn hit_chance(state: &State, attacker_id: Id, target_id: Id) -> HitChance {
+    let parts = state.parts();
+    let agent_target = parts.agent.get(target_id);
+    let agent_attacker = parts.agent.get(attacker_id);
+    let attacker_strength = parts.strength.get(attacker_id).strength;
+    let attacker_base_strength = parts.strength.get(attacker_id).base_strength;
+    let attacker_wounds = utils::clamp_max(attacker_base_strength.0 - attacker_strength.0, 3);
+    let target_dodge = agent_target.dodge;
+    let attack_accuracy = agent_attacker.attack_accuracy;
+    let attack_strength = agent_attacker.attack_strength;
+    let k_min = attack_accuracy.0 - target_dodge.0 - attacker_wounds;
+    let k_max = k_min + attack_strength.0;
+    HitChance { k_min, k_max }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Outcome {
+    Miss,
+    Wound {
+        damage: Strength,
+        armor_break: Strength,
+    },
+    Kill,
+}
+
+/// Resolves an attack for a specific dice roll.
+pub fn outcome(state: &State, attacker_id: Id, target_id: Id, roll: i32) -> Outcome {
+    let parts = state.parts();
+    let agent_attacker = parts.agent.get(attacker_id);
+    let target_strength = parts.strength.get(target_id).strength;
+    let target_armor = state::get_armor(state, target_id);
+    let attack_strength = agent_attacker.attack_strength;
+    let HitChance { k_max, .. } = hit_chance(state, attacker_id, target_id);
+    let damage_raw = k_max - roll;
+    if damage_raw < 0 {
+        return Outcome::Miss;
+    }
+    let damage = utils::clamp(damage_raw, 0, attack_strength.0);
+    let damage = Strength(utils::clamp_min(damage - target_armor.0, 0));
+    let armor_break = utils::clamp_max(agent_attacker.attack_break, target_armor);
+    if target_strength > damage {
+        Outcome::Wound {
+            damage,
+            armor_break,
+        }
+    } else {
+        Outcome::Kill
+    }
+}
+
+/// Exact probabilities of all possible outcomes of an attack.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Distribution {
+    /// Every outcome is listed only once, ordered from the worst to the best one.
+    outcomes: Vec<(Outcome, f32)>,
+
+    target_strength: Strength,
+}
+
+impl Distribution {
+    pub fn outcomes(&self) -> &[(Outcome, f32)] {
+        &self.outcomes
+    }
+
+    fn chance_of(&self, f: impl Fn(&Outcome) -> bool) -> f32 {
+        self.outcomes
+            .iter()
+            .filter(|(outcome, _)| f(outcome))
+            .map(|&(_, chance)| chance)
+            .sum()
+    }
+
+    pub fn miss_chance(&self) -> f32 {
+        self.chance_of(|outcome| *outcome == Outcome::Miss)
+    }
+
+    pub fn hit_chance(&self) -> f32 {
+        1.0 - self.miss_chance()
+    }
+
+    pub fn kill_chance(&self) -> f32 {
+        self.chance_of(|outcome| *outcome == Outcome::Kill)
+    }
+
+    /// A killed target counts as if it received the damage equal to its strength.
+    pub fn expected_damage(&self) -> f32 {
+        self.outcomes
+            .iter()
+            .map(|&(outcome, chance)| match outcome {
+                Outcome::Miss => 0.0,
+                Outcome::Wound { damage, .. } => damage.0 as f32 * chance,
+                Outcome::Kill => self.target_strength.0 as f32 * chance,
+            })
+            .sum()
+    }
+}
+
+pub fn distribution(state: &State, attacker_id: Id, target_id: Id) -> Distribution {
+    let mut outcomes: Vec<(Outcome, f32)> = Vec::new();
+    let chance_per_roll = 1.0 / DICE_SIDES as f32;
+    // The greater the roll is, the worse the outcome is, so iterate in reverse.
+    for roll in (0..DICE_SIDES).rev() {
+        let outcome = outcome(state, attacker_id, target_id, roll);
+        match outcomes.iter_mut().find(|(o, _)| *o == outcome) {
+            Some((_, chance)) => *chance += chance_per_roll,
+            None => outcomes.push((outcome, chance_per_roll)),
+        }
+    }
+    let target_strength = state.parts().strength.get(target_id).strength;
+    Distribution {
+        outcomes,
+        target_strength,
+    }
+}
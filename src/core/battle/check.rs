@@ -5,7 +5,7 @@ use crate::core::{
         self,
         ability::{self, Ability},
         command::{self, Command},
-        state, Attacks, Id, Jokers, Moves, PushStrength, State, Weight,
+        movement, state, Attacks, Id, Jokers, Moves, PushStrength, State, Weight,
     },
     map::{self, Distance, PosHex},
 };
@@ -43,6 +43,7 @@ pub enum Error {
     BadPos,
     BadActorType,
     BattleEnded,
+    StoppedByZoneOfControl,
 }
 
 const BOMB_THROW_DISTANCE_MAX: Distance = Distance(3);
@@ -54,8 +55,19 @@ fn check_command_move_to(state: &State, command: &command::MoveTo) -> Result<(),
         return Err(Error::CanNotCommandEnemyAgents);
     }
     check_agent_can_move(state, command.id)?;
+    let destination = command.path.to();
     for step in command.path.steps() {
-        check_not_blocked_and_is_inboard(state, step.to)?;
+        if !movement::can_move_on_from(state, command.id, step.from) {
+            return Err(Error::StoppedByZoneOfControl);
+        }
+        if step.to == destination {
+            check_not_blocked_and_is_inboard(state, step.to)?;
+        } else {
+            check_is_inboard(state, step.to)?;
+            if !movement::is_passable(state, command.id, step.to) {
+                return Err(Error::TileIsBlocked);
+            }
+        }
     }
     let cost = command.path.cost_for(state, command.id);
     if cost > agent.move_points {
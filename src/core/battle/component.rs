@@ -1,4 +1,12 @@
-use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, error, fmt, str::FromStr};
+
+use serde::{
+    de::{
+        self, value::EnumAccessDeserializer, DeserializeSeed, EnumAccess, IntoDeserializer,
+        MapAccess, SeqAccess, VariantAccess, Visitor,
+    },
+    Deserialize, Deserializer, Serialize,
+};
 use zcomponents::zcomponents_storage;
 
 use crate::core::{
@@ -6,6 +14,7 @@ use crate::core::{
         self,
         ability::{Ability, PassiveAbility, RechargeableAbility},
         effect::Timed,
+        movement::MovementProfile,
         Attacks, Id, Jokers, MovePoints, Moves, Phase, PlayerId, Rounds,
     },
     map,
@@ -82,6 +91,9 @@ pub struct Agent {
     pub move_points: MovePoints,
     pub reactive_attacks: Attacks,
 
+    #[serde(default)]
+    pub movement: MovementProfile,
+
     #[serde(default)]
     pub base_moves: Moves,
 
@@ -135,20 +147,62 @@ pub enum Component {
     Summoner(Summoner),
 }
 
-zcomponents_storage!(Parts<Id>: {
-    strength: Strength,
-    armor: Armor,
-    pos: Pos,
-    meta: Meta,
-    belongs_to: BelongsTo,
-    agent: Agent,
-    blocker: Blocker,
-    abilities: Abilities,
-    passive_abilities: PassiveAbilities,
-    effects: Effects,
-    schedule: Schedule,
-    summoner: Summoner,
-});
+impl Component {
+    pub fn kind(&self) -> ComponentKind {
+        match self {
+            Component::Pos(_) => ComponentKind::Pos,
+            Component::Strength(_) => ComponentKind::Strength,
+            Component::Armor(_) => ComponentKind::Armor,
+            Component::Meta(_) => ComponentKind::Meta,
+            Component::BelongsTo(_) => ComponentKind::BelongsTo,
+            Component::Agent(_) => ComponentKind::Agent,
+            Component::Blocker(_) => ComponentKind::Blocker,
+            Component::Abilities(_) => ComponentKind::Abilities,
+            Component::PassiveAbilities(_) => ComponentKind::PassiveAbilities,
+            Component::Effects(_) => ComponentKind::Effects,
+            Component::Schedule(_) => ComponentKind::Schedule,
+            Component::Summoner(_) => ComponentKind::Summoner,
+        }
+    }
+}
+
+/// A `Component`'s variant without the data.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ComponentKind {
+    Pos,
+    Strength,
+    Armor,
+    Meta,
+    BelongsTo,
+    Agent,
+    Blocker,
+    Abilities,
+    PassiveAbilities,
+    Effects,
+    Schedule,
+    Summoner,
+}
+
+// Ordered, so the battle logic visits the objects in the same order every time.
+// The components that most of the objects have are dense to make
+// the lookups and the state clones (e.g., for the previews) cheaper.
+zcomponents_storage!(
+    #[derive(Serialize, Deserialize)]
+    Parts<Id>: ordered {
+        #[dense] strength: Strength,
+        armor: Armor,
+        #[dense] pos: Pos,
+        #[dense] meta: Meta,
+        #[dense] belongs_to: BelongsTo,
+        #[dense] agent: Agent,
+        blocker: Blocker,
+        abilities: Abilities,
+        passive_abilities: PassiveAbilities,
+        effects: Effects,
+        schedule: Schedule,
+        summoner: Summoner,
+    }
+);
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Prototypes(pub HashMap<ObjType, Vec<Component>>);
@@ -167,13 +221,380 @@ fn init_component(component: &mut Component) {
     }
 }
 
-impl Prototypes {
-    pub fn from_str(s: &str) -> Self {
-        let mut prototypes: Prototypes = ron::de::from_str(s).expect("Can't parse the prototypes");
+#[derive(Debug)]
+pub enum Error {
+    Parse(ron::de::Error),
+    MissingParent {
+        typename: ObjType,
+        parent: ObjType,
+    },
+
+    /// Every prototype of the cycle, starting and ending with the same one.
+    InheritanceCycle(Vec<ObjType>),
+
+    /// The prototype overrides some of the agent's fields, but the parent has no agent.
+    NoAgentToOverride {
+        typename: ObjType,
+        parent: ObjType,
+    },
+
+    /// The prototype removes a component that the parent doesn't have.
+    NoComponentToRemove {
+        typename: ObjType,
+        parent: ObjType,
+        kind: ComponentKind,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Parse(error) => write!(f, "Can't parse the prototypes: {}", error),
+            Error::MissingParent { typename, parent } => write!(
+                f,
+                "Prototype '{}' extends '{}', which doesn't exist",
+                typename.0, parent.0
+            ),
+            Error::InheritanceCycle(cycle) => {
+                let names: Vec<&str> = cycle.iter().map(|typename| typename.0.as_str()).collect();
+                write!(f, "Prototypes inherit each other: {}", names.join(" -> "))
+            }
+            Error::NoAgentToOverride { typename, parent } => write!(
+                f,
+                "Prototype '{}' overrides the agent's fields, but '{}' has no agent",
+                typename.0, parent.0
+            ),
+            Error::NoComponentToRemove {
+                typename,
+                parent,
+                kind,
+            } => write!(
+                f,
+                "Prototype '{}' removes {:?}, but '{}' has no such component",
+                typename.0, kind, parent.0
+            ),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Parse(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+fn deserialize_some<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
+macro_rules! agent_override {
+    ($($field:ident: $ty:ty,)*) => {
+        /// Fields of the parent prototype's `Agent` to replace.
+        /// The missing ones are inherited.
+        #[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+        #[serde(default, deny_unknown_fields)]
+        pub struct AgentOverride {
+            $(
+                #[serde(deserialize_with = "deserialize_some")]
+                pub $field: Option<$ty>,
+            )*
+        }
+
+        impl AgentOverride {
+            fn apply(&self, agent: &mut Agent) {
+                $(
+                    if let Some(value) = &self.$field {
+                        agent.$field = value.clone();
+                    }
+                )*
+            }
+        }
+    };
+}
+
+agent_override! {
+    moves: Moves,
+    attacks: Attacks,
+    jokers: Jokers,
+    attack_strength: battle::Strength,
+    attack_distance: map::Distance,
+    attack_accuracy: battle::Accuracy,
+    weapon_type: WeaponType,
+    attack_break: battle::Strength,
+    dodge: battle::Dodge,
+    move_points: MovePoints,
+    reactive_attacks: Attacks,
+    movement: MovementProfile,
+}
+
+/// A component of a prototype that extends another one.
+#[derive(Clone, Debug)]
+pub enum ComponentOverride {
+    /// Replaces only the listed fields of the parent's agent.
+    Agent(AgentOverride),
+
+    /// Replaces the parent's component of the same kind.
+    Whole(Component),
+}
+
+/// Reads an enum variant's name, so the variant can be deserialized depending on it.
+struct VariantName;
+
+impl<'de> Visitor<'de> for VariantName {
+    type Value = String;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a component name")
+    }
+
+    fn visit_str<E: de::Error>(self, name: &str) -> Result<Self::Value, E> {
+        Ok(name.into())
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for VariantName {
+    type Value = String;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<String, D::Error> {
+        deserializer.deserialize_identifier(self)
+    }
+}
+
+/// An enum whose variant's name is already read.
+struct NamedVariant<V> {
+    name: String,
+    variant: V,
+}
+
+impl<'de, V: VariantAccess<'de>> EnumAccess<'de> for NamedVariant<V> {
+    type Error = V::Error;
+    type Variant = V;
+
+    fn variant_seed<S: DeserializeSeed<'de>>(self, seed: S) -> Result<(S::Value, V), V::Error> {
+        let value = seed.deserialize(self.name.into_deserializer())?;
+        Ok((value, self.variant))
+    }
+}
+
+struct ComponentOverrideVisitor;
+
+impl<'de> Visitor<'de> for ComponentOverrideVisitor {
+    type Value = ComponentOverride;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a component")
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Self::Value, A::Error> {
+        let (name, variant) = data.variant_seed(VariantName)?;
+        if name == "Agent" {
+            return variant.newtype_variant().map(ComponentOverride::Agent);
+        }
+        let data = NamedVariant { name, variant };
+        Component::deserialize(EnumAccessDeserializer::new(data)).map(ComponentOverride::Whole)
+    }
+}
+
+impl<'de> Deserialize<'de> for ComponentOverride {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        // The variants are checked by `Component` itself.
+        deserializer.deserialize_enum("Component", &[], ComponentOverrideVisitor)
+    }
+}
+
+/// A prototype as it's written in `objects.ron`: either a full list of components
+/// or a parent prototype and components that replace the parent's ones of the same kind.
+/// An `Agent` replaces only the listed fields, and `remove` drops the inherited components:
+///
+/// ```text
+/// "heavy_swordsman": (
+///     extends: "swordsman",
+///     remove: [Abilities],
+///     components: [
+///         Strength((strength: 6)),
+///         Agent((attack_accuracy: 6)),
+///     ],
+/// ),
+/// ```
+#[derive(Clone, Debug)]
+pub enum PrototypeDef {
+    Components(Vec<Component>),
+    Extends {
+        parent: ObjType,
+        remove: Vec<ComponentKind>,
+        components: Vec<ComponentOverride>,
+    },
+}
+
+#[derive(Deserialize)]
+#[serde(field_identifier, rename_all = "snake_case")]
+enum PrototypeDefField {
+    Extends,
+    Remove,
+    Components,
+}
+
+struct PrototypeDefVisitor;
+
+impl<'de> Visitor<'de> for PrototypeDefVisitor {
+    type Value = PrototypeDef;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a list of components or an `(extends: .., components: [..])` struct")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut components = Vec::new();
+        while let Some(component) = seq.next_element()? {
+            components.push(component);
+        }
+        Ok(PrototypeDef::Components(components))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut parent = None;
+        let mut remove = Vec::new();
+        let mut components = Vec::new();
+        while let Some(field) = map.next_key()? {
+            match field {
+                PrototypeDefField::Extends => parent = Some(map.next_value()?),
+                PrototypeDefField::Remove => remove = map.next_value()?,
+                PrototypeDefField::Components => components = map.next_value()?,
+            }
+        }
+        let parent = parent.ok_or_else(|| de::Error::missing_field("extends"))?;
+        Ok(PrototypeDef::Extends {
+            parent,
+            remove,
+            components,
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for PrototypeDef {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(PrototypeDefVisitor)
+    }
+}
+
+fn resolve_prototype(
+    defs: &HashMap<ObjType, PrototypeDef>,
+    resolved: &mut HashMap<ObjType, Vec<Component>>,
+    stack: &mut Vec<ObjType>,
+    typename: &ObjType,
+) -> Result<(), Error> {
+    if resolved.contains_key(typename) {
+        return Ok(());
+    }
+    if let Some(index) = stack.iter().position(|t| t == typename) {
+        let mut cycle = stack[index..].to_vec();
+        cycle.push(typename.clone());
+        return Err(Error::InheritanceCycle(cycle));
+    }
+    let components = match &defs[typename] {
+        PrototypeDef::Components(components) => components.clone(),
+        PrototypeDef::Extends {
+            parent,
+            remove,
+            components,
+        } => {
+            if !defs.contains_key(parent) {
+                return Err(Error::MissingParent {
+                    typename: typename.clone(),
+                    parent: parent.clone(),
+                });
+            }
+            stack.push(typename.clone());
+            resolve_prototype(defs, resolved, stack, parent)?;
+            stack.pop();
+            let mut inherited = resolved[parent].clone();
+            for &kind in remove {
+                let count = inherited.len();
+                inherited.retain(|c| c.kind() != kind);
+                if inherited.len() == count {
+                    return Err(Error::NoComponentToRemove {
+                        typename: typename.clone(),
+                        parent: parent.clone(),
+                        kind,
+                    });
+                }
+            }
+            for component in components {
+                match component {
+                    ComponentOverride::Agent(fields) => {
+                        let agent = inherited.iter_mut().find_map(|c| match c {
+                            Component::Agent(agent) => Some(agent),
+                            _ => None,
+                        });
+                        match agent {
+                            Some(agent) => fields.apply(agent),
+                            None => {
+                                return Err(Error::NoAgentToOverride {
+                                    typename: typename.clone(),
+                                    parent: parent.clone(),
+                                })
+                            }
+                        }
+                    }
+                    ComponentOverride::Whole(component) => {
+                        let kind = component.kind();
+                        match inherited.iter_mut().find(|c| c.kind() == kind) {
+                            Some(c) => *c = component.clone(),
+                            None => inherited.push(component.clone()),
+                        }
+                    }
+                }
+            }
+            inherited
+        }
+    };
+    resolved.insert(typename.clone(), components);
+    Ok(())
+}
+
+/// Prototypes with unresolved inheritance, so that several files can be merged
+/// before the inheritance is resolved.
+#[derive(Clone, Debug)]
+pub struct PrototypeDefs(pub HashMap<ObjType, PrototypeDef>);
+
+impl PrototypeDefs {
+    pub fn parse(s: &str) -> Result<Self, Error> {
+        ron::de::from_str(s)
+            .map(PrototypeDefs)
+            .map_err(Error::Parse)
+    }
+
+    pub fn resolve(&self) -> Result<Prototypes, Error> {
+        let defs = &self.0;
+        // Sort the names to report the same error every time.
+        let mut typenames: Vec<&ObjType> = defs.keys().collect();
+        typenames.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut resolved = HashMap::new();
+        for typename in typenames {
+            resolve_prototype(defs, &mut resolved, &mut Vec::new(), typename)?;
+        }
+        let mut prototypes = Prototypes(resolved);
         prototypes.init_components();
-        prototypes
+        Ok(prototypes)
     }
+}
+
+impl FromStr for Prototypes {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        PrototypeDefs::parse(s)?.resolve()
+    }
+}
 
+impl Prototypes {
     pub fn init_components(&mut self) {
         for components in self.0.values_mut() {
             for component in components {
@@ -182,3 +603,176 @@ impl Prototypes {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::core::battle::{self, ability::Ability};
+
+    use super::{
+        Abilities, Component, ComponentKind, Error, ObjType, PrototypeDefs, Prototypes, Strength,
+        WeaponType,
+    };
+
+    #[test]
+    fn prototypes_extends() {
+        let s = r#"#![enable(unwrap_newtypes)]
+        {
+            "base": [
+                Blocker(()),
+                Strength((strength: 3)),
+                Abilities([Jump]),
+            ],
+            "child": (
+                extends: "base",
+                components: [Strength((strength: 5))],
+            ),
+            "grandchild": (
+                extends: "child",
+                components: [Abilities([Rage])],
+            ),
+        }"#;
+        let prototypes = Prototypes::from_str(s).unwrap();
+        let grandchild = &prototypes.0[&ObjType::from("grandchild")];
+        assert_eq!(grandchild.len(), 3);
+        let strength = Strength {
+            base_strength: battle::Strength(5),
+            strength: battle::Strength(5),
+        };
+        assert_eq!(grandchild[1], Component::Strength(strength));
+        let abilities = Abilities(vec![Ability::Rage.into()]);
+        assert_eq!(grandchild[2], Component::Abilities(abilities));
+    }
+
+    #[test]
+    fn prototypes_extend_agent_fields() {
+        let s = r#"#![enable(unwrap_newtypes)]
+        {
+            "base": [
+                Agent((
+                    moves: 1,
+                    attacks: 1,
+                    jokers: 1,
+                    reactive_attacks: 1,
+                    attack_distance: 1,
+                    attack_strength: 2,
+                    attack_accuracy: 4,
+                    weapon_type: Slash,
+                    move_points: 3,
+                )),
+            ],
+            "child": (
+                extends: "base",
+                components: [Agent((attack_accuracy: 5, weapon_type: Pierce))],
+            ),
+        }"#;
+        let prototypes = Prototypes::from_str(s).unwrap();
+        let agent = match &prototypes.0[&ObjType::from("child")][..] {
+            [Component::Agent(agent)] => agent,
+            components => panic!("Unexpected components: {:?}", components),
+        };
+        assert_eq!(agent.attack_accuracy, battle::Accuracy(5));
+        assert_eq!(agent.weapon_type, WeaponType::Pierce);
+        assert_eq!(agent.attack_strength, battle::Strength(2));
+        assert_eq!(agent.move_points, battle::MovePoints(3));
+        assert_eq!(agent.base_attacks, battle::Attacks(1));
+    }
+
+    #[test]
+    fn prototypes_extend_missing_agent() {
+        let s = r#"#![enable(unwrap_newtypes)]
+        {
+            "base": [Blocker(())],
+            "child": (extends: "base", components: [Agent((attack_accuracy: 5))]),
+        }"#;
+        match Prototypes::from_str(s) {
+            Err(Error::NoAgentToOverride { typename, parent }) => {
+                assert_eq!(typename, "child".into());
+                assert_eq!(parent, "base".into());
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn prototypes_extend_remove() {
+        let s = r#"#![enable(unwrap_newtypes)]
+        {
+            "base": [
+                Blocker(()),
+                Abilities([Jump]),
+                PassiveAbilities([HeavyImpact]),
+            ],
+            "child": (
+                extends: "base",
+                remove: [Abilities, PassiveAbilities],
+                components: [Strength((strength: 5))],
+            ),
+        }"#;
+        let prototypes = Prototypes::from_str(s).unwrap();
+        let kinds: Vec<ComponentKind> = prototypes.0[&ObjType::from("child")]
+            .iter()
+            .map(Component::kind)
+            .collect();
+        assert_eq!(kinds, vec![ComponentKind::Blocker, ComponentKind::Strength]);
+    }
+
+    #[test]
+    fn prototypes_remove_missing_component() {
+        let s = r#"{
+            "base": [Blocker(())],
+            "child": (extends: "base", remove: [Abilities]),
+        }"#;
+        match Prototypes::from_str(s) {
+            Err(Error::NoComponentToRemove {
+                typename,
+                parent,
+                kind,
+            }) => {
+                assert_eq!(typename, "child".into());
+                assert_eq!(parent, "base".into());
+                assert_eq!(kind, ComponentKind::Abilities);
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn prototypes_extend_merged_defs() {
+        let mut defs = PrototypeDefs::parse(r#"{ "base": [Blocker(())] }"#).unwrap();
+        let s = r#"#![enable(unwrap_newtypes)]
+            { "child": (extends: "base", components: [Abilities([Jump])]) }"#;
+        defs.0.extend(PrototypeDefs::parse(s).unwrap().0);
+        let prototypes = defs.resolve().unwrap();
+        assert_eq!(prototypes.0[&ObjType::from("child")].len(), 2);
+    }
+
+    #[test]
+    fn prototypes_missing_parent() {
+        let s = r#"{ "child": (extends: "nothing", components: []) }"#;
+        match Prototypes::from_str(s) {
+            Err(Error::MissingParent { typename, parent }) => {
+                assert_eq!(typename, "child".into());
+                assert_eq!(parent, "nothing".into());
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn prototypes_inheritance_cycle() {
+        let s = r#"{
+            "a": (extends: "b", components: []),
+            "b": (extends: "c", components: []),
+            "c": (extends: "a", components: []),
+        }"#;
+        match Prototypes::from_str(s) {
+            Err(Error::InheritanceCycle(cycle)) => {
+                let expected: Vec<ObjType> = vec!["a".into(), "b".into(), "c".into(), "a".into()];
+                assert_eq!(cycle, expected);
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        }
+    }
+}
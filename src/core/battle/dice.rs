@@ -0,0 +1,99 @@
+//! Random choices of the battle logic.
+
+use std::{cell::RefCell, rc::Rc};
+
+use crate::core::utils;
+
+#[derive(Clone, Debug, Default)]
+pub enum Dice {
+    /// Uses the global RNG.
+    #[default]
+    Random,
+
+    /// Makes the choices from the script and never touches the global RNG.
+    Scripted(Rc<RefCell<Script>>),
+}
+
+impl Dice {
+    /// Picks an option with the given chance. The chances must sum to one.
+    pub fn choose(&self, chances: &[f32]) -> usize {
+        assert!(!chances.is_empty());
+        if chances.len() == 1 {
+            return 0;
+        }
+        match self {
+            Dice::Random => {
+                let mut roll: f32 = utils::roll_dice(0.0, 1.0);
+                for (index, &chance) in chances.iter().enumerate() {
+                    if roll < chance {
+                        return index;
+                    }
+                    roll -= chance;
+                }
+                chances.len() - 1
+            }
+            Dice::Scripted(script) => script.borrow_mut().choose(chances),
+        }
+    }
+
+    /// Picks one of `count` equally likely options.
+    pub fn pick(&self, count: usize) -> usize {
+        let chance = 1.0 / count as f32;
+        self.choose(&vec![chance; count])
+    }
+}
+
+/// Walks through all the possible sequences of choices, one sequence per run.
+#[derive(Debug)]
+pub struct Script {
+    /// The picked option and the number of options for every choice of the run.
+    choices: Vec<(usize, usize)>,
+
+    /// How many choices were made during the current run.
+    position: usize,
+
+    /// Chance of the current run's sequence of choices.
+    chance: f32,
+}
+
+impl Default for Script {
+    fn default() -> Self {
+        Self {
+            choices: Vec::new(),
+            position: 0,
+            chance: 1.0,
+        }
+    }
+}
+
+impl Script {
+    fn choose(&mut self, chances: &[f32]) -> usize {
+        if self.position == self.choices.len() {
+            self.choices.push((0, chances.len()));
+        }
+        let (index, count) = self.choices[self.position];
+        assert_eq!(count, chances.len(), "The run isn't reproducible");
+        self.position += 1;
+        self.chance *= chances[index];
+        index
+    }
+
+    pub fn chance(&self) -> f32 {
+        self.chance
+    }
+
+    /// Switches to the next sequence of choices.
+    /// Returns `false` if all of them were tried already.
+    pub fn next_run(&mut self) -> bool {
+        self.choices.truncate(self.position);
+        self.position = 0;
+        self.chance = 1.0;
+        while let Some((index, count)) = self.choices.pop() {
+            if index + 1 < count {
+                self.choices.push((index + 1, count));
+                return true;
+            }
+        }
+        false
+    }
+}
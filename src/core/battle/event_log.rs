@@ -0,0 +1,169 @@
+//! What the battle log tells about the events, apart from the text itself.
+
+use crate::core::{
+    battle::{
+        ability::{Ability, PassiveAbility},
+        effect::{self, Effect},
+        event::{self, ActiveEvent, AttackMode, Event},
+        Id, PlayerId, State, Strength,
+    },
+    map::PosHex,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum AttackOutcome {
+    Miss,
+    Wound(Strength),
+    Kill,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Kind {
+    BeginTurn(PlayerId),
+    EndBattle {
+        winner_id: PlayerId,
+    },
+    MoveTo {
+        id: Id,
+    },
+    Attack {
+        attacker_id: Id,
+        target_id: Id,
+        mode: AttackMode,
+        outcome: AttackOutcome,
+    },
+
+    /// All the explosion abilities of the bombs.
+    Explode {
+        id: Id,
+    },
+
+    UseAbility {
+        id: Id,
+        ability: Ability,
+    },
+    UsePassiveAbility {
+        id: Id,
+        ability: PassiveAbility,
+    },
+    EffectTick {
+        id: Id,
+        effect: effect::Lasting,
+    },
+    EffectEnd {
+        id: Id,
+        effect: effect::Lasting,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Record {
+    pub kind: Kind,
+
+    /// Objects that took part in the event.
+    pub ids: Vec<Id>,
+
+    /// Where the event happened: the positions of its objects at that moment
+    /// (they may move or die later) and the destination of a move.
+    pub positions: Vec<PosHex>,
+}
+
+/// The actor goes first and then all the objects affected by the event.
+fn involved_ids(actor_id: Id, event: &Event) -> Vec<Id> {
+    let instant = event.instant_effects.iter().map(|(id, _)| *id);
+    let timed = event.timed_effects.iter().map(|(id, _)| *id);
+    let mut ids = vec![actor_id];
+    for id in instant.chain(timed) {
+        if !ids.contains(&id) {
+            ids.push(id);
+        }
+    }
+    ids
+}
+
+fn attack_outcome(event: &Event, target_id: Id) -> AttackOutcome {
+    let effects: Vec<&Effect> = event
+        .instant_effects
+        .iter()
+        .filter(|(id, _)| *id == target_id)
+        .flat_map(|(_, effects)| effects)
+        .collect();
+    if effects
+        .iter()
+        .any(|effect| matches!(effect, Effect::Kill(_)))
+    {
+        return AttackOutcome::Kill;
+    }
+    let damage = effects.iter().find_map(|effect| match effect {
+        Effect::Wound(wound) => Some(wound.damage),
+        _ => None,
+    });
+    match damage {
+        Some(damage) => AttackOutcome::Wound(damage),
+        None => AttackOutcome::Miss,
+    }
+}
+
+fn kind_and_ids(event: &Event) -> Option<(Kind, Vec<Id>)> {
+    let kind_and_ids = match event.active_event {
+        ActiveEvent::Create | ActiveEvent::EndTurn(_) => return None,
+        ActiveEvent::EndBattle(ref ev) => {
+            let winner_id = ev.result.winner_id;
+            (Kind::EndBattle { winner_id }, Vec::new())
+        }
+        ActiveEvent::BeginTurn(ref ev) => (Kind::BeginTurn(ev.player_id), Vec::new()),
+        ActiveEvent::UseAbility(event::UseAbility { id, ability, .. }) => {
+            let kind = match ability {
+                Ability::ExplodePush
+                | Ability::ExplodeDamage
+                | Ability::ExplodeFire
+                | Ability::ExplodePoison => Kind::Explode { id },
+                ability => Kind::UseAbility { id, ability },
+            };
+            (kind, involved_ids(id, event))
+        }
+        ActiveEvent::UsePassiveAbility(event::UsePassiveAbility { id, ability, .. }) => {
+            let kind = Kind::UsePassiveAbility { id, ability };
+            (kind, involved_ids(id, event))
+        }
+        ActiveEvent::MoveTo(ref ev) => (Kind::MoveTo { id: ev.id }, vec![ev.id]),
+        ActiveEvent::Attack(ref ev) => {
+            let kind = Kind::Attack {
+                attacker_id: ev.attacker_id,
+                target_id: ev.target_id,
+                mode: ev.mode.clone(),
+                outcome: attack_outcome(event, ev.target_id),
+            };
+            (kind, vec![ev.attacker_id, ev.target_id])
+        }
+        ActiveEvent::EffectTick(event::EffectTick { id, effect }) => {
+            (Kind::EffectTick { id, effect }, vec![id])
+        }
+        ActiveEvent::EffectEnd(event::EffectEnd { id, effect }) => {
+            (Kind::EffectEnd { id, effect }, vec![id])
+        }
+    };
+    Some(kind_and_ids)
+}
+
+/// Must be called before the event is applied, while all its objects still exist.
+/// Returns `None` for the events that aren't worth a line.
+pub fn record(state: &State, event: &Event) -> Option<Record> {
+    let (kind, ids) = kind_and_ids(event)?;
+    let mut positions = Vec::new();
+    // The objects created by the event have no positions yet.
+    for &id in &ids {
+        if let Some(pos) = state.parts().pos.get_opt(id) {
+            positions.push(pos.0);
+        }
+    }
+    if let ActiveEvent::MoveTo(ref ev) = event.active_event {
+        positions.push(ev.path.to());
+    }
+    positions.dedup();
+    Some(Record {
+        kind,
+        ids,
+        positions,
+    })
+}
@@ -1,22 +1,21 @@
-use std::collections::HashMap;
-
 use log::{error, trace};
 
 use crate::core::{
     battle::{
         self,
         ability::{Ability, PassiveAbility},
+        attack,
         check::{check, Error},
         command::{self, Command},
         component::{self, ObjType},
         effect::{self, Effect},
         event::{self, ActiveEvent, Event},
-        movement::Path,
+        movement::{self, Path},
         state::{self, BattleResult, State},
         Id, Moves, Phase, PlayerId, PushStrength, Rounds, Strength, Weight,
     },
     map::{self, Dir, PosHex},
-    utils::{self, roll_dice},
+    utils,
 };
 
 #[derive(PartialEq, Clone, Copy, Debug)]
@@ -75,6 +74,9 @@ fn execute_move_to(state: &mut State, cb: Cb, command: &command::MoveTo) {
         let path = Path::new(vec![step.from, step.to]);
         do_move(state, cb, id, cost.take(), path);
         try_execute_passive_abilities_on_move(state, cb, id);
+        if state.parts().agent.get_opt(id).is_some() && is_passing_through_ally(state, id) {
+            continue;
+        }
         let attack_status = try_execute_reaction_attacks(state, cb, id);
         let is_alive = state.parts().agent.get_opt(id).is_some();
         if attack_status == AttackStatus::Hit || !is_alive {
@@ -83,6 +85,13 @@ fn execute_move_to(state: &mut State, cb: Cb, command: &command::MoveTo) {
     }
 }
 
+/// Reaction attacks can't stop an agent inside an ally's tile.
+fn is_passing_through_ally(state: &State, id: Id) -> bool {
+    let pos = state.parts().pos.get(id).0;
+    let blocker_ids = state::blocker_ids_at(state, pos);
+    blocker_ids.into_iter().any(|blocker_id| blocker_id != id)
+}
+
 fn do_move(state: &mut State, cb: Cb, id: Id, cost: Option<Moves>, path: Path) {
     let cost = cost.unwrap_or(Moves(0));
     let active_event = event::MoveTo { path, cost, id }.into();
@@ -273,6 +282,9 @@ fn try_execute_passive_abilities_tick(state: &mut State, cb: Cb, target_id: Id)
             assert!(state.parts().is_exist(target_id));
             match ability {
                 PassiveAbility::SpikeTrap => {
+                    if movement::profile(state, target_id).ignores_ground_traps() {
+                        continue;
+                    }
                     let context = try_execute_passive_ability_spike_trap(state, target_id);
                     do_passive_ability(state, cb, id, target_pos, ability, context);
                 }
@@ -835,63 +847,34 @@ fn wound_break_kill(
     }
 }
 
-// TODO: Return a `Result` or an `Option` (check that attack is possible at all?).
-// TODO: Return a struct with named fields.
-// TODO: Move to some other module.
-pub fn hit_chance(state: &State, attacker_id: Id, target_id: Id) -> (i32, i32) {
-    let parts = state.parts();
-    let agent_target = parts.agent.get(target_id);
-    let agent_attacker = parts.agent.get(attacker_id);
-    let attacker_strength = parts.strength.get(attacker_id).strength;
-    let attacker_base_strength = parts.strength.get(attacker_id).base_strength;
-    let attacker_wounds = utils::clamp_max(attacker_base_strength.0 - attacker_strength.0, 3);
-    let target_dodge = agent_target.dodge;
-    let attack_accuracy = agent_attacker.attack_accuracy;
-    let attack_strength = agent_attacker.attack_strength;
-    let k_min = attack_accuracy.0 - target_dodge.0 - attacker_wounds;
-    let k_max = k_min + attack_strength.0;
-    (k_min, k_max)
-}
-
 fn try_attack(state: &State, attacker_id: Id, target_id: Id) -> Option<Effect> {
-    let parts = state.parts();
-    let agent_attacker = state.parts().agent.get(attacker_id);
-    let target_strength = parts.strength.get(target_id).strength;
-    let target_armor = state::get_armor(state, target_id);
-    let attack_strength = agent_attacker.attack_strength;
     let attacker_pos = Some(state.parts().pos.get(attacker_id).0);
-    let (k_min, k_max) = hit_chance(state, attacker_id, target_id);
     if state.deterministic_mode() {
+        let chance = attack::hit_chance(state, attacker_id, target_id);
         // I want to be sure that I either will totally miss
         // or that I'll surely hit the target at a full force.
-        let sure_miss = k_min < 0;
-        let sure_hit = k_min > 10;
-        assert!(
-            sure_miss || sure_hit,
-            "Hit isn't determined: {:?}",
-            (k_min, k_max)
-        );
-    }
-    let r = roll_dice(0, 11);
-    let damage_raw = Strength(k_max - r);
-    let damage = Strength(utils::clamp(damage_raw.0, 0, attack_strength.0));
-    if damage_raw < Strength(0) {
-        // That was a total miss
-        return None;
+        let sure_miss = chance.k_min < 0;
+        let sure_hit = chance.k_min > 10;
+        assert!(sure_miss || sure_hit, "Hit isn't determined: {:?}", chance);
     }
-    let damage = correct_damage_with_armor(state, target_id, damage);
-    let attack_break = utils::clamp_max(agent_attacker.attack_break, target_armor);
-    let effect = if target_strength > damage {
-        effect::Wound {
+    let distribution = attack::distribution(state, attacker_id, target_id);
+    let chances: Vec<f32> = distribution.outcomes().iter().map(|o| o.1).collect();
+    let (outcome, _) = distribution.outcomes()[state.dice().choose(&chances)];
+    match outcome {
+        attack::Outcome::Miss => None,
+        attack::Outcome::Wound {
             damage,
-            armor_break: attack_break,
-            attacker_pos,
-        }
-        .into()
-    } else {
-        effect::Kill { attacker_pos }.into()
-    };
-    Some(effect)
+            armor_break,
+        } => Some(
+            effect::Wound {
+                damage,
+                armor_break,
+                attacker_pos,
+            }
+            .into(),
+        ),
+        attack::Outcome::Kill => Some(effect::Kill { attacker_pos }.into()),
+    }
 }
 
 fn execute_use_ability_explode_damage(
@@ -1185,21 +1168,17 @@ fn choose_who_to_summon(
     available_typenames: &[ObjType],
 ) -> ObjType {
     assert!(!available_typenames.is_empty());
-    let agents = existing_agents.iter().chain(new_agents);
-    let mut map = HashMap::new();
-    for typename in available_typenames {
-        map.insert(typename, 0);
-    }
-    for typename in agents {
-        if let Some(count) = map.get_mut(typename) {
-            *count += 1;
-        }
-    }
-    let (key, _value) = map
-        .into_iter()
-        .min_by_key(|&(_key, value)| value)
-        .expect("The map can't be empty");
-    key.clone()
+    let count = |typename: &&ObjType| {
+        let agents = existing_agents.iter().chain(new_agents);
+        agents.filter(|agent| agent == typename).count()
+    };
+    // The first of the equally rare types is picked, so the previews
+    // and the execution don't depend on a hash map's order.
+    available_typenames
+        .iter()
+        .min_by_key(count)
+        .expect("The list can't be empty")
+        .clone()
 }
 
 #[cfg(test)]
@@ -1,19 +1,85 @@
-use std::{collections::VecDeque, slice::Windows};
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap, VecDeque},
+    fmt::{self, Debug},
+    slice::Windows,
+};
 
 use serde::{Deserialize, Serialize};
 
 use crate::core::{
     battle::{ability::PassiveAbility, state, Id, State, TileType},
-    map::{dirs, Dir, Distance, HexMap, PosHex},
+    map::{self, dirs, Dir, HexMap, PosHex},
 };
 
 #[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub struct MovePoints(pub i32);
 
+/// How an agent moves across the terrain.
+#[derive(Serialize, Deserialize, Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MovementProfile {
+    #[default]
+    Walker,
+
+    /// Flies over rocks and ground traps and over allied agents.
+    Flyer,
+
+    /// Gets stuck in rocks.
+    Heavy,
+
+    /// Handles rough terrain better than walkers.
+    /// There's no water on the maps yet.
+    Amphibious,
+}
+
+impl MovementProfile {
+    pub fn terrain_cost(self, tile: TileType) -> MovePoints {
+        match (self, tile) {
+            (_, TileType::Plain) | (MovementProfile::Flyer, TileType::Rocks) => MovePoints(1),
+            (MovementProfile::Amphibious, TileType::Rocks) => MovePoints(2),
+            (MovementProfile::Walker, TileType::Rocks) => MovePoints(3),
+            (MovementProfile::Heavy, TileType::Rocks) => MovePoints(4),
+        }
+    }
+
+    /// Spike traps can't hurt agents that don't touch the ground.
+    pub fn ignores_ground_traps(self) -> bool {
+        self == MovementProfile::Flyer
+    }
+
+    /// Can the agent move through tiles blocked by allied agents?
+    pub fn passes_allies(self) -> bool {
+        self == MovementProfile::Flyer
+    }
+}
+
+impl fmt::Display for MovementProfile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            MovementProfile::Walker => write!(f, "Walker"),
+            MovementProfile::Flyer => write!(f, "Flyer"),
+            MovementProfile::Heavy => write!(f, "Heavy"),
+            MovementProfile::Amphibious => write!(f, "Amphibious"),
+        }
+    }
+}
+
+/// An optional scenario rule that limits moving past enemies.
+/// Tiles next to enemy agents form their zone of control.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ZoneOfControl {
+    /// Entering a zone of control ends the move.
+    StopOnEnter,
+
+    /// Leaving a zone of control costs additional move points.
+    ExtraCostToLeave(MovePoints),
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct Tile {
     cost: MovePoints,
     parent_dir: Option<Dir>,
+    can_stop: bool,
 }
 
 impl Tile {
@@ -24,6 +90,11 @@ impl Tile {
     pub fn cost(self) -> MovePoints {
         self.cost
     }
+
+    /// The agent can only pass through this tile if it's occupied by an ally.
+    pub fn can_stop(self) -> bool {
+        self.can_stop
+    }
 }
 
 impl Default for Tile {
@@ -31,6 +102,7 @@ impl Default for Tile {
         Self {
             cost: MovePoints(0),
             parent_dir: None,
+            can_stop: true,
         }
     }
 }
@@ -39,25 +111,137 @@ pub const fn max_cost() -> MovePoints {
     MovePoints(i32::max_value())
 }
 
-pub fn tile_cost(state: &State, _: Id, _: PosHex, pos: PosHex) -> MovePoints {
-    // taking other dangerous objects in the tile into account
-    for id in state.parts().passive_abilities.ids() {
-        if state.parts().pos.get(id).0 != pos {
-            continue;
-        }
-        for &ability in &state.parts().passive_abilities.get(id).0 {
+pub fn profile(state: &State, id: Id) -> MovementProfile {
+    state.parts().agent.get(id).movement
+}
+
+fn is_hazard_at(state: &State, id: Id, pos: PosHex) -> bool {
+    let profile = profile(state, id);
+    let parts = state.parts();
+    for object_id in state::ids_at(state, pos) {
+        let abilities = match parts.passive_abilities.get_opt(object_id) {
+            Some(abilities) => abilities,
+            None => continue,
+        };
+        for &ability in &abilities.0 {
             match ability {
+                PassiveAbility::SpikeTrap if profile.ignores_ground_traps() => {}
                 PassiveAbility::SpikeTrap | PassiveAbility::Burn | PassiveAbility::Poison => {
-                    return MovePoints(4);
+                    return true;
                 }
                 _ => {}
             }
         }
     }
-    // just tile's cost
-    match state.map().tile(pos) {
-        TileType::Plain => MovePoints(1),
-        TileType::Rocks => MovePoints(3),
+    false
+}
+
+/// Can the agent move through this tile (without stopping in it)?
+pub fn is_passable(state: &State, id: Id, pos: PosHex) -> bool {
+    if !state::is_tile_blocked(state, pos) {
+        return true;
+    }
+    if !profile(state, id).passes_allies() {
+        return false;
+    }
+    let parts = state.parts();
+    let player_id = parts.belongs_to.get(id).0;
+    for blocker_id in state::blocker_ids_at(state, pos) {
+        let is_ally = match parts.belongs_to.get_opt(blocker_id) {
+            Some(belongs_to) => {
+                belongs_to.0 == player_id && parts.agent.get_opt(blocker_id).is_some()
+            }
+            None => false,
+        };
+        if !is_ally {
+            return false;
+        }
+    }
+    true
+}
+
+pub fn is_in_zone_of_control(state: &State, id: Id, pos: PosHex) -> bool {
+    let player_id = state.parts().belongs_to.get(id).0;
+    state::check_enemies_around(state, pos, player_id)
+}
+
+/// Can the agent continue its move after stepping into this tile?
+pub fn can_move_on_from(state: &State, id: Id, pos: PosHex) -> bool {
+    if state.parts().pos.get(id).0 == pos {
+        // An agent can always leave the tile where its move started.
+        return true;
+    }
+    match state.scenario().zone_of_control {
+        Some(ZoneOfControl::StopOnEnter) => !is_in_zone_of_control(state, id, pos),
+        _ => true,
+    }
+}
+
+fn zone_of_control_cost(state: &State, id: Id, from: PosHex) -> MovePoints {
+    match state.scenario().zone_of_control {
+        Some(ZoneOfControl::ExtraCostToLeave(cost)) if is_in_zone_of_control(state, id, from) => {
+            cost
+        }
+        _ => MovePoints(0),
+    }
+}
+
+pub fn tile_cost(state: &State, id: Id, from: PosHex, pos: PosHex) -> MovePoints {
+    let extra_cost = zone_of_control_cost(state, id, from);
+    // taking other dangerous objects in the tile into account
+    let cost = if is_hazard_at(state, id, pos) {
+        MovePoints(4)
+    } else {
+        // just tile's cost
+        profile(state, id).terrain_cost(state.map().tile(pos))
+    };
+    MovePoints(cost.0 + extra_cost.0)
+}
+
+/// Decides how much a step costs for a specific agent when searching for a path.
+///
+/// This only affects what path is chosen: the agent always pays `tile_cost`.
+/// A step must cost at least one move point, otherwise A* may miss the best path.
+pub trait CostPolicy {
+    fn step_cost(&self, state: &State, id: Id, from: PosHex, to: PosHex) -> MovePoints;
+}
+
+/// Prefers the paths that spend the least move points.
+#[derive(Clone, Copy, Debug)]
+pub struct ActualCost;
+
+impl CostPolicy for ActualCost {
+    fn step_cost(&self, state: &State, id: Id, from: PosHex, to: PosHex) -> MovePoints {
+        tile_cost(state, id, from, to)
+    }
+}
+
+/// Adds penalties to the actual cost to avoid some tiles when possible.
+#[derive(Clone, Copy, Debug)]
+pub struct WeightedCost {
+    /// Spikes, fire and poison clouds.
+    pub hazard: MovePoints,
+
+    pub rocks: MovePoints,
+
+    /// Tiles next to the enemies of the agent.
+    pub enemy_adjacency: MovePoints,
+}
+
+impl CostPolicy for WeightedCost {
+    fn step_cost(&self, state: &State, id: Id, from: PosHex, to: PosHex) -> MovePoints {
+        let mut cost = tile_cost(state, id, from, to);
+        if is_hazard_at(state, id, to) {
+            cost.0 += self.hazard.0;
+        }
+        if state.map().tile(to) == TileType::Rocks {
+            cost.0 += self.rocks.0;
+        }
+        let player_id = state.parts().belongs_to.get(id).0;
+        if state::check_enemies_around(state, to, player_id) {
+            cost.0 += self.enemy_adjacency.0;
+        }
+        cost
     }
 }
 
@@ -95,6 +279,13 @@ impl Path {
                 break;
             }
             new_path.push(to);
+            if !can_move_on_from(state, id, to) {
+                break;
+            }
+        }
+        // Don't stop above an ally.
+        while new_path.len() >= 2 && state::is_tile_blocked(state, *new_path.last().unwrap()) {
+            new_path.pop();
         }
         if new_path.len() >= 2 {
             Some(Self::new(new_path))
@@ -104,9 +295,13 @@ impl Path {
     }
 
     pub fn cost_for(&self, state: &State, id: Id) -> MovePoints {
+        self.cost_with_policy(state, id, &ActualCost)
+    }
+
+    pub fn cost_with_policy(&self, state: &State, id: Id, policy: &dyn CostPolicy) -> MovePoints {
         let mut cost = MovePoints(0);
         for step in self.steps() {
-            cost.0 += tile_cost(state, id, step.from, step.to).0;
+            cost.0 += policy.step_cost(state, id, step.from, step.to).0;
         }
         cost
     }
@@ -144,6 +339,95 @@ impl<'a> Iterator for Steps<'a> {
     }
 }
 
+/// A node of the A* open set.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Node {
+    estimated_cost: MovePoints,
+    pos: PosHex,
+}
+
+impl Ord for Node {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed to make `BinaryHeap` a min-heap.
+        // The ties are broken by the position to keep `Ord` consistent with `Eq`
+        // and the order of the equally good nodes deterministic.
+        other
+            .estimated_cost
+            .cmp(&self.estimated_cost)
+            .then_with(|| (other.pos.q, other.pos.r).cmp(&(self.pos.q, self.pos.r)))
+    }
+}
+
+impl PartialOrd for Node {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Finds the cheapest path for the agent to the destination using A*.
+///
+/// Use this for point-to-point queries and `Pathfinder` for reachability maps.
+pub fn find_path(
+    state: &State,
+    id: Id,
+    destination: PosHex,
+    policy: &dyn CostPolicy,
+) -> Option<Path> {
+    let start = state.parts().pos.get(id).0;
+    if !state.map().is_inboard(destination) {
+        return None;
+    }
+    if start != destination && state::is_tile_blocked(state, destination) {
+        return None;
+    }
+    let heuristic = |pos| MovePoints(map::distance_hex(pos, destination).0);
+    let mut costs: HashMap<PosHex, MovePoints> = HashMap::new();
+    let mut parents: HashMap<PosHex, PosHex> = HashMap::new();
+    let mut open = BinaryHeap::new();
+    costs.insert(start, MovePoints(0));
+    open.push(Node {
+        estimated_cost: heuristic(start),
+        pos: start,
+    });
+    while let Some(Node { pos, .. }) = open.pop() {
+        if pos == destination {
+            let mut tiles = vec![pos];
+            let mut current = pos;
+            while let Some(&parent) = parents.get(&current) {
+                tiles.push(parent);
+                current = parent;
+            }
+            tiles.reverse();
+            return Some(Path::new(tiles));
+        }
+        if !can_move_on_from(state, id, pos) {
+            continue;
+        }
+        let cost = costs[&pos];
+        for dir in dirs() {
+            let neighbor_pos = Dir::get_neighbor_pos(pos, dir);
+            if !state.map().is_inboard(neighbor_pos) || !is_passable(state, id, neighbor_pos) {
+                continue;
+            }
+            let step_cost = policy.step_cost(state, id, pos, neighbor_pos);
+            let new_cost = MovePoints(cost.0 + step_cost.0);
+            let is_better = match costs.get(&neighbor_pos) {
+                Some(&old_cost) => new_cost < old_cost,
+                None => true,
+            };
+            if is_better {
+                costs.insert(neighbor_pos, new_cost);
+                parents.insert(neighbor_pos, pos);
+                open.push(Node {
+                    estimated_cost: MovePoints(new_cost.0 + heuristic(neighbor_pos).0),
+                    pos: neighbor_pos,
+                });
+            }
+        }
+    }
+    None
+}
+
 #[derive(Clone, Debug)]
 pub struct Pathfinder {
     queue: VecDeque<PosHex>,
@@ -151,10 +435,10 @@ pub struct Pathfinder {
 }
 
 impl Pathfinder {
-    pub fn new(map_radius: Distance) -> Self {
+    pub fn new<T: Copy + Debug>(map: &HexMap<T>) -> Self {
         Self {
             queue: VecDeque::new(),
-            map: HexMap::new(map_radius),
+            map: HexMap::new_like(map),
         }
     }
 
@@ -178,6 +462,7 @@ impl Pathfinder {
             let updated_tile = Tile {
                 cost: new_cost,
                 parent_dir: Some(parent_dir),
+                can_stop: !state::is_tile_blocked(state, neighbor_pos),
             };
             self.map.set_tile(neighbor_pos, updated_tile);
             self.queue.push_back(neighbor_pos);
@@ -189,6 +474,7 @@ impl Pathfinder {
             let tile = Tile {
                 cost: max_cost(),
                 parent_dir: None,
+                can_stop: true,
             };
             self.map.set_tile(pos, tile);
         }
@@ -196,9 +482,12 @@ impl Pathfinder {
 
     fn try_to_push_neighbors(&mut self, state: &State, id: Id, pos: PosHex) {
         assert!(self.map.is_inboard(pos));
+        if !can_move_on_from(state, id, pos) {
+            return;
+        }
         for dir in dirs() {
             let neighbor_pos = Dir::get_neighbor_pos(pos, dir);
-            if self.map.is_inboard(neighbor_pos) && !state::is_tile_blocked(state, neighbor_pos) {
+            if self.map.is_inboard(neighbor_pos) && is_passable(state, id, neighbor_pos) {
                 self.process_neighbor_pos(state, id, pos, neighbor_pos);
             }
         }
@@ -221,7 +510,8 @@ impl Pathfinder {
     }
 
     pub fn path(&self, destination: PosHex) -> Option<Path> {
-        if self.map.tile(destination).cost == max_cost() {
+        let tile = self.map.tile(destination);
+        if tile.cost == max_cost() || !tile.can_stop {
             return None;
         }
         let mut path = vec![destination];
@@ -246,8 +536,10 @@ impl Pathfinder {
 
 #[cfg(test)]
 mod tests {
+    use std::cmp::Ordering;
+
     use crate::core::battle::{
-        movement::{Path, Step},
+        movement::{MovePoints, Node, Path, Step},
         PosHex,
     };
 
@@ -295,4 +587,20 @@ mod tests {
         );
         assert_eq!(steps.next(), None);
     }
+
+    #[test]
+    fn node_order_is_consistent_with_eq() {
+        let node = |cost, pos| Node {
+            estimated_cost: MovePoints(cost),
+            pos,
+        };
+        let a = node(1, NODE_0);
+        let b = node(1, NODE_1);
+        assert_ne!(a, b);
+        assert_ne!(a.cmp(&b), Ordering::Equal);
+        assert_eq!(a.cmp(&b), b.cmp(&a).reverse());
+        assert_eq!(a.cmp(&node(1, NODE_0)), Ordering::Equal);
+        // The cheaper node goes first in a max-heap.
+        assert_eq!(node(0, NODE_2).cmp(&a), Ordering::Greater);
+    }
 }
@@ -0,0 +1,115 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::core::{
+    battle::{
+        check::{check, Error},
+        command::Command,
+        dice::{Dice, Script},
+        effect::Effect,
+        event::Event,
+        execute::{execute, ApplyPhase},
+        State,
+    },
+    map::PosHex,
+};
+
+/// Limits the number of the simulated sequences of random choices.
+const MAX_RUNS: i32 = 1024;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Outcome {
+    /// Events in the order they would be applied to the state.
+    pub events: Vec<Event>,
+
+    /// Tiles touched by the events: wounded or killed objects,
+    /// destinations of knockbacks and throws, created objects.
+    pub affected_positions: Vec<PosHex>,
+
+    /// Exact, unless the command has more than `MAX_RUNS` ways to go:
+    /// the rest of them are ignored then.
+    pub chance: f32,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Preview {
+    /// Sorted from the most likely outcome to the least likely one.
+    outcomes: Vec<Outcome>,
+}
+
+impl Preview {
+    pub fn outcomes(&self) -> &[Outcome] {
+        &self.outcomes
+    }
+}
+
+fn add_pos(positions: &mut Vec<PosHex>, pos: PosHex) {
+    if !positions.contains(&pos) {
+        positions.push(pos);
+    }
+}
+
+fn collect_affected_positions(state: &State, event: &Event, positions: &mut Vec<PosHex>) {
+    let pos_of = |id| state.parts().pos.get_opt(id).map(|pos| pos.0);
+    for (id, effects) in &event.instant_effects {
+        for effect in effects {
+            let pos = match effect {
+                Effect::Create(effect) => Some(effect.pos),
+                Effect::Knockback(effect) => Some(effect.to),
+                Effect::FlyOff(effect) => Some(effect.to),
+                Effect::Throw(effect) => Some(effect.to),
+                _ => pos_of(*id),
+            };
+            if let Some(pos) = pos {
+                add_pos(positions, pos);
+            }
+        }
+    }
+    for (id, _) in &event.timed_effects {
+        if let Some(pos) = pos_of(*id) {
+            add_pos(positions, pos);
+        }
+    }
+}
+
+fn dry_run(state: &State, command: &Command, dice: Dice) -> (Vec<Event>, Vec<PosHex>) {
+    let mut state = state.clone();
+    state.set_dice(dice);
+    let mut events = Vec::new();
+    let mut positions = Vec::new();
+    execute(&mut state, command, &mut |state, event, phase| {
+        if phase == ApplyPhase::Pre {
+            collect_affected_positions(state, event, &mut positions);
+            events.push(event.clone());
+        }
+    })
+    .expect("The command was checked");
+    (events, positions)
+}
+
+/// Runs the command on copies of the state and reports what may happen.
+///
+/// Every possible sequence of the random choices is simulated once,
+/// so the global RNG is never used.
+pub fn preview(state: &State, command: &Command) -> Result<Preview, Error> {
+    check(state, command)?;
+    let script = Rc::new(RefCell::new(Script::default()));
+    let mut outcomes: Vec<Outcome> = Vec::new();
+    for _ in 0..MAX_RUNS {
+        let dice = Dice::Scripted(script.clone());
+        let (events, affected_positions) = dry_run(state, command, dice);
+        let chance = script.borrow().chance();
+        match outcomes.iter_mut().find(|outcome| outcome.events == events) {
+            Some(outcome) => outcome.chance += chance,
+            None => outcomes.push(Outcome {
+                events,
+                affected_positions,
+                chance,
+            }),
+        }
+        if !script.borrow_mut().next_run() {
+            break;
+        }
+    }
+    outcomes.sort_by(|a, b| b.chance.partial_cmp(&a.chance).unwrap());
+    Ok(Preview { outcomes })
+}
@@ -1,14 +1,18 @@
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Debug,
+};
 
 use serde::{Deserialize, Serialize};
 
 use crate::core::{
     battle::{
         component::ObjType,
+        movement::ZoneOfControl,
         state::{self, State},
         PlayerId, TileType,
     },
-    map::{self, PosHex},
+    map::{self, HexMap, PosHex},
     utils::roll_dice,
 };
 
@@ -38,6 +42,13 @@ pub struct Object {
 #[serde(default)]
 pub struct Scenario {
     pub map_radius: map::Distance,
+
+    /// Overrides `map_radius` if present.
+    pub map_shape: Option<map::Shape>,
+
+    /// Tiles cut out of the map shape.
+    pub holes: Vec<PosHex>,
+
     pub players_count: i32,
 
     // TODO: rename it to `randomized_tiles` later (not only `TileType::Rocks`)
@@ -48,30 +59,71 @@ pub struct Scenario {
     pub randomized_objects: Vec<ObjectsGroup>,
 
     pub objects: Vec<Object>,
+
+    pub zone_of_control: Option<ZoneOfControl>,
 }
 
-#[derive(Clone, Debug, derive_more::From)]
+#[derive(Clone, Debug, PartialEq, derive_more::From)]
 pub enum Error {
     MapIsTooSmall,
     PosOutsideOfMap(PosHex),
     NoPlayerAgents,
     NoEnemyAgents,
     UnsupportedPlayersCount(i32),
+    #[from(ignore)]
+    OverlappingObjects(PosHex),
+    GroupsDoNotFit {
+        owner: PlayerId,
+        line: Line,
+        count: i32,
+        capacity: i32,
+    },
 }
 
 impl Scenario {
     pub fn check(&self) -> Result<(), Error> {
+        match self.errors().into_iter().next() {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    /// Unlike `check`, doesn't stop at the first error.
+    pub fn errors(&self) -> Vec<Error> {
+        let mut errors = Vec::new();
         if self.players_count != 2 {
-            return Err(Error::UnsupportedPlayersCount(self.players_count));
+            errors.push(Error::UnsupportedPlayersCount(self.players_count));
         }
-        if self.map_radius.0 < 3 {
-            return Err(Error::MapIsTooSmall);
+        let is_too_small = match self.map_shape() {
+            map::Shape::Hexagon { radius } => radius.0 < 3,
+            map::Shape::Rectangle { width, height }
+            | map::Shape::Parallelogram { width, height } => width < 5 || height < 5,
+        };
+        // The holes may leave no tiles even if the shape itself is big enough.
+        let map: Option<HexMap<bool>> = if is_too_small {
+            None
+        } else {
+            self.try_make_map()
+        };
+        if map.is_none() {
+            errors.push(Error::MapIsTooSmall);
         }
-        let origin = PosHex { q: 0, r: 0 };
+        let mut occupied = HashSet::new();
         for obj in &self.objects {
-            let dist = map::distance_hex(origin, obj.pos);
-            if dist > self.map_radius {
-                return Err(Error::PosOutsideOfMap(obj.pos));
+            if let Some(map) = &map {
+                if !map.is_inboard(obj.pos) {
+                    errors.push(Error::PosOutsideOfMap(obj.pos));
+                }
+            }
+            if !occupied.insert(obj.pos) {
+                errors.push(Error::OverlappingObjects(obj.pos));
+            }
+        }
+        if let Some(map) = &map {
+            for &pos in self.tiles.keys() {
+                if !map.is_inboard(pos) {
+                    errors.push(Error::PosOutsideOfMap(pos));
+                }
             }
         }
         let any_exact_player_agents = self
@@ -83,7 +135,7 @@ impl Scenario {
             .iter()
             .any(|obj| obj.owner == Some(PlayerId(0)));
         if !any_exact_player_agents && !any_random_player_agents {
-            return Err(Error::NoPlayerAgents);
+            errors.push(Error::NoPlayerAgents);
         }
         let any_exact_enemy_agents = self
             .objects
@@ -94,9 +146,62 @@ impl Scenario {
             .iter()
             .any(|obj| obj.owner == Some(PlayerId(1)));
         if !any_exact_enemy_agents && !any_random_enemy_agents {
-            return Err(Error::NoEnemyAgents);
+            errors.push(Error::NoEnemyAgents);
+        }
+        if let Some(map) = &map {
+            errors.extend(self.sector_errors(map));
         }
-        Ok(())
+        errors
+    }
+
+    /// Groups placed into the same sector must have enough tiles there.
+    fn sector_errors(&self, map: &HexMap<bool>) -> Vec<Error> {
+        let mut counts: Vec<(PlayerId, Line, i32)> = Vec::new();
+        for group in &self.randomized_objects {
+            let (owner, line) = match (group.owner, group.line) {
+                (Some(owner), Some(line)) if owner.0 < self.players_count => (owner, line),
+                _ => continue,
+            };
+            match counts
+                .iter_mut()
+                .find(|(o, l, _)| *o == owner && *l == line)
+            {
+                Some((_, _, count)) => *count += group.count,
+                None => counts.push((owner, line, group.count)),
+            }
+        }
+        let mut errors = Vec::new();
+        for (owner, line, count) in counts {
+            let capacity = sector(map, owner, line).len() as i32;
+            if count > capacity {
+                errors.push(Error::GroupsDoNotFit {
+                    owner,
+                    line,
+                    count,
+                    capacity,
+                });
+            }
+        }
+        errors
+    }
+
+    pub fn map_shape(&self) -> map::Shape {
+        match self.map_shape {
+            Some(shape) => shape,
+            None => map::Shape::Hexagon {
+                radius: self.map_radius,
+            },
+        }
+    }
+
+    /// Note: panics if the holes leave no tiles, see `check`.
+    pub fn make_map<T: Copy + Default + Debug>(&self) -> HexMap<T> {
+        HexMap::from_shape(self.map_shape(), &self.holes)
+    }
+
+    /// Same as `make_map`, but returns `None` if no tiles are left.
+    pub fn try_make_map<T: Copy + Default + Debug>(&self) -> Option<HexMap<T>> {
+        HexMap::try_from_shape(self.map_shape(), &self.holes)
     }
 }
 
@@ -104,11 +209,14 @@ impl Default for Scenario {
     fn default() -> Self {
         Self {
             map_radius: map::Distance(5),
+            map_shape: None,
+            holes: Vec::new(),
             players_count: 2,
             rocky_tiles_count: 0,
             tiles: HashMap::new(),
             randomized_objects: Vec::new(),
             objects: Vec::new(),
+            zone_of_control: None,
         }
     }
 }
@@ -116,11 +224,11 @@ impl Default for Scenario {
 pub fn random_free_pos(state: &State) -> Option<PosHex> {
     assert!(!state.deterministic_mode());
     let attempts = 30;
-    let radius = state.map().radius();
+    let (min, max) = state.map().bounds();
     for _ in 0..attempts {
         let pos = PosHex {
-            q: roll_dice(-radius.0, radius.0),
-            r: roll_dice(-radius.0, radius.0),
+            q: roll_dice(min.q, max.q + 1),
+            r: roll_dice(min.r, max.r + 1),
         };
         if state::is_tile_plain_and_completely_free(state, pos) {
             return Some(pos);
@@ -142,7 +250,7 @@ fn middle_range(min: i32, max: i32) -> (i32, i32) {
     (min, max)
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum Line {
     Any,
     Front,
@@ -162,21 +270,45 @@ impl Line {
     }
 }
 
+/// A horizontal coordinate of the tile's center on the screen, in half-tiles.
+fn screen_column(pos: PosHex) -> i32 {
+    2 * pos.q + pos.r
+}
+
+/// How many columns are there between the tile and the player's edge of the map.
+fn depth(player_id: PlayerId, min_column: i32, max_column: i32, pos: PosHex) -> i32 {
+    let half_tiles = match player_id.0 {
+        0 => screen_column(pos) - min_column,
+        1 => max_column - screen_column(pos),
+        _ => unimplemented!(),
+    };
+    half_tiles / 2
+}
+
+/// Tiles where the player's randomized objects of this line can be placed.
+fn sector<T: Copy + Debug>(map: &HexMap<T>, player_id: PlayerId, line: Line) -> Vec<PosHex> {
+    let min_column = map.iter().map(screen_column).min().unwrap();
+    let max_column = map.iter().map(screen_column).max().unwrap();
+    // Each player's half of the map is the analog of a hexagonal map's radius.
+    let half_width = map::Distance((max_column - min_column) / 4);
+    let (min, max) = line.to_range(half_width);
+    map.iter()
+        .filter(|&pos| {
+            let depth = depth(player_id, min_column, max_column, pos);
+            depth >= min && depth < max
+        })
+        .collect()
+}
+
 fn random_free_sector_pos(state: &State, player_id: PlayerId, line: Line) -> Option<PosHex> {
     assert!(!state.deterministic_mode());
     let attempts = 30;
-    let radius = state.map().radius();
-    let (min, max) = line.to_range(radius);
+    let sector = sector(state.map(), player_id, line);
+    if sector.is_empty() {
+        return None;
+    }
     for _ in 0..attempts {
-        let q = radius.0 - roll_dice(min, max);
-        let pos = PosHex {
-            q: match player_id.0 {
-                0 => -q,
-                1 => q,
-                _ => unimplemented!(),
-            },
-            r: roll_dice(-radius.0, radius.0 + 1),
-        };
+        let pos = sector[roll_dice(0, sector.len())];
         let no_enemies_around = !state::check_enemies_around(state, pos, player_id);
         if state::is_tile_completely_free(state, pos) && no_enemies_around {
             return Some(pos);
@@ -194,7 +326,12 @@ pub fn random_pos(state: &State, owner: Option<PlayerId>, line: Option<Line>) ->
 
 #[cfg(test)]
 mod tests {
-    use super::middle_range;
+    use crate::core::{
+        battle::PlayerId,
+        map::{self, Distance, PosHex},
+    };
+
+    use super::{middle_range, Error, Line, Object, ObjectsGroup, Scenario};
 
     #[test]
     fn test_middle_range() {
@@ -207,4 +344,68 @@ mod tests {
         assert_eq!(middle_range(0, 9), (2, 6));
         assert_eq!(middle_range(0, 10), (3, 7));
     }
+
+    #[test]
+    fn test_errors_are_collected() {
+        let pos = PosHex { q: 0, r: 0 };
+        let object = |owner| Object {
+            owner,
+            typename: "swordsman".into(),
+            pos,
+        };
+        let scenario = Scenario {
+            map_radius: Distance(3),
+            objects: vec![object(Some(PlayerId(0))), object(None)],
+            randomized_objects: vec![ObjectsGroup {
+                owner: Some(PlayerId(1)),
+                typename: "imp".into(),
+                line: Some(Line::Front),
+                count: 30,
+            }],
+            ..Scenario::default()
+        };
+        let errors = scenario.errors();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0], Error::OverlappingObjects(pos));
+        assert!(matches!(errors[1], Error::GroupsDoNotFit { count: 30, .. }));
+        assert_eq!(scenario.check(), Err(errors[0].clone()));
+    }
+
+    fn scenario_with_agents() -> Scenario {
+        let object = |owner, q| Object {
+            owner: Some(PlayerId(owner)),
+            typename: "swordsman".into(),
+            pos: PosHex { q, r: 0 },
+        };
+        Scenario {
+            objects: vec![object(0, 0), object(1, 1)],
+            ..Scenario::default()
+        }
+    }
+
+    #[test]
+    fn test_negative_radius() {
+        let scenario = Scenario {
+            map_radius: Distance(-1),
+            ..scenario_with_agents()
+        };
+        assert_eq!(scenario.errors(), vec![Error::MapIsTooSmall]);
+    }
+
+    #[test]
+    fn test_holes_cover_every_tile() {
+        let (width, height) = (5, 5);
+        let shape = map::Shape::Rectangle { width, height };
+        let holes = (-height..=height)
+            .flat_map(|r| (-width - height..=width + height).map(move |q| PosHex { q, r }))
+            .filter(|&pos| shape.contains(pos))
+            .collect();
+        let scenario = Scenario {
+            map_shape: Some(shape),
+            holes,
+            ..scenario_with_agents()
+        };
+        assert_eq!(scenario.errors(), vec![Error::MapIsTooSmall]);
+        assert!(scenario.try_make_map::<bool>().is_none());
+    }
 }
@@ -6,7 +6,6 @@ use crate::core::{
         effect, Id, PlayerId, Strength, TileType,
     },
     map::{self, PosHex},
-    utils,
 };
 
 pub use self::{
@@ -15,6 +14,7 @@ pub use self::{
 };
 
 mod apply;
+mod positions;
 mod private;
 
 pub fn is_agent_belong_to(state: &State, player_id: PlayerId, id: Id) -> bool {
@@ -23,36 +23,23 @@ pub fn is_agent_belong_to(state: &State, player_id: PlayerId, id: Id) -> bool {
 
 pub fn is_tile_blocked(state: &State, pos: PosHex) -> bool {
     assert!(state.map().is_inboard(pos));
-    for id in state.parts().blocker.ids() {
-        if state.parts().pos.get(id).0 == pos {
-            return true;
-        }
-    }
-    false
+    let blockers = &state.parts().blocker;
+    let ids = state.positions().ids_at(pos);
+    ids.iter().any(|&id| blockers.get_opt(id).is_some())
 }
 
 pub fn is_tile_plain_and_completely_free(state: &State, pos: PosHex) -> bool {
     if !state.map().is_inboard(pos) || state.map().tile(pos) != TileType::Plain {
         return false;
     }
-    for id in state.parts().pos.ids() {
-        if state.parts().pos.get(id).0 == pos {
-            return false;
-        }
-    }
-    true
+    is_tile_completely_free(state, pos)
 }
 
 pub fn is_tile_completely_free(state: &State, pos: PosHex) -> bool {
     if !state.map().is_inboard(pos) {
         return false;
     }
-    for id in state.parts().pos.ids() {
-        if state.parts().pos.get(id).0 == pos {
-            return false;
-        }
-    }
-    true
+    state.positions().ids_at(pos).is_empty()
 }
 
 pub fn is_lasting_effect_over(state: &State, id: Id, timed_effect: &effect::Timed) -> bool {
@@ -79,9 +66,9 @@ pub fn check_enemies_around(state: &State, pos: PosHex, player_id: PlayerId) ->
     false
 }
 
+/// Ids of all the objects at the position, in ascending order.
 pub fn ids_at(state: &State, pos: PosHex) -> Vec<Id> {
-    let i = state.parts().pos.ids();
-    i.filter(|&id| state.parts().pos.get(id).0 == pos).collect()
+    state.positions().ids_at(pos).to_vec()
 }
 
 pub fn obj_with_passive_ability_at(
@@ -124,36 +111,45 @@ pub fn agent_id_at_opt(state: &State, pos: PosHex) -> Option<Id> {
 }
 
 pub fn agent_ids_at(state: &State, pos: PosHex) -> Vec<Id> {
-    let i = state.parts().agent.ids();
-    i.filter(|&id| state.parts().pos.get(id).0 == pos).collect()
+    let agents = &state.parts().agent;
+    let ids = state.positions().ids_at(pos).iter().copied();
+    ids.filter(|&id| agents.get_opt(id).is_some()).collect()
 }
 
 pub fn blocker_ids_at(state: &State, pos: PosHex) -> Vec<Id> {
-    let i = state.parts().blocker.ids();
-    i.filter(|&id| state.parts().pos.get(id).0 == pos).collect()
+    let blockers = &state.parts().blocker;
+    let ids = state.positions().ids_at(pos).iter().copied();
+    ids.filter(|&id| blockers.get_opt(id).is_some()).collect()
 }
 
 pub fn players_agent_ids(state: &State, player_id: PlayerId) -> Vec<Id> {
-    let i = state.parts().agent.ids();
-    i.filter(|&id| is_agent_belong_to(state, player_id, id))
+    let parts = state.parts();
+    let agents = parts.agent.query().join(&parts.belongs_to);
+    agents
+        .filter(|(_, (_, belongs_to))| belongs_to.0 == player_id)
+        .map(|(id, _)| id)
         .collect()
 }
 
 pub fn enemy_agent_ids(state: &State, player_id: PlayerId) -> Vec<Id> {
-    let i = state.parts().agent.ids();
-    i.filter(|&id| !is_agent_belong_to(state, player_id, id))
+    let parts = state.parts();
+    let agents = parts.agent.query().join(&parts.belongs_to);
+    agents
+        .filter(|(_, (_, belongs_to))| belongs_to.0 != player_id)
+        .map(|(id, _)| id)
         .collect()
 }
 
 pub fn free_neighbor_positions(state: &State, origin: PosHex, count: i32) -> Vec<PosHex> {
     let mut positions = Vec::new();
-    for dir in utils::shuffle_vec(map::dirs().collect()) {
+    // The directions are picked one by one instead of shuffling them all at once,
+    // so the scripted dice go only through the picks that are actually made.
+    let mut dirs: Vec<map::Dir> = map::dirs().collect();
+    while !dirs.is_empty() && positions.len() < count as usize {
+        let dir = dirs.remove(state.dice().pick(dirs.len()));
         let pos = map::Dir::get_neighbor_pos(origin, dir);
         if state.map().is_inboard(pos) && !is_tile_blocked(state, pos) {
             positions.push(pos);
-            if positions.len() == count as usize {
-                break;
-            }
         }
     }
     positions
@@ -188,6 +184,14 @@ pub fn players_agent_types(state: &State, player_id: PlayerId) -> Vec<ObjType> {
         .collect()
 }
 
+/// Can the agent still move, attack or use an ability during this turn?
+pub fn has_actions_left(state: &State, id: Id) -> bool {
+    let agent = state.parts().agent.get(id);
+    agent.moves > battle::Moves(0)
+        || agent.attacks > battle::Attacks(0)
+        || agent.jokers > battle::Jokers(0)
+}
+
 pub fn can_agent_use_ability(state: &State, id: Id, ability: &Ability) -> bool {
     let parts = state.parts();
     let agent_player_id = parts.belongs_to.get(id).0;
@@ -5,7 +5,7 @@ use crate::core::battle::{
     component::{self, Component, Parts, PlannedAbility},
     effect::{self, Duration, Effect},
     event::{self, ActiveEvent, Event},
-    state, Attacks, Id, Jokers, Moves, Phase, PlayerId, State, Strength,
+    state, Attacks, Id, Jokers, Moves, Phase, PlayerId, PosHex, State, Strength,
 };
 
 pub fn apply(state: &mut State, event: &Event) {
@@ -48,13 +48,32 @@ fn add_components(state: &mut State, id: Id, components: &[Component]) {
     for component in components {
         add_component(parts, id, component.clone());
     }
+    if let Some(pos) = state.parts().pos.get_opt(id) {
+        let pos = pos.0;
+        state.positions_mut().add(id, pos);
+    }
+}
+
+/// Moves the object and updates the positions index.
+fn set_pos(state: &mut State, id: Id, pos: PosHex) {
+    let object_pos = &mut state.parts_mut().pos.get_mut(id).0;
+    let from = *object_pos;
+    *object_pos = pos;
+    state.positions_mut().move_to(id, from, pos);
+}
+
+/// Removes all the object's components and its entry in the positions index.
+fn remove_object(state: &mut State, id: Id) {
+    if let Some(pos) = state.parts().pos.get_opt(id) {
+        let pos = pos.0;
+        state.positions_mut().remove(id, pos);
+    }
+    state.parts_mut().remove(id);
 }
 
 fn apply_event_move_to(state: &mut State, event: &event::MoveTo) {
-    let parts = state.parts_mut();
-    let agent = parts.agent.get_mut(event.id);
-    let pos = parts.pos.get_mut(event.id);
-    pos.0 = event.path.to();
+    set_pos(state, event.id, event.path.to());
+    let agent = state.parts_mut().agent.get_mut(event.id);
     if agent.moves.0 > 0 {
         agent.moves.0 -= event.cost.0;
     } else {
@@ -80,13 +99,16 @@ fn apply_event_end_turn(state: &mut State, event: &event::EndTurn) {
     // Update attacks
     {
         let parts = state.parts_mut();
-        for id in parts.agent.ids_collected() {
-            let agent = parts.agent.get_mut(id);
-            let player_id = parts.belongs_to.get(id).0;
-            if player_id == event.player_id {
+        let agents = parts
+            .agent
+            .query_mut()
+            .join(&parts.belongs_to)
+            .left_join(&parts.effects);
+        for (_, (agent, belongs_to, effects)) in agents {
+            if belongs_to.0 == event.player_id {
                 agent.attacks.0 += agent.reactive_attacks.0;
             }
-            if let Some(effects) = parts.effects.get_opt(id) {
+            if let Some(effects) = effects {
                 for effect in &effects.0 {
                     if let effect::Lasting::Stun = effect.effect {
                         agent.attacks.0 = 0;
@@ -187,7 +209,7 @@ fn apply_event_use_ability(state: &mut State, event: &event::UseAbility) {
     }
     match event.ability {
         Ability::Jump | Ability::LongJump | Ability::Dash => {
-            parts.pos.get_mut(id).0 = event.pos;
+            set_pos(state, id, event.pos);
         }
         Ability::Rage => {
             let component = parts.agent.get_mut(id);
@@ -278,13 +300,11 @@ fn apply_effect_create(state: &mut State, id: Id, effect: &effect::Create) {
 }
 
 fn apply_effect_kill(state: &mut State, id: Id, _: &effect::Kill) {
-    let parts = state.parts_mut();
-    parts.remove(id);
+    remove_object(state, id);
 }
 
 fn apply_effect_vanish(state: &mut State, id: Id) {
-    let parts = state.parts_mut();
-    parts.remove(id);
+    remove_object(state, id);
 }
 
 fn apply_effect_stun(state: &mut State, id: Id) {
@@ -346,8 +366,7 @@ fn apply_effect_knockback(state: &mut State, id: Id, effect: &effect::Knockback)
     }
     assert!(state.map().is_inboard(effect.to));
     assert!(!state::is_tile_blocked(state, effect.to));
-    let parts = state.parts_mut();
-    parts.pos.get_mut(id).0 = effect.to;
+    set_pos(state, id, effect.to);
 }
 
 fn apply_effect_fly_off(state: &mut State, id: Id, effect: &effect::FlyOff) {
@@ -357,16 +376,14 @@ fn apply_effect_fly_off(state: &mut State, id: Id, effect: &effect::FlyOff) {
     }
     assert!(state.map().is_inboard(effect.to));
     assert!(!state::is_tile_blocked(state, effect.to));
-    let parts = state.parts_mut();
-    parts.pos.get_mut(id).0 = effect.to;
+    set_pos(state, id, effect.to);
 }
 
 fn apply_effect_throw(state: &mut State, id: Id, effect: &effect::Throw) {
     assert!(state.map().is_inboard(effect.from));
     assert!(state.map().is_inboard(effect.to));
     assert!(!state::is_tile_blocked(state, effect.to));
-    let parts = state.parts_mut();
-    parts.pos.get_mut(id).0 = effect.to;
+    set_pos(state, id, effect.to);
 }
 
 fn apply_effect_bloodlust(state: &mut State, id: Id) {
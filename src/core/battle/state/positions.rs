@@ -0,0 +1,58 @@
+use std::collections::HashMap;
+
+use crate::core::{
+    battle::{component::Parts, Id},
+    map::PosHex,
+};
+
+/// Index of the objects by their positions, so the position queries
+/// don't have to scan all the objects.
+///
+/// The ids of every tile are kept sorted.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Positions {
+    ids: HashMap<PosHex, Vec<Id>>,
+}
+
+impl Positions {
+    /// Builds the index from scratch.
+    pub fn from_parts(parts: &Parts) -> Self {
+        let mut positions = Self::default();
+        for (id, (pos,)) in parts.pos.query() {
+            positions.add(id, pos.0);
+        }
+        positions
+    }
+
+    pub fn ids_at(&self, pos: PosHex) -> &[Id] {
+        self.ids.get(&pos).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn add(&mut self, id: Id, pos: PosHex) {
+        let ids = self.ids.entry(pos).or_default();
+        match ids.binary_search(&id) {
+            Ok(_) => panic!("{:?} is already at {:?}", id, pos),
+            Err(index) => ids.insert(index, id),
+        }
+    }
+
+    /// Note: panics if there's no such object at the position.
+    pub fn remove(&mut self, id: Id, pos: PosHex) {
+        let ids = self
+            .ids
+            .get_mut(&pos)
+            .unwrap_or_else(|| panic!("No objects at {:?}", pos));
+        let index = ids
+            .binary_search(&id)
+            .unwrap_or_else(|_| panic!("Can't find {:?} at {:?}", id, pos));
+        ids.remove(index);
+        if ids.is_empty() {
+            self.ids.remove(&pos);
+        }
+    }
+
+    pub fn move_to(&mut self, id: Id, from: PosHex, to: PosHex) {
+        self.remove(id, from);
+        self.add(id, to);
+    }
+}
@@ -1,13 +1,15 @@
 use log::error;
+use zcomponents::Changes;
 
 use crate::core::{
     battle::{
         command,
         component::{Component, ObjType, Parts, Prototypes},
+        dice::Dice,
         event::Event,
         execute,
         scenario::{self, Scenario},
-        state::apply::apply,
+        state::{apply::apply, positions::Positions},
         Id, PlayerId, TileType,
     },
     map,
@@ -22,11 +24,13 @@ pub struct BattleResult {
 #[derive(Clone, Debug)]
 pub struct State {
     parts: Parts,
+    positions: Positions,
     map: map::HexMap<TileType>,
     scenario: Scenario,
     player_id: PlayerId,
     prototypes: Prototypes,
     battle_result: Option<BattleResult>,
+    dice: Dice,
 
     /// Enables panics when non-deterministic functions are called.
     deterministic_mode: bool,
@@ -35,14 +39,15 @@ pub struct State {
 impl State {
     pub fn new(prototypes: Prototypes, scenario: Scenario, cb: execute::Cb) -> Self {
         scenario.check().expect("Bad scenario");
-        assert!(scenario.map_radius.0 >= 3);
         let mut this = Self {
-            map: map::HexMap::new(scenario.map_radius),
+            map: scenario.make_map(),
             player_id: PlayerId(0),
             scenario,
             parts: Parts::new(),
+            positions: Positions::default(),
             prototypes,
             battle_result: None,
+            dice: Dice::Random,
             deterministic_mode: false,
         };
         this.create_terrain();
@@ -58,8 +63,10 @@ impl State {
         &self.scenario
     }
 
-    // TODO: Handle Scenario::exact_tiles
     fn create_terrain(&mut self) {
+        for (&pos, &tile) in &self.scenario.tiles {
+            self.map.set_tile(pos, tile);
+        }
         for _ in 0..self.scenario.rocky_tiles_count {
             let pos = match scenario::random_free_pos(self) {
                 Some(pos) => pos,
@@ -130,6 +137,10 @@ impl State {
         &self.map
     }
 
+    pub(super) fn positions(&self) -> &Positions {
+        &self.positions
+    }
+
     pub(in crate::core) fn prototype_for(&self, name: &ObjType) -> Vec<Component> {
         let prototypes = &self.prototypes.0;
         prototypes[name].clone()
@@ -138,6 +149,10 @@ impl State {
     pub fn battle_result(&self) -> &Option<BattleResult> {
         &self.battle_result
     }
+
+    pub(in crate::core) fn dice(&self) -> &Dice {
+        &self.dice
+    }
 }
 
 /// Public mutators. Be careful with them!
@@ -146,6 +161,11 @@ impl State {
         &mut self.parts
     }
 
+    /// Must be updated together with the `Pos` components.
+    pub(super) fn positions_mut(&mut self) -> &mut Positions {
+        &mut self.positions
+    }
+
     pub(in crate::core) fn set_player_id(&mut self, new_value: PlayerId) {
         self.player_id = new_value;
     }
@@ -154,6 +174,10 @@ impl State {
         self.battle_result = Some(result);
     }
 
+    pub(in crate::core) fn set_dice(&mut self, dice: Dice) {
+        self.dice = dice;
+    }
+
     #[allow(dead_code)]
     pub fn set_deterministic_mode(&mut self, value: bool) {
         self.deterministic_mode = value;
@@ -165,5 +189,27 @@ impl State {
 
     pub fn apply(&mut self, event: &Event) {
         apply(self, event);
+        if cfg!(debug_assertions) {
+            self.check_positions();
+        }
+    }
+
+    /// Panics if the positions index doesn't match the `Pos` components.
+    fn check_positions(&self) {
+        let expected = Positions::from_parts(&self.parts);
+        assert_eq!(
+            self.positions, expected,
+            "The positions index is out of sync with the components"
+        );
+    }
+
+    /// Starts or stops recording the changed components, see `take_changes`.
+    pub fn set_change_tracking(&mut self, enabled: bool) {
+        self.parts.set_change_tracking(enabled);
+    }
+
+    /// The components that were changed since the previous call.
+    pub fn take_changes(&mut self) -> Changes<Id> {
+        self.parts.take_changes()
     }
 }
@@ -1,4 +1,5 @@
 use pretty_assertions::assert_eq;
+use zcomponents::ChangeKind;
 
 // TODO: don't construct your own Id(*), get them from the state!
 //     They're kind of implementation detail (can be shifted
@@ -7,19 +8,24 @@ use pretty_assertions::assert_eq;
 use crate::core::{
     battle::{
         ability::{Ability, PassiveAbility},
+        attack::{self, Outcome},
         check,
         command::{self, Command},
         component::{self, Component, ObjType, PlannedAbility, Prototypes, WeaponType},
         effect::{self, Effect},
         event::{self, ActiveEvent, AttackMode, Event},
+        event_log,
         execute::{execute, ApplyPhase},
-        movement::Path,
-        scenario::{Object, Scenario},
+        movement::{
+            self, ActualCost, MovementProfile, Path, Pathfinder, WeightedCost, ZoneOfControl,
+        },
+        preview::preview,
+        scenario::{self, Line, Object, ObjectsGroup, Scenario},
         state::BattleResult,
-        Accuracy, Attacks, Dodge, Id, Jokers, MovePoints, Moves, Phase, PlayerId, PushStrength,
-        State, Strength, Weight,
+        threat, Accuracy, Attacks, Dodge, Id, Jokers, MovePoints, Moves, Phase, PlayerId,
+        PushStrength, State, Strength, TileType, Weight,
     },
-    map::{Distance, PosHex},
+    map::{self, Distance, PosHex},
 };
 
 const P0: PlayerId = PlayerId(0);
@@ -64,6 +70,7 @@ fn agent_dull() -> component::Agent {
         dodge: Dodge(0),
         move_points: MovePoints(0),
         reactive_attacks: Attacks(0),
+        movement: MovementProfile::Walker,
         base_moves: Moves(0),
         base_attacks: Attacks(0),
         base_jokers: Jokers(0),
@@ -331,6 +338,33 @@ fn basic_move() {
     );
 }
 
+#[test]
+fn move_changes_only_agent_and_pos() {
+    let prototypes = prototypes(&[
+        ("mover", [component_agent_move_basic()].to_vec()),
+        ("dull", [component_agent_dull()].to_vec()),
+    ]);
+    let scenario = Scenario::default()
+        .object(P0, "mover", PosHex { q: 0, r: 0 })
+        .object(P1, "dull", PosHex { q: 0, r: 2 });
+    let mut state = debug_state(prototypes, scenario);
+    let snapshot = state.clone();
+    state.set_change_tracking(true);
+    let path = Path::new(vec![PosHex { q: 0, r: 0 }, PosHex { q: 0, r: 1 }]);
+    exec(&mut state, command::MoveTo { id: Id(0), path });
+    let changes = state.take_changes();
+    let changed: Vec<_> = changes
+        .iter()
+        .map(|c| (c.component, c.id, c.kind))
+        .collect();
+    let expected = vec![
+        ("pos", Id(0), ChangeKind::Modified),
+        ("agent", Id(0), ChangeKind::Modified),
+    ];
+    assert_eq!(changed, expected);
+    assert_eq!(snapshot.parts().diff(state.parts()), changes);
+}
+
 #[test]
 fn basic_attack() {
     let prototypes = prototypes(&[
@@ -1745,3 +1779,730 @@ fn heavy_strike_flyoff_on_spikes() {
     );
     assert_eq!(state.parts().pos.get(Id(1)).0, position_target_updated);
 }
+
+/// Compares outcome chances with the expected numbers of dice rolls.
+fn assert_outcomes(distribution: &attack::Distribution, expected: &[(Outcome, i32)]) {
+    let outcomes = distribution.outcomes();
+    assert_eq!(outcomes.len(), expected.len());
+    for (&(outcome, chance), &(expected_outcome, rolls)) in outcomes.iter().zip(expected) {
+        assert_eq!(outcome, expected_outcome);
+        let expected_chance = rolls as f32 / attack::DICE_SIDES as f32;
+        assert!((chance - expected_chance).abs() < 0.0001);
+    }
+}
+
+#[test]
+fn attack_distribution_sure_hit() {
+    let prototypes = prototypes(&[
+        (
+            "swordsman",
+            [
+                component_agent_always_hit_strength_1(),
+                component_strength(1),
+            ]
+            .to_vec(),
+        ),
+        (
+            "imp",
+            [component_agent_dull(), component_strength(2)].to_vec(),
+        ),
+    ]);
+    let scenario = Scenario::default()
+        .object(P0, "swordsman", PosHex { q: 0, r: 0 })
+        .object(P1, "imp", PosHex { q: 0, r: 1 });
+    let state = debug_state(prototypes, scenario);
+    let distribution = attack::distribution(&state, Id(0), Id(1));
+    let wound = Outcome::Wound {
+        damage: Strength(1),
+        armor_break: Strength(0),
+    };
+    assert_outcomes(&distribution, &[(wound, 11)]);
+    assert!(distribution.miss_chance().abs() < 0.0001);
+    assert!((distribution.expected_damage() - 1.0).abs() < 0.0001);
+}
+
+#[test]
+fn attack_distribution_armor_and_break() {
+    let agent_attacker = component::Agent {
+        attack_distance: Distance(1),
+        attacks: Attacks(1),
+        attack_accuracy: Accuracy(5),
+        attack_strength: Strength(2),
+        attack_break: Strength(1),
+        ..agent_dull()
+    };
+    let prototypes = prototypes(&[
+        (
+            "swordsman",
+            [agent_attacker.into(), component_strength(1)].to_vec(),
+        ),
+        (
+            "heavy",
+            [
+                component_agent_dull(),
+                component_strength(2),
+                component::Armor { armor: Strength(1) }.into(),
+            ]
+            .to_vec(),
+        ),
+    ]);
+    let scenario = Scenario::default()
+        .object(P0, "swordsman", PosHex { q: 0, r: 0 })
+        .object(P1, "heavy", PosHex { q: 0, r: 1 });
+    let state = debug_state(prototypes, scenario);
+    let distribution = attack::distribution(&state, Id(0), Id(1));
+    let wound = |damage| Outcome::Wound {
+        damage: Strength(damage),
+        armor_break: Strength(1),
+    };
+    assert_outcomes(
+        &distribution,
+        &[(Outcome::Miss, 3), (wound(0), 2), (wound(1), 6)],
+    );
+    assert!(distribution.kill_chance().abs() < 0.0001);
+}
+
+#[test]
+fn attack_distribution_kill() {
+    let agent_attacker = component::Agent {
+        attack_distance: Distance(1),
+        attacks: Attacks(1),
+        attack_accuracy: Accuracy(4),
+        attack_strength: Strength(1),
+        ..agent_dull()
+    };
+    let prototypes = prototypes(&[
+        (
+            "swordsman",
+            [agent_attacker.into(), component_strength(1)].to_vec(),
+        ),
+        (
+            "imp",
+            [component_agent_dull(), component_strength(1)].to_vec(),
+        ),
+    ]);
+    let scenario = Scenario::default()
+        .object(P0, "swordsman", PosHex { q: 0, r: 0 })
+        .object(P1, "imp", PosHex { q: 0, r: 1 });
+    let state = debug_state(prototypes, scenario);
+    let distribution = attack::distribution(&state, Id(0), Id(1));
+    let wound_0 = Outcome::Wound {
+        damage: Strength(0),
+        armor_break: Strength(0),
+    };
+    assert_outcomes(
+        &distribution,
+        &[(Outcome::Miss, 5), (wound_0, 1), (Outcome::Kill, 5)],
+    );
+}
+
+#[test]
+fn preview_knockback_does_not_change_state() {
+    let prototypes = prototypes(&[
+        (
+            "knockbacker",
+            vec![
+                component_agent_always_hit(),
+                component_abilities(&[Ability::Knockback]),
+            ],
+        ),
+        (
+            "normal_target",
+            [
+                component_agent_dull(),
+                component_strength(1),
+                component_blocker(Weight::Normal),
+            ]
+            .to_vec(),
+        ),
+    ]);
+    let target_position_initial = PosHex { q: 0, r: 1 };
+    let target_position_updated = PosHex { q: 0, r: 2 };
+    let scenario = Scenario::default()
+        .object(P0, "knockbacker", PosHex { q: 0, r: 0 })
+        .object(P1, "normal_target", target_position_initial);
+    let mut state = debug_state(prototypes, scenario);
+    let command: Command = command::UseAbility {
+        id: Id(0),
+        pos: target_position_initial,
+        ability: Ability::Knockback,
+    }
+    .into();
+    let preview = preview(&state, &command).unwrap();
+    assert_eq!(state.parts().pos.get(Id(1)).0, target_position_initial);
+    assert_eq!(preview.outcomes().len(), 1);
+    let outcome = &preview.outcomes()[0];
+    assert_eq!(outcome.affected_positions, vec![target_position_updated]);
+    assert!((outcome.chance - 1.0).abs() < 0.0001);
+    assert_eq!(outcome.events, exec(&mut state, command));
+}
+
+#[test]
+fn preview_attack_exact_chances() {
+    let prototypes = prototypes(&[
+        (
+            "attacker",
+            [
+                component::Agent {
+                    attack_distance: Distance(1),
+                    attacks: Attacks(1),
+                    attack_accuracy: Accuracy(5),
+                    attack_strength: Strength(2),
+                    ..agent_dull()
+                }
+                .into(),
+                component_strength(2),
+            ]
+            .to_vec(),
+        ),
+        (
+            "target",
+            [component_agent_dull(), component_strength(2)].to_vec(),
+        ),
+    ]);
+    let scenario = Scenario::default()
+        .object(P0, "attacker", PosHex { q: 0, r: 0 })
+        .object(P1, "target", PosHex { q: 0, r: 1 });
+    let mut state = debug_state(prototypes, scenario);
+    state.set_deterministic_mode(false);
+    let command = command::Attack {
+        attacker_id: Id(0),
+        target_id: Id(1),
+    }
+    .into();
+    let preview = preview(&state, &command).unwrap();
+    // Kill, miss and two kinds of wounds.
+    let chances: Vec<f32> = preview.outcomes().iter().map(|o| o.chance).collect();
+    let expected = [6.0 / 11.0, 3.0 / 11.0, 1.0 / 11.0, 1.0 / 11.0];
+    assert_eq!(chances.len(), expected.len());
+    for (chance, expected) in chances.iter().zip(&expected) {
+        assert!((chance - expected).abs() < 0.0001);
+    }
+}
+
+#[test]
+fn preview_summon_positions() {
+    let imp = [component_agent_dull(), component_strength(1)].to_vec();
+    let prototypes = prototypes(&[
+        (
+            "summoner",
+            [
+                component_agent_one_attack(),
+                component_abilities(&[Ability::Summon]),
+                component::Summoner { count: 1 }.into(),
+            ]
+            .to_vec(),
+        ),
+        ("imp", imp.clone()),
+        ("toxic_imp", imp.clone()),
+        ("imp_bomber", imp),
+        ("boulder", [component_blocker(Weight::Normal)].to_vec()),
+    ]);
+    let origin = PosHex { q: 0, r: 0 };
+    let blocked_pos = PosHex { q: 1, r: 0 };
+    let scenario = Scenario::default()
+        .object(P0, "summoner", origin)
+        .object(P1, "imp", PosHex { q: 0, r: -4 })
+        .object_without_owner("boulder", blocked_pos);
+    let mut state = debug_state(prototypes, scenario);
+    state.set_deterministic_mode(false);
+    let command = command::UseAbility {
+        id: Id(0),
+        pos: origin,
+        ability: Ability::Summon,
+    }
+    .into();
+    let preview = preview(&state, &command).unwrap();
+    // Every free neighbor tile is equally likely.
+    let mut positions = Vec::new();
+    for outcome in preview.outcomes() {
+        assert!((outcome.chance - 1.0 / 5.0).abs() < 0.0001);
+        assert_eq!(outcome.affected_positions.len(), 1);
+        positions.push(outcome.affected_positions[0]);
+    }
+    let mut expected: Vec<PosHex> = map::dirs()
+        .map(|dir| map::Dir::get_neighbor_pos(origin, dir))
+        .filter(|&pos| pos != blocked_pos)
+        .collect();
+    let key = |pos: &PosHex| (pos.q, pos.r);
+    positions.sort_by_key(key);
+    expected.sort_by_key(key);
+    assert_eq!(positions, expected);
+}
+
+#[test]
+fn preview_bad_command() {
+    let prototypes = prototypes(&[("agent", [component_agent_dull()].to_vec())]);
+    let scenario = Scenario::default()
+        .object(P0, "agent", PosHex { q: 0, r: 0 })
+        .object(P1, "agent", PosHex { q: 0, r: 2 });
+    let state = debug_state(prototypes, scenario);
+    let command = command::Attack {
+        attacker_id: Id(0),
+        target_id: Id(1),
+    }
+    .into();
+    assert_eq!(
+        preview(&state, &command),
+        Err(check::Error::NotEnoughAttacks)
+    );
+}
+
+#[test]
+fn reaction_attackers_and_threats() {
+    let prototypes = prototypes(&[
+        ("dull", [component_agent_dull()].to_vec()),
+        ("guard", [component_agent_always_hit()].to_vec()),
+    ]);
+    let guard_pos = PosHex { q: 0, r: 2 };
+    let scenario = Scenario::default()
+        .object(P0, "dull", PosHex { q: 0, r: 0 })
+        .object(P1, "guard", guard_pos);
+    let state = debug_state(prototypes, scenario);
+    let near_guard = PosHex { q: 0, r: 1 };
+    assert_eq!(
+        threat::reaction_attackers(&state, Id(0), near_guard),
+        vec![Id(1)]
+    );
+    assert!(threat::reaction_attackers(&state, Id(0), PosHex { q: 0, r: 0 }).is_empty());
+    let threats = threat::threat_map(&state, P0);
+    for pos in threats.iter() {
+        let expected = map::distance_hex(pos, guard_pos) <= Distance(1);
+        assert_eq!(threats.tile(pos), expected, "{:?}", pos);
+    }
+}
+
+#[test]
+fn find_path_matches_flood_fill() {
+    let prototypes = prototypes(&[
+        ("mover", [component_agent_move_basic()].to_vec()),
+        (
+            "boulder",
+            [component_blocker(Weight::Heavy), component_strength(1)].to_vec(),
+        ),
+        ("dull", [component_agent_dull()].to_vec()),
+    ]);
+    let scenario = Scenario::default()
+        .object(P0, "mover", PosHex { q: 0, r: 0 })
+        .object_without_owner("boulder", PosHex { q: 1, r: 0 })
+        .object_without_owner("boulder", PosHex { q: 1, r: -1 })
+        .object_without_owner("boulder", PosHex { q: 0, r: 1 })
+        .object(P1, "dull", PosHex { q: -3, r: 0 });
+    let state = debug_state(prototypes, scenario);
+    let mut pathfinder = Pathfinder::new(state.map());
+    pathfinder.fill_map(&state, Id(0));
+    for pos in state.map().iter() {
+        let expected = pathfinder
+            .path(pos)
+            .map(|path| path.cost_for(&state, Id(0)));
+        let found = movement::find_path(&state, Id(0), pos, &ActualCost);
+        let cost = found.map(|path| path.cost_for(&state, Id(0)));
+        assert_eq!(cost, expected, "{:?}", pos);
+    }
+}
+
+#[test]
+fn find_path_weighted_avoids_enemies() {
+    let prototypes = prototypes(&[
+        ("mover", [component_agent_move_basic()].to_vec()),
+        ("dull", [component_agent_dull()].to_vec()),
+    ]);
+    let enemy_pos = PosHex { q: 0, r: -1 };
+    let destination = PosHex { q: 2, r: 0 };
+    let scenario = Scenario::default()
+        .object(P0, "mover", PosHex { q: -2, r: 0 })
+        .object(P1, "dull", enemy_pos);
+    let state = debug_state(prototypes, scenario);
+    let is_near_enemy = |path: &Path| {
+        let tiles = path.tiles();
+        tiles
+            .iter()
+            .any(|&pos| map::distance_hex(pos, enemy_pos) <= Distance(1))
+    };
+    let shortest = movement::find_path(&state, Id(0), destination, &ActualCost).unwrap();
+    assert_eq!(shortest.tiles().len(), 5);
+    assert!(is_near_enemy(&shortest));
+    let policy = WeightedCost {
+        hazard: MovePoints(0),
+        rocks: MovePoints(0),
+        enemy_adjacency: MovePoints(10),
+    };
+    let cautious = movement::find_path(&state, Id(0), destination, &policy).unwrap();
+    assert_eq!(cautious.to(), destination);
+    assert!(!is_near_enemy(&cautious));
+}
+
+fn component_agent_move_profile(movement: MovementProfile) -> Component {
+    component::Agent {
+        moves: Moves(1),
+        move_points: MovePoints(3),
+        movement,
+        ..agent_dull()
+    }
+    .into()
+}
+
+#[test]
+fn flyer_passes_allies() {
+    let prototypes = prototypes(&[
+        (
+            "flyer",
+            [component_agent_move_profile(MovementProfile::Flyer)].to_vec(),
+        ),
+        (
+            "walker",
+            [component_agent_move_profile(MovementProfile::Walker)].to_vec(),
+        ),
+        (
+            "ally",
+            [component_agent_dull(), component_blocker(Weight::Normal)].to_vec(),
+        ),
+        (
+            "enemy",
+            [
+                component_agent_always_hit_strength_1(),
+                component_strength(1),
+                component_blocker(Weight::Normal),
+            ]
+            .to_vec(),
+        ),
+    ]);
+    let scenario = Scenario::default()
+        .object(P0, "flyer", PosHex { q: 0, r: 0 })
+        .object(P0, "walker", PosHex { q: 0, r: 1 })
+        .object(P0, "ally", PosHex { q: 1, r: 0 })
+        .object(P0, "ally", PosHex { q: 2, r: 0 })
+        .object(P1, "enemy", PosHex { q: 2, r: -1 });
+    let mut state = debug_state(prototypes, scenario);
+    let through_allies = vec![
+        PosHex { q: 0, r: 0 },
+        PosHex { q: 1, r: 0 },
+        PosHex { q: 2, r: 0 },
+        PosHex { q: 3, r: 0 },
+    ];
+    let path = Path::new(through_allies[..3].to_vec());
+    let command = command::MoveTo { id: Id(0), path };
+    assert_eq!(
+        try_exec(&mut state, command),
+        Err(check::Error::TileIsBlocked)
+    );
+    let path = Path::new(vec![
+        PosHex { q: 0, r: 1 },
+        PosHex { q: 1, r: 0 },
+        PosHex { q: 2, r: 0 },
+    ]);
+    let command = command::MoveTo { id: Id(1), path };
+    assert_eq!(
+        try_exec(&mut state, command),
+        Err(check::Error::TileIsBlocked)
+    );
+    let path = Path::new(through_allies);
+    let events = exec(&mut state, command::MoveTo { id: Id(0), path });
+    // The enemy can't react while the flyer is above the allies.
+    for event in &events {
+        assert!(matches!(event.active_event, ActiveEvent::MoveTo(_)));
+    }
+    assert_eq!(state.parts().pos.get(Id(0)).0, PosHex { q: 3, r: 0 });
+}
+
+#[test]
+fn flyer_ignores_spike_trap() {
+    let prototypes = prototypes(&[
+        (
+            "flyer",
+            [
+                component_agent_move_profile(MovementProfile::Flyer),
+                component_strength(1),
+            ]
+            .to_vec(),
+        ),
+        (
+            "walker",
+            [
+                component_agent_move_profile(MovementProfile::Walker),
+                component_strength(1),
+            ]
+            .to_vec(),
+        ),
+        (
+            "spike_trap",
+            [component_passive_abilities(&[PassiveAbility::SpikeTrap])].to_vec(),
+        ),
+        ("dull", [component_agent_dull()].to_vec()),
+    ]);
+    let spikes_pos = PosHex { q: 1, r: 0 };
+    let scenario = Scenario::default()
+        .object(P0, "flyer", PosHex { q: 0, r: 0 })
+        .object(P0, "walker", PosHex { q: 1, r: -1 })
+        .object_without_owner("spike_trap", spikes_pos)
+        .object(P1, "dull", PosHex { q: -3, r: 0 });
+    let mut state = debug_state(prototypes, scenario);
+    let mut pathfinder = Pathfinder::new(state.map());
+    pathfinder.fill_map(&state, Id(0));
+    assert_eq!(pathfinder.map().tile(spikes_pos).cost(), MovePoints(1));
+    pathfinder.fill_map(&state, Id(1));
+    assert_eq!(pathfinder.map().tile(spikes_pos).cost(), MovePoints(4));
+    let path = Path::new(vec![PosHex { q: 0, r: 0 }, spikes_pos]);
+    exec(&mut state, command::MoveTo { id: Id(0), path });
+    assert_eq!(state.parts().pos.get(Id(0)).0, spikes_pos);
+    assert_eq!(state.parts().strength.get(Id(0)).strength, Strength(1));
+}
+
+#[test]
+fn zone_of_control_stop_on_enter() {
+    let prototypes = prototypes(&[
+        ("mover", [component_agent_move_basic()].to_vec()),
+        ("dull", [component_agent_dull()].to_vec()),
+    ]);
+    let scenario = Scenario {
+        zone_of_control: Some(ZoneOfControl::StopOnEnter),
+        ..Scenario::default()
+    }
+    .object(P0, "mover", PosHex { q: 0, r: 0 })
+    .object(P1, "dull", PosHex { q: 2, r: -1 });
+    let mut state = debug_state(prototypes, scenario);
+    let path = Path::new(vec![
+        PosHex { q: 0, r: 0 },
+        PosHex { q: 1, r: 0 },
+        PosHex { q: 2, r: 0 },
+        PosHex { q: 3, r: 0 },
+    ]);
+    let command = command::MoveTo {
+        id: Id(0),
+        path: path.clone(),
+    };
+    assert_eq!(
+        try_exec(&mut state, command),
+        Err(check::Error::StoppedByZoneOfControl)
+    );
+    let truncated_path = path.truncate(&state, Id(0)).unwrap();
+    assert_eq!(truncated_path.to(), PosHex { q: 1, r: 0 });
+    let mut pathfinder = Pathfinder::new(state.map());
+    pathfinder.fill_map(&state, Id(0));
+    let detour = pathfinder.path(PosHex { q: 3, r: 0 }).unwrap();
+    assert!(detour.cost_for(&state, Id(0)) > MovePoints(3));
+    exec(
+        &mut state,
+        command::MoveTo {
+            id: Id(0),
+            path: truncated_path,
+        },
+    );
+    assert_eq!(state.parts().pos.get(Id(0)).0, PosHex { q: 1, r: 0 });
+}
+
+#[test]
+fn zone_of_control_extra_cost_to_leave() {
+    let prototypes = prototypes(&[
+        ("mover", [component_agent_move_basic()].to_vec()),
+        ("dull", [component_agent_dull()].to_vec()),
+    ]);
+    let scenario = Scenario {
+        zone_of_control: Some(ZoneOfControl::ExtraCostToLeave(MovePoints(2))),
+        ..Scenario::default()
+    }
+    .object(P0, "mover", PosHex { q: 1, r: 0 })
+    .object(P1, "dull", PosHex { q: 2, r: -1 });
+    let mut state = debug_state(prototypes, scenario);
+    let short_path = Path::new(vec![PosHex { q: 1, r: 0 }, PosHex { q: 0, r: 0 }]);
+    assert_eq!(short_path.cost_for(&state, Id(0)), MovePoints(3));
+    let long_path = Path::new(vec![
+        PosHex { q: 1, r: 0 },
+        PosHex { q: 0, r: 0 },
+        PosHex { q: -1, r: 0 },
+    ]);
+    let command = command::MoveTo {
+        id: Id(0),
+        path: long_path,
+    };
+    assert_eq!(
+        try_exec(&mut state, command),
+        Err(check::Error::NotEnoughMovePoints)
+    );
+}
+
+#[test]
+fn random_deployment_on_rectangle_map() {
+    let prototypes = prototypes(&[
+        ("dull", [component_agent_dull()].to_vec()),
+        ("boulder", [component_blocker(Weight::Heavy)].to_vec()),
+    ]);
+    let group = |owner, typename: &str, line| ObjectsGroup {
+        owner,
+        typename: typename.into(),
+        line,
+        count: 3,
+    };
+    let hole = PosHex { q: 0, r: 0 };
+    let scenario = Scenario {
+        map_shape: Some(map::Shape::Rectangle {
+            width: 9,
+            height: 6,
+        }),
+        holes: vec![hole],
+        rocky_tiles_count: 5,
+        randomized_objects: vec![
+            group(Some(P0), "dull", Some(Line::Front)),
+            group(Some(P1), "dull", Some(Line::Back)),
+            group(None, "boulder", None),
+        ],
+        ..Scenario::default()
+    };
+    let state = State::new(prototypes, scenario, &mut |_, _, _| {});
+    let parts = state.parts();
+    let mut columns = [Vec::new(), Vec::new()];
+    for id in parts.pos.ids() {
+        let pos = parts.pos.get(id).0;
+        assert!(state.map().is_inboard(pos));
+        assert_ne!(pos, hole);
+        if let Some(belongs_to) = parts.belongs_to.get_opt(id) {
+            columns[belongs_to.0 .0 as usize].push(2 * pos.q + pos.r);
+        }
+    }
+    assert_eq!(columns[0].len(), 3);
+    assert_eq!(columns[1].len(), 3);
+    let max_column_p0 = columns[0].iter().max().unwrap();
+    let min_column_p1 = columns[1].iter().min().unwrap();
+    assert!(max_column_p0 < min_column_p1);
+}
+
+#[test]
+fn scenario_exact_tiles() {
+    let prototypes = prototypes(&[("dull", [component_agent_dull()].to_vec())]);
+    let rocks_pos = PosHex { q: 1, r: 1 };
+    let mut scenario = Scenario::default()
+        .object(P0, "dull", PosHex { q: 0, r: 0 })
+        .object(P1, "dull", PosHex { q: 2, r: 0 });
+    scenario.tiles.insert(rocks_pos, TileType::Rocks);
+    let state = debug_state(prototypes, scenario.clone());
+    assert_eq!(state.map().tile(rocks_pos), TileType::Rocks);
+    assert_eq!(state.map().tile(PosHex { q: 0, r: 1 }), TileType::Plain);
+    let outside_pos = PosHex { q: 9, r: 0 };
+    scenario.tiles.insert(outside_pos, TileType::Rocks);
+    assert!(matches!(
+        scenario.check(),
+        Err(scenario::Error::PosOutsideOfMap(pos)) if pos == outside_pos
+    ));
+}
+
+fn exec_and_record(state: &mut State, command: impl Into<Command>) -> Vec<event_log::Record> {
+    let mut records = Vec::new();
+    execute(state, &command.into(), &mut |state, event, phase| {
+        if phase == ApplyPhase::Pre {
+            records.extend(event_log::record(state, event));
+        }
+    })
+    .unwrap();
+    records
+}
+
+#[test]
+fn log_attack() {
+    let prototypes = prototypes(&[
+        (
+            "swordsman",
+            [
+                component_agent_always_hit_strength_1(),
+                component_strength(1),
+            ]
+            .to_vec(),
+        ),
+        (
+            "imp",
+            [component_agent_dull(), component_strength(2)].to_vec(),
+        ),
+    ]);
+    let scenario = Scenario::default()
+        .object(P0, "swordsman", PosHex { q: 0, r: 0 })
+        .object(P1, "imp", PosHex { q: 0, r: 1 });
+    let mut state = debug_state(prototypes, scenario);
+    let command = command::Attack {
+        attacker_id: Id(0),
+        target_id: Id(1),
+    };
+    let records = exec_and_record(&mut state, command);
+    let expected = event_log::Record {
+        kind: event_log::Kind::Attack {
+            attacker_id: Id(0),
+            target_id: Id(1),
+            mode: AttackMode::Active,
+            outcome: event_log::AttackOutcome::Wound(Strength(1)),
+        },
+        ids: vec![Id(0), Id(1)],
+        positions: vec![PosHex { q: 0, r: 0 }, PosHex { q: 0, r: 1 }],
+    };
+    assert_eq!(records, vec![expected]);
+}
+
+#[test]
+fn log_effect_tick() {
+    let prototypes = prototypes(&[("agent", [component_agent_dull()].to_vec())]);
+    let scenario = Scenario::default()
+        .object(P0, "agent", PosHex { q: 0, r: 0 })
+        .object(P1, "agent", PosHex { q: 0, r: 2 });
+    let state = debug_state(prototypes, scenario);
+    let event = Event {
+        active_event: event::EffectTick {
+            id: Id(1),
+            effect: effect::Lasting::Poison,
+        }
+        .into(),
+        actor_ids: vec![Id(1)],
+        instant_effects: Vec::new(),
+        timed_effects: Vec::new(),
+        scheduled_abilities: Vec::new(),
+    };
+    let expected = event_log::Record {
+        kind: event_log::Kind::EffectTick {
+            id: Id(1),
+            effect: effect::Lasting::Poison,
+        },
+        ids: vec![Id(1)],
+        positions: vec![PosHex { q: 0, r: 2 }],
+    };
+    assert_eq!(event_log::record(&state, &event), Some(expected));
+}
+
+#[test]
+fn log_explosion() {
+    let prototypes = prototypes(&[
+        ("agent", [component_agent_dull()].to_vec()),
+        ("bomb", Vec::new()),
+    ]);
+    let scenario = Scenario::default()
+        .object(P0, "agent", PosHex { q: 0, r: 0 })
+        .object(P1, "agent", PosHex { q: 0, r: 2 })
+        .object_without_owner("bomb", PosHex { q: 0, r: 1 });
+    let state = debug_state(prototypes, scenario);
+    let wound = effect::Wound {
+        damage: Strength(1),
+        armor_break: Strength(0),
+        attacker_pos: Some(PosHex { q: 0, r: 1 }),
+    };
+    let event = Event {
+        active_event: event::UseAbility {
+            id: Id(2),
+            pos: PosHex { q: 0, r: 1 },
+            ability: Ability::ExplodeDamage,
+        }
+        .into(),
+        actor_ids: vec![Id(2)],
+        instant_effects: vec![
+            (Id(0), vec![wound.clone().into()]),
+            (Id(1), vec![wound.into()]),
+            (Id(2), vec![Effect::Vanish]),
+        ],
+        timed_effects: Vec::new(),
+        scheduled_abilities: Vec::new(),
+    };
+    let expected = event_log::Record {
+        kind: event_log::Kind::Explode { id: Id(2) },
+        ids: vec![Id(2), Id(0), Id(1)],
+        positions: vec![
+            PosHex { q: 0, r: 1 },
+            PosHex { q: 0, r: 0 },
+            PosHex { q: 0, r: 2 },
+        ],
+    };
+    assert_eq!(event_log::record(&state, &event), Some(expected));
+}
@@ -0,0 +1,68 @@
+use crate::core::{
+    battle::{movement::Pathfinder, state, Attacks, Id, Jokers, Moves, PlayerId, PosHex, State},
+    map::{self, HexMap},
+};
+
+fn can_attack_now(state: &State, id: Id) -> bool {
+    let agent = state.parts().agent.get(id);
+    agent.attacks > Attacks(0) || agent.jokers > Jokers(0)
+}
+
+/// Enemy agents that would make a reaction attack if the agent stood at this tile.
+pub fn reaction_attackers(state: &State, id: Id, pos: PosHex) -> Vec<Id> {
+    let parts = state.parts();
+    let player_id = parts.belongs_to.get(id).0;
+    let mut attacker_ids = Vec::new();
+    for enemy_id in state::enemy_agent_ids(state, player_id) {
+        if !can_attack_now(state, enemy_id) {
+            continue;
+        }
+        let enemy_pos = parts.pos.get(enemy_id).0;
+        let attack_distance = parts.agent.get(enemy_id).attack_distance;
+        if map::distance_hex(enemy_pos, pos) <= attack_distance {
+            attacker_ids.push(enemy_id);
+        }
+    }
+    attacker_ids
+}
+
+/// Tiles reachable by the agent during its next turn.
+fn reachable_positions(state: &State, pathfinder: &mut Pathfinder, id: Id) -> Vec<PosHex> {
+    let agent = state.parts().agent.get(id);
+    let pos = state.parts().pos.get(id).0;
+    if agent.base_moves == Moves(0) && agent.base_jokers == Jokers(0) {
+        return vec![pos];
+    }
+    pathfinder.fill_map(state, id);
+    let map = pathfinder.map();
+    map.iter()
+        .filter(|&pos| map.tile(pos).can_stop() && map.tile(pos).cost() <= agent.move_points)
+        .collect()
+}
+
+/// Tiles that enemies of the player can attack: right now with reaction attacks
+/// or during their next turn after moving.
+pub fn threat_map(state: &State, player_id: PlayerId) -> HexMap<bool> {
+    let mut threats = HexMap::new_like(state.map());
+    let mut pathfinder = Pathfinder::new(state.map());
+    let parts = state.parts();
+    for enemy_id in state::enemy_agent_ids(state, player_id) {
+        let agent = parts.agent.get(enemy_id);
+        let mut attack_origins = Vec::new();
+        if can_attack_now(state, enemy_id) {
+            attack_origins.push(parts.pos.get(enemy_id).0);
+        }
+        if agent.base_attacks > Attacks(0) || agent.base_jokers > Jokers(0) {
+            attack_origins.extend(reachable_positions(state, &mut pathfinder, enemy_id));
+        }
+        for pos in threats.iter() {
+            let is_threatened = attack_origins
+                .iter()
+                .any(|&origin| map::distance_hex(origin, pos) <= agent.attack_distance);
+            if is_threatened {
+                threats.set_tile(pos, true);
+            }
+        }
+    }
+    threats
+}
@@ -61,6 +61,16 @@ pub struct Plan {
     nodes: Vec<CampaignNode>,
 }
 
+impl Plan {
+    pub fn initial_agents(&self) -> &[ObjType] {
+        &self.initial_agents
+    }
+
+    pub fn nodes(&self) -> &[CampaignNode] {
+        &self.nodes
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct AgentInfo {
     pub cost: Renown,
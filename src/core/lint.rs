@@ -0,0 +1,271 @@
+//! Checks of the game data that need more context than a single file.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+use crate::core::{
+    battle::{
+        component::{ObjType, Prototypes},
+        scenario::{self, ObjectsGroup, Scenario},
+        PlayerId,
+    },
+    campaign::{AgentInfo, Award, Plan},
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Problem {
+    Scenario(scenario::Error),
+    NoPrototype(ObjType),
+    NoSprite(ObjType),
+    UpgradeWithoutInfo { from: ObjType, to: ObjType },
+    RecruitWithoutCost(ObjType),
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Problem::Scenario(error) => write!(f, "bad scenario: {:?}", error),
+            Problem::NoPrototype(typename) => write!(f, "no prototype for '{}'", typename.0),
+            Problem::NoSprite(typename) => write!(f, "no sprite for '{}'", typename.0),
+            Problem::UpgradeWithoutInfo { from, to } => {
+                write!(f, "upgrade '{}' -> '{}' has no campaign info", from.0, to.0)
+            }
+            Problem::RecruitWithoutCost(typename) => {
+                write!(f, "recruit '{}' has no cost", typename.0)
+            }
+        }
+    }
+}
+
+/// Everything the scenarios and campaigns refer to.
+#[derive(Clone, Debug)]
+pub struct Data {
+    pub prototypes: Prototypes,
+    pub sprites: HashSet<ObjType>,
+    pub agent_info: HashMap<ObjType, AgentInfo>,
+}
+
+impl Data {
+    fn check_typename(&self, typename: &ObjType, problems: &mut Vec<Problem>) {
+        let problem = if !self.prototypes.0.contains_key(typename) {
+            Problem::NoPrototype(typename.clone())
+        } else if !self.sprites.contains(typename) {
+            Problem::NoSprite(typename.clone())
+        } else {
+            return;
+        };
+        if !problems.contains(&problem) {
+            problems.push(problem);
+        }
+    }
+
+    pub fn lint_agent_info(&self) -> Vec<Problem> {
+        let mut problems = Vec::new();
+        let mut typenames: Vec<&ObjType> = self.agent_info.keys().collect();
+        typenames.sort_by(|a, b| a.0.cmp(&b.0));
+        for from in typenames {
+            self.check_typename(from, &mut problems);
+            for to in &self.agent_info[from].upgrades {
+                if !self.agent_info.contains_key(to) {
+                    problems.push(Problem::UpgradeWithoutInfo {
+                        from: from.clone(),
+                        to: to.clone(),
+                    });
+                }
+            }
+        }
+        problems
+    }
+
+    pub fn lint_scenario(&self, scenario: &Scenario) -> Vec<Problem> {
+        let mut problems: Vec<Problem> = scenario
+            .errors()
+            .into_iter()
+            .map(Problem::Scenario)
+            .collect();
+        for group in &scenario.randomized_objects {
+            self.check_typename(&group.typename, &mut problems);
+        }
+        for object in &scenario.objects {
+            self.check_typename(&object.typename, &mut problems);
+        }
+        problems
+    }
+
+    pub fn lint_award(&self, award: &Award) -> Vec<Problem> {
+        let mut problems = Vec::new();
+        for typename in &award.recruits {
+            self.check_typename(typename, &mut problems);
+            if !self.agent_info.contains_key(typename) {
+                problems.push(Problem::RecruitWithoutCost(typename.clone()));
+            }
+        }
+        problems
+    }
+
+    /// Problems of the campaign plan, each with a short description of its location.
+    pub fn lint_campaign(&self, plan: &Plan) -> Vec<(String, Problem)> {
+        let mut problems = Vec::new();
+        let mut initial_problems = Vec::new();
+        for typename in plan.initial_agents() {
+            self.check_typename(typename, &mut initial_problems);
+        }
+        for problem in &initial_problems {
+            problems.push(("initial agents".into(), problem.clone()));
+        }
+        for (i, node) in plan.nodes().iter().enumerate() {
+            let location = format!("node {}", i);
+            // The player's agents are added by the campaign itself,
+            // so check the scenario with at least the initial ones.
+            // The campaign doesn't place them by lines, so neither does the lint.
+            let mut scenario = node.scenario.clone();
+            for typename in plan.initial_agents() {
+                scenario.randomized_objects.push(ObjectsGroup {
+                    owner: Some(PlayerId(0)),
+                    typename: typename.clone(),
+                    line: None,
+                    count: 1,
+                });
+            }
+            let scenario_problems = self.lint_scenario(&scenario);
+            let award_problems = self.lint_award(&node.award);
+            for problem in scenario_problems.into_iter().chain(award_problems) {
+                // The initial agents' problems are already reported once.
+                if !initial_problems.contains(&problem) {
+                    problems.push((location.clone(), problem));
+                }
+            }
+        }
+        problems
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::{
+        battle::{
+            component::{ObjType, Prototypes},
+            scenario::Error,
+        },
+        campaign::{AgentInfo, Plan, Renown},
+    };
+
+    use super::{Data, Problem};
+
+    /// Every type has a prototype, a sprite and a campaign info.
+    fn data(typenames: &[&str]) -> Data {
+        let typenames: Vec<ObjType> = typenames.iter().map(|&name| name.into()).collect();
+        let info = AgentInfo {
+            cost: Renown(10),
+            upgrades: Vec::new(),
+        };
+        Data {
+            prototypes: Prototypes(typenames.iter().map(|t| (t.clone(), vec![])).collect()),
+            sprites: typenames.iter().cloned().collect(),
+            agent_info: typenames.into_iter().map(|t| (t, info.clone())).collect(),
+        }
+    }
+
+    fn plan(s: &str) -> Plan {
+        ron::de::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn clean_campaign() {
+        let data = data(&["swordsman", "imp", "alchemist"]);
+        // More initial agents than any single line of this small map can hold.
+        let plan = plan(
+            r#"(
+                initial_agents: [
+                    "swordsman", "swordsman", "swordsman", "swordsman", "swordsman",
+                    "swordsman", "swordsman", "swordsman", "swordsman", "swordsman",
+                ],
+                nodes: [(
+                    scenario: (
+                        map_radius: (3),
+                        randomized_objects: [
+                            (owner: Some((1)), typename: "imp", line: Some(Front), count: 2),
+                        ],
+                    ),
+                    award: (recruits: ["alchemist"], renown: 10),
+                )],
+            )"#,
+        );
+        assert_eq!(data.lint_agent_info(), vec![]);
+        assert_eq!(data.lint_campaign(&plan), vec![]);
+    }
+
+    #[test]
+    fn campaign_with_several_problems() {
+        let mut data = data(&["swordsman", "imp", "alchemist"]);
+        data.sprites.remove(&"imp".into());
+        data.agent_info.remove(&"alchemist".into());
+        let plan = plan(
+            r#"(
+                initial_agents: ["swordsman"],
+                nodes: [
+                    (
+                        scenario: (
+                            randomized_objects: [
+                                (owner: Some((1)), typename: "imp", line: Some(Front), count: 1),
+                            ],
+                        ),
+                        award: (recruits: ["alchemist"], renown: 10),
+                    ),
+                    (
+                        scenario: (players_count: 3),
+                        award: (renown: 10),
+                    ),
+                ],
+            )"#,
+        );
+        let expected = vec![
+            ("node 0".into(), Problem::NoSprite("imp".into())),
+            (
+                "node 0".into(),
+                Problem::RecruitWithoutCost("alchemist".into()),
+            ),
+            (
+                "node 1".into(),
+                Problem::Scenario(Error::UnsupportedPlayersCount(3)),
+            ),
+            ("node 1".into(), Problem::Scenario(Error::NoEnemyAgents)),
+        ];
+        assert_eq!(data.lint_campaign(&plan), expected);
+    }
+
+    #[test]
+    fn campaign_with_missing_prototype() {
+        let mut data = data(&["swordsman", "imp"]);
+        let ghost: ObjType = "ghost".into();
+        data.sprites.insert(ghost.clone());
+        let plan = plan(
+            r#"(
+                initial_agents: ["swordsman", "ghost"],
+                nodes: [
+                    (
+                        scenario: (
+                            randomized_objects: [
+                                (owner: Some((1)), typename: "imp", line: Some(Front), count: 1),
+                            ],
+                        ),
+                        award: (renown: 10),
+                    ),
+                    (
+                        scenario: (
+                            randomized_objects: [
+                                (owner: Some((1)), typename: "ghost", line: Some(Front), count: 1),
+                            ],
+                        ),
+                        award: (renown: 10),
+                    ),
+                ],
+            )"#,
+        );
+        let problem = Problem::NoPrototype(ghost);
+        let expected = vec![("initial agents".into(), problem)];
+        assert_eq!(data.lint_campaign(&plan), expected);
+    }
+}
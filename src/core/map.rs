@@ -1,4 +1,4 @@
-use std::{fmt::Debug, iter::repeat};
+use std::{fmt::Debug, sync::Arc};
 
 use num::{Num, Signed};
 use serde::{Deserialize, Serialize};
@@ -76,57 +76,74 @@ pub fn distance_hex(a: PosHex, b: PosHex) -> Distance {
     distance_cube(hex_to_cube(a), hex_to_cube(b))
 }
 
-fn is_inboard(radius: Distance, pos: PosHex) -> bool {
-    let origin = PosHex { q: 0, r: 0 };
-    distance_hex(origin, pos) <= radius
+/// The outline of a map. All shapes are centered on the origin.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Shape {
+    Hexagon {
+        radius: Distance,
+    },
+
+    /// Every other row is shifted by half a tile.
+    Rectangle {
+        width: i32,
+        height: i32,
+    },
+
+    /// Rows and columns follow the axial axes.
+    Parallelogram {
+        width: i32,
+        height: i32,
+    },
 }
 
-#[derive(Clone, Debug)]
-pub struct HexIter {
-    cursor: PosHex,
-    radius: Distance,
+/// Is `n` within a range of `size` numbers centered on zero?
+fn is_in_centered_range(size: i32, n: i32) -> bool {
+    let min = -(size / 2);
+    let max = min + size - 1;
+    n >= min && n <= max
 }
 
-impl HexIter {
-    fn new(radius: Distance) -> Self {
-        let mut iter = Self {
-            cursor: PosHex {
-                q: -radius.0,
-                r: -radius.0,
-            },
-            radius,
-        };
-        iter.inc_cursor_with_hex_bounds();
-        iter
-    }
-
-    fn inc_cursor(&mut self) {
-        self.cursor.q += 1;
-        if self.cursor.q > self.radius.0 {
-            self.cursor.q = -self.radius.0;
-            self.cursor.r += 1;
+impl Shape {
+    pub fn contains(self, pos: PosHex) -> bool {
+        match self {
+            Shape::Hexagon { radius } => {
+                let origin = PosHex { q: 0, r: 0 };
+                distance_hex(origin, pos) <= radius
+            }
+            Shape::Rectangle { width, height } => {
+                let column = pos.q + pos.r.div_euclid(2);
+                is_in_centered_range(width, column) && is_in_centered_range(height, pos.r)
+            }
+            Shape::Parallelogram { width, height } => {
+                is_in_centered_range(width, pos.q) && is_in_centered_range(height, pos.r)
+            }
         }
     }
 
-    fn inc_cursor_with_hex_bounds(&mut self) {
-        self.inc_cursor();
-        while !is_inboard(self.radius, self.cursor) && self.cursor.r < self.radius.0 + 1 {
-            self.inc_cursor();
+    /// A distance from the origin that is guaranteed to cover the whole shape.
+    fn extent(self) -> i32 {
+        match self {
+            Shape::Hexagon { radius } => radius.0,
+            Shape::Rectangle { width, height } | Shape::Parallelogram { width, height } => {
+                width + height
+            }
         }
     }
 }
 
+#[derive(Clone, Debug)]
+pub struct HexIter {
+    positions: Arc<Vec<PosHex>>,
+    index: usize,
+}
+
 impl Iterator for HexIter {
     type Item = PosHex;
 
     fn next(&mut self) -> Option<PosHex> {
-        if self.cursor.r > self.radius.0 {
-            None
-        } else {
-            let current = self.cursor;
-            self.inc_cursor_with_hex_bounds();
-            Some(current)
-        }
+        let pos = self.positions.get(self.index).cloned();
+        self.index += 1;
+        pos
     }
 }
 
@@ -149,15 +166,15 @@ impl Iterator for HexIter {
 /// ```
 ///
 #[allow(dead_code)]
-pub fn dump_map<F: Fn(PosHex) -> char>(radius: Distance, f: F) {
-    let s = radius.0;
-    for r in -s..=s {
-        for _ in -s..r {
+pub fn dump_map<T: Copy + Debug, F: Fn(PosHex) -> char>(map: &HexMap<T>, f: F) {
+    let min = map.min;
+    for r in min.r..min.r + map.height {
+        for _ in min.r..r {
             print!(" ");
         }
-        for q in -s..=s {
+        for q in min.q..min.q + map.width {
             let pos = PosHex { q, r };
-            if is_inboard(radius, pos) {
+            if map.is_inboard(pos) {
                 print!("{} ", f(pos));
             } else {
                 print!("  ");
@@ -168,52 +185,127 @@ pub fn dump_map<F: Fn(PosHex) -> char>(radius: Distance, f: F) {
     println!();
 }
 
-pub fn radius_to_diameter(radius: Distance) -> Distance {
-    Distance(radius.0 * 2 + 1)
-}
-
-///
+/// ```text
 ///     [-1, 0]  [0, -1]
 /// [-1, 1]  [0, 0]  [1, -1]
 ///     [ 0, 1]  [ 1, 0]
-///
+/// ```
 #[derive(Debug, Clone)]
 pub struct HexMap<T: Copy + Debug> {
     tiles: Vec<T>,
-    size: Distance,
-    radius: Distance,
+
+    /// The corner of the bounding box with the smallest coordinates.
+    min: PosHex,
+
+    /// Size of the bounding box along the `q` axis.
+    width: i32,
+
+    /// Size of the bounding box along the `r` axis.
+    height: i32,
+
+    /// Tells if a tile of the bounding box is a part of the map.
+    mask: Arc<Vec<bool>>,
+
+    /// All the inboard positions in the iteration order.
+    positions: Arc<Vec<PosHex>>,
 }
 
 impl<T: Copy + Default + Debug> HexMap<T> {
-    pub fn new(radius: Distance) -> Self {
-        let size = Distance(radius.0 * 2 + 1);
-        let tiles_count = (size.0 * size.0) as usize;
-        let tiles = repeat(Default::default()).take(tiles_count).collect();
-        Self {
-            tiles,
-            size,
-            radius,
+    /// Creates a map of the given shape without the tiles listed in `holes`.
+    ///
+    /// Note: panics if no tiles are left, see `try_from_shape`.
+    pub fn from_shape(shape: Shape, holes: &[PosHex]) -> Self {
+        Self::try_from_shape(shape, holes).expect("Empty map")
+    }
+
+    /// Same as `from_shape`, but returns `None` if no tiles are left.
+    pub fn try_from_shape(shape: Shape, holes: &[PosHex]) -> Option<Self> {
+        let extent = shape.extent();
+        let mut positions = Vec::new();
+        for r in -extent..=extent {
+            for q in -extent..=extent {
+                let pos = PosHex { q, r };
+                if shape.contains(pos) && !holes.contains(&pos) {
+                    positions.push(pos);
+                }
+            }
+        }
+        if positions.is_empty() {
+            return None;
         }
+        let min = PosHex {
+            q: positions.iter().map(|pos| pos.q).min().unwrap(),
+            r: positions.iter().map(|pos| pos.r).min().unwrap(),
+        };
+        let max_q = positions.iter().map(|pos| pos.q).max().unwrap();
+        let max_r = positions.iter().map(|pos| pos.r).max().unwrap();
+        let width = max_q - min.q + 1;
+        let height = max_r - min.r + 1;
+        let tiles_count = (width * height) as usize;
+        let mut map = Self {
+            tiles: vec![Default::default(); tiles_count],
+            min,
+            width,
+            height,
+            mask: Arc::new(vec![false; tiles_count]),
+            positions: Arc::new(Vec::new()),
+        };
+        let mut mask = vec![false; tiles_count];
+        for &pos in &positions {
+            mask[map.hex_to_index(pos)] = true;
+        }
+        map.mask = Arc::new(mask);
+        map.positions = Arc::new(positions);
+        Some(map)
     }
 
-    pub fn radius(&self) -> Distance {
-        self.radius
+    /// Creates a map with the same set of tiles as the given one.
+    pub fn new_like<U: Copy + Debug>(other: &HexMap<U>) -> Self {
+        let tiles_count = other.tiles.len();
+        Self {
+            tiles: vec![Default::default(); tiles_count],
+            min: other.min,
+            width: other.width,
+            height: other.height,
+            mask: other.mask.clone(),
+            positions: other.positions.clone(),
+        }
     }
+}
 
+impl<T: Copy + Debug> HexMap<T> {
+    /// The number of rows.
     pub fn height(&self) -> Distance {
-        radius_to_diameter(self.radius())
+        Distance(self.height)
+    }
+
+    /// The corners of the bounding box, both inclusive.
+    pub fn bounds(&self) -> (PosHex, PosHex) {
+        let max = PosHex {
+            q: self.min.q + self.width - 1,
+            r: self.min.r + self.height - 1,
+        };
+        (self.min, max)
     }
 
     pub fn iter(&self) -> HexIter {
-        HexIter::new(self.radius)
+        HexIter {
+            positions: self.positions.clone(),
+            index: 0,
+        }
     }
 
     pub fn is_inboard(&self, pos: PosHex) -> bool {
-        is_inboard(self.radius, pos)
+        let q = pos.q - self.min.q;
+        let r = pos.r - self.min.r;
+        if q < 0 || r < 0 || q >= self.width || r >= self.height {
+            return false;
+        }
+        self.mask[self.hex_to_index(pos)]
     }
 
     fn hex_to_index(&self, hex: PosHex) -> usize {
-        let i = (hex.r + self.radius.0) + (hex.q + self.radius.0) * self.size.0;
+        let i = (hex.q - self.min.q) + (hex.r - self.min.r) * self.width;
         i as usize
     }
 
@@ -313,12 +405,65 @@ impl Iterator for DirIter {
 
 #[cfg(test)]
 mod tests {
-    use crate::core::map::{Distance, HexMap};
+    use crate::core::map::{Distance, HexMap, PosHex, Shape};
 
     #[test]
     fn test_map_height() {
-        let map: HexMap<u8> = HexMap::new(Distance(3));
+        let map: HexMap<u8> = HexMap::from_shape(
+            Shape::Hexagon {
+                radius: Distance(3),
+            },
+            &[],
+        );
         let height = map.height();
         assert_eq!(height, Distance(7));
     }
+
+    #[test]
+    fn test_hexagon_tiles_count() {
+        let map: HexMap<u8> = HexMap::from_shape(
+            Shape::Hexagon {
+                radius: Distance(3),
+            },
+            &[],
+        );
+        assert_eq!(map.iter().count(), 37);
+        assert!(map.is_inboard(PosHex { q: 3, r: -3 }));
+        assert!(!map.is_inboard(PosHex { q: 3, r: 3 }));
+    }
+
+    #[test]
+    fn test_rectangle() {
+        let shape = Shape::Rectangle {
+            width: 6,
+            height: 4,
+        };
+        let map: HexMap<u8> = HexMap::from_shape(shape, &[]);
+        assert_eq!(map.iter().count(), 24);
+        assert_eq!(map.height(), Distance(4));
+        for r in -2..2 {
+            let row_len = map.iter().filter(|pos| pos.r == r).count();
+            assert_eq!(row_len, 6);
+        }
+    }
+
+    #[test]
+    fn test_parallelogram_with_holes() {
+        let shape = Shape::Parallelogram {
+            width: 3,
+            height: 5,
+        };
+        let hole = PosHex { q: 0, r: 0 };
+        let mut map: HexMap<u8> = HexMap::from_shape(shape, &[hole]);
+        assert_eq!(map.iter().count(), 14);
+        assert!(!map.is_inboard(hole));
+        assert!(map.is_inboard(PosHex { q: 1, r: 2 }));
+        assert!(!map.is_inboard(PosHex { q: 2, r: 0 }));
+        for pos in map.iter() {
+            map.set_tile(pos, 1);
+        }
+        let other: HexMap<bool> = HexMap::new_like(&map);
+        assert_eq!(other.iter().count(), 14);
+        assert!(!other.is_inboard(hole));
+    }
 }
@@ -1,9 +1,12 @@
 use std::{error, fmt, io, path::PathBuf};
 
+use crate::core::battle::component;
+
 #[derive(Debug, derive_more::From)]
 pub enum ZError {
     Ui(ui::Error),
     Scene(zscene::Error),
+    Prototypes(component::Error),
     RonDeserialize {
         error: ron::de::Error,
         path: PathBuf,
@@ -24,6 +27,7 @@ impl fmt::Display for ZError {
         match self {
             ZError::Ui(ref e) => write!(f, "ZGUI Error: {}", e),
             ZError::Scene(ref e) => write!(f, "ZScene Error: {}", e),
+            ZError::Prototypes(ref e) => write!(f, "Prototypes Error: {}", e),
             ZError::RonDeserialize { error, path } => {
                 let s = path.to_str().unwrap_or("<no path>");
                 write!(f, "Can't deserialize '{}': {}", s, error)
@@ -40,6 +44,7 @@ impl error::Error for ZError {
         match self {
             ZError::Ui(ref e) => Some(e),
             ZError::Scene(ref e) => Some(e),
+            ZError::Prototypes(ref e) => Some(e),
             ZError::RonDeserialize { error, .. } => Some(error),
             ZError::IO(ref e) => Some(e),
             ZError::MqFile(ref e) => Some(e),
@@ -1,7 +1,9 @@
 use mq::math::Vec2;
 
+use std::fmt::Debug;
+
 use crate::core::{
-    map::{hex_round, PosHex},
+    map::{hex_round, HexMap, PosHex},
     utils::roll_dice,
 };
 
@@ -9,6 +11,9 @@ const SQRT_OF_3: f32 = 1.732_05;
 
 pub const FLATNESS_COEFFICIENT: f32 = 0.8125; // should fit the tile sprite's geometry
 
+/// The widest map can take this much of the screen in world units.
+const MAP_WIDTH_MAX: f32 = 2.4;
+
 /// <http://www.redblobgames.com/grids/hexagons/#hex-to-pixel>
 pub fn hex_to_point(size: f32, hex: PosHex) -> Vec2 {
     let x = size * SQRT_OF_3 * (hex.q as f32 + hex.r as f32 / 2.0);
@@ -24,6 +29,17 @@ pub fn point_to_hex(size: f32, mut point: Vec2) -> PosHex {
     hex_round(PosHex { q, r })
 }
 
+/// Picks a tile size that fits the whole map into the screen.
+pub fn tile_size<T: Copy + Debug>(map: &HexMap<T>) -> f32 {
+    let size_by_height = 1.0 / (map.height().0 as f32 * 0.75);
+    let xs = map.iter().map(|pos| hex_to_point(1.0, pos).x);
+    let min_x = xs.clone().fold(f32::MAX, f32::min);
+    let max_x = xs.fold(f32::MIN, f32::max);
+    let tile_width = SQRT_OF_3;
+    let size_by_width = MAP_WIDTH_MAX / (max_x - min_x + tile_width);
+    size_by_height.min(size_by_width)
+}
+
 pub fn rand_tile_offset(size: f32, radius: f32) -> Vec2 {
     assert!(radius >= 0.0);
     let r = size * radius;
@@ -0,0 +1,161 @@
+//! Keyboard and mouse bindings of the game actions, `bindings.ron`.
+
+use std::collections::HashMap;
+
+use log::warn;
+use mq::input::{self, KeyCode, MouseButton};
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum Action {
+    EndTurn,
+
+    /// Selects the next agent that still has some actions left.
+    NextAgent,
+
+    /// Picks the selected agent's ability by its number, starting from 1.
+    Ability(usize),
+
+    /// Cancels the current selection or closes a popup.
+    Cancel,
+
+    AgentInfo,
+
+    /// Speeds the animations up while its key or button is held.
+    FastForward,
+
+    /// Turns the instant animations mode on or off.
+    ToggleSkipAnimations,
+}
+
+/// Keys and mouse buttons are named as macroquad's `KeyCode` and `MouseButton` variants.
+#[derive(Debug, Clone, Deserialize)]
+pub struct BindingsDef {
+    pub keys: HashMap<String, Action>,
+    pub mouse_buttons: HashMap<String, Action>,
+}
+
+/// Keys that can be bound to the actions.
+const KEY_CODES: &[KeyCode] = &[
+    KeyCode::Space,
+    KeyCode::Key0,
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+    KeyCode::Key6,
+    KeyCode::Key7,
+    KeyCode::Key8,
+    KeyCode::Key9,
+    KeyCode::A,
+    KeyCode::B,
+    KeyCode::C,
+    KeyCode::D,
+    KeyCode::E,
+    KeyCode::F,
+    KeyCode::G,
+    KeyCode::H,
+    KeyCode::I,
+    KeyCode::J,
+    KeyCode::K,
+    KeyCode::L,
+    KeyCode::M,
+    KeyCode::N,
+    KeyCode::O,
+    KeyCode::P,
+    KeyCode::Q,
+    KeyCode::R,
+    KeyCode::S,
+    KeyCode::T,
+    KeyCode::U,
+    KeyCode::V,
+    KeyCode::W,
+    KeyCode::X,
+    KeyCode::Y,
+    KeyCode::Z,
+    KeyCode::Escape,
+    KeyCode::Enter,
+    KeyCode::Tab,
+    KeyCode::Backspace,
+    KeyCode::Delete,
+    KeyCode::Right,
+    KeyCode::Left,
+    KeyCode::Down,
+    KeyCode::Up,
+    KeyCode::F1,
+    KeyCode::F2,
+    KeyCode::F3,
+    KeyCode::F4,
+    KeyCode::F5,
+    KeyCode::F6,
+    KeyCode::F7,
+    KeyCode::F8,
+    KeyCode::F9,
+    KeyCode::F10,
+    KeyCode::F11,
+    KeyCode::F12,
+];
+
+const MOUSE_BUTTONS: &[MouseButton] = &[MouseButton::Left, MouseButton::Right, MouseButton::Middle];
+
+fn find_by_name<T: Copy + std::fmt::Debug>(values: &[T], name: &str) -> Option<T> {
+    values
+        .iter()
+        .copied()
+        .find(|value| format!("{:?}", value) == name)
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Bindings {
+    keys: Vec<(KeyCode, Action)>,
+    mouse_buttons: Vec<(MouseButton, Action)>,
+}
+
+impl Bindings {
+    /// Unknown key or button names are skipped with a warning.
+    pub fn new(def: BindingsDef) -> Self {
+        let mut bindings = Self::default();
+        for (name, action) in def.keys {
+            match find_by_name(KEY_CODES, &name) {
+                Some(key) => bindings.keys.push((key, action)),
+                None => warn!("bindings: Unknown key '{}'", name),
+            }
+        }
+        for (name, action) in def.mouse_buttons {
+            match find_by_name(MOUSE_BUTTONS, &name) {
+                Some(button) => bindings.mouse_buttons.push((button, action)),
+                None => warn!("bindings: Unknown mouse button '{}'", name),
+            }
+        }
+        bindings
+    }
+
+    /// Is any of the action's keys or buttons held down right now?
+    pub fn is_held(&self, action: Action) -> bool {
+        let is_key_down = self
+            .keys
+            .iter()
+            .any(|&(key, a)| a == action && input::is_key_down(key));
+        let is_button_down = self
+            .mouse_buttons
+            .iter()
+            .any(|&(button, a)| a == action && input::is_mouse_button_down(button));
+        is_key_down || is_button_down
+    }
+
+    /// Actions whose keys or buttons were pressed during the current frame.
+    pub fn pressed_actions(&self) -> Vec<Action> {
+        let keys = self
+            .keys
+            .iter()
+            .filter(|(key, _)| input::is_key_pressed(*key))
+            .map(|&(_, action)| action);
+        let mouse_buttons = self
+            .mouse_buttons
+            .iter()
+            .filter(|(button, _)| input::is_mouse_button_pressed(*button))
+            .map(|&(_, action)| action);
+        keys.chain(mouse_buttons).collect()
+    }
+}
@@ -0,0 +1,138 @@
+//! Translated user-facing text.
+//!
+//! Every language is a table of strings keyed by stable ids (`lang/<language>.ron`).
+//! Strings that are missing in the current language are taken from the fallback one.
+
+use std::{collections::HashMap, fmt};
+
+use heck::{SnakeCase, TitleCase};
+use log::warn;
+use serde::Deserialize;
+
+use crate::{
+    assets,
+    core::battle::{
+        ability::{Ability, PassiveAbility},
+        component::ObjType,
+        effect::Lasting,
+        movement::MovementProfile,
+        TileType, Weight,
+    },
+    settings,
+};
+
+/// The list of the available languages, `lang/languages.ron`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct LanguageList {
+    pub fallback: String,
+    pub languages: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Language {
+    /// The language's own name of itself.
+    pub name: String,
+
+    pub strings: HashMap<String, String>,
+}
+
+/// The language is a part of the settings.
+pub fn current() -> String {
+    match settings::with(|s| s.language.clone()) {
+        Some(language) => language,
+        None => assets::get().language_list.fallback.clone(),
+    }
+}
+
+pub fn set_current(language: &str) {
+    let mut settings = settings::get();
+    settings.language = Some(language.into());
+    settings::set(settings);
+}
+
+pub fn name(language: &str) -> String {
+    match assets::get().languages.get(language) {
+        Some(language) => language.name.clone(),
+        None => language.into(),
+    }
+}
+
+fn try_tr(id: &str) -> Option<String> {
+    let assets = assets::get();
+    let lookup = |language: &str| {
+        let language = assets.languages.get(language)?;
+        language.strings.get(id).cloned()
+    };
+    // `tr` is called a lot, so don't clone the current language's name.
+    settings::with(|s| s.language.as_deref().and_then(lookup))
+        .or_else(|| lookup(&assets.language_list.fallback))
+}
+
+/// Returns the string's id itself if there's no such string even in the fallback language.
+pub fn tr(id: &str) -> String {
+    try_tr(id).unwrap_or_else(|| {
+        warn!("lang: No string '{}'", id);
+        id.into()
+    })
+}
+
+/// Replaces `{name}` placeholders with the arguments.
+pub fn tr_with(id: &str, args: &[(&str, &dyn fmt::Display)]) -> String {
+    let mut s = tr(id);
+    for (name, value) in args {
+        s = s.replace(&format!("{{{}}}", name), &value.to_string());
+    }
+    s
+}
+
+pub fn tr_lines(id: &str) -> Vec<String> {
+    tr(id).lines().map(ToString::to_string).collect()
+}
+
+fn id_of(prefix: &str, value: impl fmt::Debug) -> String {
+    format!("{}.{}", prefix, format!("{:?}", value).to_snake_case())
+}
+
+pub fn ability_title(ability: Ability) -> String {
+    tr(&format!("{}.title", id_of("ability", ability)))
+}
+
+pub fn ability_description(ability: Ability) -> Vec<String> {
+    tr_lines(&format!("{}.description", id_of("ability", ability)))
+}
+
+pub fn passive_ability_title(ability: PassiveAbility) -> String {
+    tr(&format!("{}.title", id_of("passive_ability", ability)))
+}
+
+pub fn passive_ability_description(ability: PassiveAbility) -> Vec<String> {
+    tr_lines(&format!(
+        "{}.description",
+        id_of("passive_ability", ability)
+    ))
+}
+
+pub fn effect_title(effect: Lasting) -> String {
+    tr(&format!("{}.title", id_of("effect", effect)))
+}
+
+pub fn effect_description(effect: Lasting) -> Vec<String> {
+    tr_lines(&format!("{}.description", id_of("effect", effect)))
+}
+
+pub fn weight(weight: Weight) -> String {
+    tr(&id_of("weight", weight))
+}
+
+pub fn movement_profile(profile: MovementProfile) -> String {
+    tr(&id_of("movement", profile))
+}
+
+pub fn tile_type(tile: TileType) -> String {
+    tr(&id_of("tile", tile))
+}
+
+/// Objects added by mods may have no translated names, so typenames are used instead.
+pub fn object_name(typename: &ObjType) -> String {
+    try_tr(&format!("object.{}", typename.0)).unwrap_or_else(|| typename.0.to_title_case())
+}
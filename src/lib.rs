@@ -0,0 +1,3 @@
+//! The engine-independent part of the game, shared by the game itself and the tools.
+
+pub mod core;
@@ -2,15 +2,19 @@
 
 use std::time::Duration;
 
-use mq::{input, window};
+use mq::window;
 
 mod assets;
-mod core;
 mod error;
 mod geom;
+mod input;
+mod lang;
 mod screen;
+mod settings;
 mod utils;
 
+use zemeroth::core;
+
 type ZResult<T = ()> = Result<T, error::ZError>;
 
 struct MainState {
@@ -32,9 +36,12 @@ impl MainState {
         // Handle user input events.
         let pos = utils::get_world_mouse_pos(&camera);
         self.screens.move_mouse(pos)?;
-        if input::is_mouse_button_pressed(input::MouseButton::Left) {
+        if mq::input::is_mouse_button_pressed(mq::input::MouseButton::Left) {
             self.screens.click(pos)?;
         }
+        for action in assets::get().bindings.pressed_actions() {
+            self.screens.input_action(action)?;
+        }
         // Update the game state.
         let dtime = Duration::from_secs_f32(mq::time::get_frame_time());
         self.screens.update(dtime)?;
@@ -64,6 +71,7 @@ async fn main() -> ZResult {
     env_logger::init();
     quad_rand::srand(mq::miniquad::date::now() as _);
     mq::file::set_pc_assets_folder("assets");
+    settings::load().await.expect("Can't load settings");
     assets::load().await.expect("Can't load assets");
     let mut state = MainState::new().expect("Can't create the main state");
     loop {
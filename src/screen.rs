@@ -6,18 +6,20 @@ use mq::{
     math::{Rect, Vec2},
 };
 
-use crate::{utils, ZResult};
+use crate::{input, utils, ZResult};
 
 mod agent_info;
 mod battle;
 mod campaign;
 mod confirm;
+mod editor;
 mod general_info;
 mod main_menu;
+mod settings;
 
 pub use self::{
-    agent_info::AgentInfo, battle::Battle, campaign::Campaign, confirm::Confirm,
-    general_info::GeneralInfo, main_menu::MainMenu,
+    agent_info::AgentInfo, battle::Battle, campaign::Campaign, confirm::Confirm, editor::Editor,
+    general_info::GeneralInfo, main_menu::MainMenu, settings::Settings,
 };
 
 pub const COLOR_SCREEN_BG: Color = Color::new(0.9, 0.9, 0.8, 1.0);
@@ -40,6 +42,11 @@ pub trait Screen: Debug {
     fn move_mouse(&mut self, _pos: Vec2) -> ZResult {
         Ok(())
     }
+
+    /// Handles a bound key or mouse button, see `input::Bindings`.
+    fn input_action(&mut self, _action: input::Action) -> ZResult<StackCommand> {
+        Ok(StackCommand::None)
+    }
 }
 
 const ERR_MSG_STACK_EMPTY: &str = "Screen stack is empty";
@@ -96,6 +103,11 @@ impl ScreenStack {
         self.handle_command(command)
     }
 
+    pub fn input_action(&mut self, action: input::Action) -> ZResult {
+        let command = self.screen_mut().top_mut().input_action(action)?;
+        self.handle_command(command)
+    }
+
     pub fn move_mouse(&mut self, pos: Vec2) -> ZResult {
         self.screen_mut().top_mut().move_mouse(pos)
     }
@@ -1,6 +1,5 @@
 use std::time::Duration;
 
-use heck::TitleCase;
 use mq::{color::Color, math::Vec2};
 use ui::{self, Drawable, Gui, Widget};
 
@@ -10,6 +9,7 @@ use crate::{
         ability::{Ability, PassiveAbility},
         component::{self, Component, ObjType, Prototypes},
     },
+    input, lang,
     screen::{self, Screen, StackCommand},
     utils, ZResult,
 };
@@ -92,7 +92,7 @@ fn info_panel(
     let spacer_s = || Box::new(ui::Spacer::new_horizontal(h * 0.5).stretchable(true));
     let line = |arg: &str, val: &str| -> ZResult<_> {
         let mut line = ui::HLayout::new().stretchable(true);
-        line.add(label(arg)?);
+        line.add(label(&lang::tr(arg))?);
         line.add(spacer_s());
         line.add(label(val)?);
         Ok(Box::new(line))
@@ -100,51 +100,55 @@ fn info_panel(
     let line_i = |arg: &str, val: i32| -> ZResult<_> { line(arg, &val.to_string()) };
     {
         if let Some(meta) = info.meta {
-            let title = meta.name.0.to_title_case();
-            add(label_s(&format!("~~~ {} ~~~", title))?);
+            let title = lang::object_name(&meta.name);
+            add(label_s(&lang::tr_with(
+                "common.title",
+                &[("title", &title)],
+            ))?);
             add(spacer_v());
         }
         if let Some(strength) = info.strength {
-            add(line_i("strength:", strength.base_strength.0)?);
+            add(line_i("info.strength", strength.base_strength.0)?);
         }
         if let Some(a) = info.agent {
-            add(line_i("attacks:", a.base_attacks.0)?);
-            add(line_i("moves:", a.base_moves.0)?);
+            add(line_i("info.attacks", a.base_attacks.0)?);
+            add(line_i("info.moves", a.base_moves.0)?);
             if a.base_jokers.0 != 0 {
-                add(line_i("jokers:", a.base_jokers.0)?);
+                add(line_i("info.jokers", a.base_jokers.0)?);
             }
             if a.reactive_attacks.0 != 0 {
-                add(line_i("reactive attacks:", a.reactive_attacks.0)?);
+                add(line_i("info.reactive_attacks", a.reactive_attacks.0)?);
             }
             if a.attack_distance.0 != 1 {
-                add(line_i("attack distance:", a.attack_distance.0)?);
+                add(line_i("info.attack_distance", a.attack_distance.0)?);
             }
-            add(line_i("attack strength:", a.attack_strength.0)?);
-            add(line_i("attack accuracy:", a.attack_accuracy.0)?);
+            add(line_i("info.attack_strength", a.attack_strength.0)?);
+            add(line_i("info.attack_accuracy", a.attack_accuracy.0)?);
             if a.attack_break.0 > 0 {
-                add(line_i("armor break:", a.attack_break.0)?);
+                add(line_i("info.armor_break", a.attack_break.0)?);
             }
             if a.dodge.0 > 0 {
-                add(line_i("dodge:", a.dodge.0)?);
+                add(line_i("info.dodge", a.dodge.0)?);
             }
-            add(line_i("move points:", a.move_points.0)?);
+            add(line_i("info.move_points", a.move_points.0)?);
         }
         if let Some(armor) = info.armor {
             let armor = armor.armor.0;
             if armor != 0 {
-                add(line_i("armor:", armor)?);
+                add(line_i("info.armor", armor)?);
             }
         }
         if let Some(blocker) = info.blocker {
-            add(line("weight:", &format!("{}", blocker.weight))?);
+            add(line("info.weight", &lang::weight(blocker.weight))?);
         }
         if let Some(abilities) = info.abilities {
             if !abilities.0.is_empty() {
-                add(label_s("~ abilities ~")?);
+                add(label_s(&lang::tr("info.abilities"))?);
                 for r_ability in &abilities.0 {
-                    let s = r_ability.ability.title();
+                    let s = lang::ability_title(r_ability.ability);
                     let cooldown = r_ability.ability.base_cooldown();
-                    let text = format!("{} (cooldown: {}t)", s, cooldown);
+                    let args = [("ability", &s as _), ("cooldown", &cooldown as _)];
+                    let text = lang::tr_with("info.ability_cooldown", &args);
                     let mut line_layout = ui::HLayout::new().stretchable(true);
                     line_layout.add(label(&text)?);
                     line_layout.add(spacer_s());
@@ -159,10 +163,10 @@ fn info_panel(
         }
         if let Some(abilities) = info.passive_abilities {
             if !abilities.0.is_empty() {
-                add(label_s("~ passive abilities ~")?);
+                add(label_s(&lang::tr("info.passive_abilities"))?);
                 for &ability in &abilities.0 {
                     let mut line_layout = ui::HLayout::new().stretchable(true);
-                    line_layout.add(label(&ability.title())?);
+                    line_layout.add(label(&lang::passive_ability_title(ability))?);
                     line_layout.add(spacer_s());
                     let icon = Drawable::Texture(assets::get().textures.icons.info);
                     let message = Message::PassiveAbilityInfo(ability);
@@ -181,7 +185,7 @@ fn info_panel(
 fn button_back(gui: &mut ui::Gui<Message>, layout_width: f32) -> ZResult<Box<dyn ui::Widget>> {
     let font = assets::get().font;
     let h = utils::line_heights().normal;
-    let text = ui::Drawable::text("back", font);
+    let text = ui::Drawable::text(lang::tr("common.back"), font);
     let msg = Message::Back;
     let mut button = ui::Button::new(text, h, gui.sender(), msg)?.stretchable(true);
     button.stretch(layout_width / 3.0);
@@ -260,21 +264,32 @@ impl Screen for AgentInfo {
         match message {
             Some(Message::Back) => Ok(StackCommand::Pop),
             Some(Message::AbilityInfo(info)) => {
-                let mut description = info.description();
-                description.push(format!("Cooldown: {}t", info.base_cooldown()));
-                let screen = screen::GeneralInfo::new(&info.title(), &description)?;
+                let mut description = lang::ability_description(info);
+                let cooldown = info.base_cooldown();
+                description.push(lang::tr_with("info.cooldown", &[("cooldown", &cooldown)]));
+                let title = lang::ability_title(info);
+                let screen = screen::GeneralInfo::new(&title, &description)?;
                 Ok(StackCommand::PushPopup(Box::new(screen)))
             }
             Some(Message::PassiveAbilityInfo(info)) => {
-                let screen = screen::GeneralInfo::new(&info.title(), &info.description())?;
+                let title = lang::passive_ability_title(info);
+                let description = lang::passive_ability_description(info);
+                let screen = screen::GeneralInfo::new(&title, &description)?;
                 Ok(StackCommand::PushPopup(Box::new(screen)))
             }
             None => Ok(StackCommand::None),
         }
     }
 
+    fn input_action(&mut self, action: input::Action) -> ZResult<StackCommand> {
+        match action {
+            input::Action::Cancel => Ok(StackCommand::Pop),
+            _ => Ok(StackCommand::None),
+        }
+    }
+
     fn resize(&mut self, aspect_ratio: f32) {
-        self.gui.resize_if_needed(aspect_ratio);
+        utils::resize_gui(&mut self.gui, aspect_ratio);
     }
 
     fn move_mouse(&mut self, pos: Vec2) -> ZResult {
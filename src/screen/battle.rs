@@ -3,7 +3,6 @@ use std::{
     time::Duration,
 };
 
-use heck::TitleCase;
 use log::{info, trace};
 use mq::{color::Color, math::Vec2};
 
@@ -17,39 +16,55 @@ use crate::{
             self,
             ability::{self, Ability, PassiveAbility},
             ai::Ai,
-            check, command,
+            attack, check, command,
             component::Prototypes,
             effect,
-            movement::Pathfinder,
-            scenario,
+            movement::{self, MovementProfile, Pathfinder},
+            preview, scenario,
             state::{self, BattleResult},
-            Id, PlayerId, State,
+            threat, Id, PlayerId, State, TileType,
         },
-        map::PosHex,
+        map::{HexMap, PosHex},
     },
-    geom,
+    geom, input, lang,
     screen::{
         self,
         battle::{
+            event_log::Entry,
             view::{make_action_create_map, BattleView, SelectionMode},
             visualize::{color, fork, visualize},
         },
         Screen, StackCommand,
     },
+    settings,
     utils::{self, line_heights, time_s},
     ZResult,
 };
 
+mod event_log;
+mod tooltip;
 mod view;
 mod visualize;
 
+/// How much faster the animations are played while the fast-forward key is held.
+const FAST_FORWARD_TIME_SCALE: f32 = 4.0;
+
+/// How many entries of the battle log are visible at once.
+const LOG_LINES: usize = 6;
+
 #[derive(Clone, Debug)]
 enum Message {
     Exit,
     EndTurn,
+    SuggestCommand,
+    AutoTurn,
+    ToggleThreats,
     Ability(Ability),
     PassiveAbilityInfo(PassiveAbility),
     LastingEffectInfo(effect::Lasting),
+    ScrollLogUp,
+    ScrollLogDown,
+    LogEntry(usize),
 }
 
 fn textures() -> &'static assets::Textures {
@@ -89,7 +104,7 @@ fn build_panel_agent_info(gui: &mut Gui<Message>, state: &State, id: Id) -> ZRes
     let label_s = |text: &str| -> ZResult<_> { Ok(Box::new(label_(text)?.stretchable(true))) };
     let line = |arg: &str, val: &str| -> ZResult<_> {
         let mut line = ui::HLayout::new().stretchable(true);
-        line.add(label(arg)?);
+        line.add(label(&lang::tr(arg))?);
         line.add(Box::new(ui::Spacer::new_horizontal(h).stretchable(true)));
         line.add(label(val)?);
         Ok(Box::new(line))
@@ -106,69 +121,75 @@ fn build_panel_agent_info(gui: &mut Gui<Message>, state: &State, id: Id) -> ZRes
             .with_color(dot_color);
         line.add(Box::new(label_dot));
         line.add(Box::new(ui::Spacer::new_horizontal(h * 0.1)));
-        line.add(label(arg)?);
+        line.add(label(&lang::tr(arg))?);
         line.add(Box::new(ui::Spacer::new_horizontal(h).stretchable(true)));
         line.add(label(val)?);
         Ok(Box::new(line))
     };
     {
-        let title = meta.name.0.to_title_case();
-        add(label_s(&format!("~~~ {} ~~~", title))?);
+        let title = lang::object_name(&meta.name);
+        add(label_s(&lang::tr_with(
+            "common.title",
+            &[("title", &title)],
+        ))?);
         add(line_dot(
-            "strength:",
+            "info.strength",
             &format!("{}/{}", st.strength.0, st.base_strength.0),
             color::STRENGTH,
         )?);
         if let Some(armor) = parts.armor.get_opt(id) {
             let armor = armor.armor.0;
             if armor != 0 {
-                add(line_dot("armor:", &armor.to_string(), color::ARMOR)?);
+                add(line_dot("info.armor", &armor.to_string(), color::ARMOR)?);
             }
         }
         if a.jokers.0 != 0 || a.base_jokers.0 != 0 {
             add(line_dot(
-                "jokers:",
+                "info.jokers",
                 &format!("{}/{}", a.jokers.0, a.base_jokers.0),
                 color::JOKERS,
             )?);
         }
         add(line_dot(
-            "attacks:",
+            "info.attacks",
             &format!("{}/{}", a.attacks.0, a.base_attacks.0),
             color::ATTACKS,
         )?);
         if a.reactive_attacks.0 != 0 {
             add(line_dot(
-                "reactive attacks:",
+                "info.reactive_attacks",
                 &a.reactive_attacks.0.to_string(),
                 color::ATTACKS,
             )?);
         }
         add(line_dot(
-            "moves:",
+            "info.moves",
             &format!("{}/{}", a.moves.0, a.base_moves.0),
             color::MOVES,
         )?);
         if a.attack_distance.0 != 1 {
-            add(line_i("attack distance:", a.attack_distance.0)?);
+            add(line_i("info.attack_distance", a.attack_distance.0)?);
         }
-        add(line_i("attack strength:", a.attack_strength.0)?);
-        add(line_i("attack accuracy:", a.attack_accuracy.0)?);
+        add(line_i("info.attack_strength", a.attack_strength.0)?);
+        add(line_i("info.attack_accuracy", a.attack_accuracy.0)?);
         if a.attack_break.0 > 0 {
-            add(line_i("armor break:", a.attack_break.0)?);
+            add(line_i("info.armor_break", a.attack_break.0)?);
         }
         if a.dodge.0 > 0 {
-            add(line_i("dodge:", a.dodge.0)?);
+            add(line_i("info.dodge", a.dodge.0)?);
+        }
+        add(line_i("info.move_points", a.move_points.0)?);
+        if a.movement != MovementProfile::Walker {
+            add(line("info.movement", &lang::movement_profile(a.movement))?);
         }
-        add(line_i("move points:", a.move_points.0)?);
         if let Some(blocker) = parts.blocker.get_opt(id) {
-            add(line("weight:", &blocker.weight.to_string())?);
+            add(line("info.weight", &lang::weight(blocker.weight))?);
         }
         if let Some(abilities) = parts.passive_abilities.get_opt(id) {
             if !abilities.0.is_empty() {
-                add(label_s("~ passive abilities ~")?);
+                add(label_s(&lang::tr("info.passive_abilities"))?);
                 for &ability in &abilities.0 {
-                    let text = ability.title();
+                    let text = lang::passive_ability_title(ability);
                     let message = Message::PassiveAbilityInfo(ability);
                     add(line_with_info_button(gui, &text, message)?);
                     add(Box::new(ui::Spacer::new_vertical(space_between_buttons)));
@@ -177,12 +198,15 @@ fn build_panel_agent_info(gui: &mut Gui<Message>, state: &State, id: Id) -> ZRes
         }
         if let Some(effects) = parts.effects.get_opt(id) {
             if !effects.0.is_empty() {
-                add(label_s("~ effects ~")?);
+                add(label_s(&lang::tr("info.effects"))?);
                 for effect in &effects.0 {
-                    let s = effect.effect.title();
+                    let s = lang::effect_title(effect.effect);
                     let text = match effect.duration {
-                        effect::Duration::Forever => s.into(),
-                        effect::Duration::Rounds(n) => format!("{} ({}t)", s, n),
+                        effect::Duration::Forever => s,
+                        effect::Duration::Rounds(n) => {
+                            let args = [("effect", &s as _), ("rounds", &n as _)];
+                            lang::tr_with("info.effect_duration", &args)
+                        }
                     };
                     let message = Message::LastingEffectInfo(effect.effect);
                     let text = ui::Drawable::text(text, font);
@@ -216,6 +240,56 @@ fn build_panel_agent_info(gui: &mut Gui<Message>, state: &State, id: Id) -> ZRes
     Ok(layout)
 }
 
+fn build_panel_attack_outcomes(
+    gui: &mut Gui<Message>,
+    state: &State,
+    attacker_id: Id,
+    target_id: Id,
+) -> ZResult<ui::RcWidget> {
+    let font = assets::get().font;
+    let h = line_heights().normal;
+    let distribution = attack::distribution(state, attacker_id, target_id);
+    let mut layout = Box::new(ui::VLayout::new().stretchable(true));
+    let text = |s: &str| ui::Drawable::text(s, font);
+    let line = |arg: &str, val: &str| -> ZResult<_> {
+        let mut line = ui::HLayout::new().stretchable(true);
+        line.add(Box::new(ui::Label::new(text(arg), h)?));
+        line.add(Box::new(ui::Spacer::new_horizontal(h).stretchable(true)));
+        line.add(Box::new(ui::Label::new(text(val), h)?));
+        Ok(Box::new(line))
+    };
+    let title = ui::Label::new(text(&lang::tr("battle.attack_outcomes")), h)?.stretchable(true);
+    layout.add(Box::new(title));
+    for &(outcome, chance) in distribution.outcomes() {
+        let description = match outcome {
+            attack::Outcome::Miss => lang::tr("battle.outcome_miss"),
+            attack::Outcome::Wound {
+                damage,
+                armor_break,
+            } if armor_break.0 > 0 => {
+                let args = [
+                    ("damage", &damage.0 as _),
+                    ("armor_break", &armor_break.0 as _),
+                ];
+                lang::tr_with("battle.outcome_wound_with_break", &args)
+            }
+            attack::Outcome::Wound { damage, .. } => {
+                lang::tr_with("battle.outcome_wound", &[("damage", &damage.0)])
+            }
+            attack::Outcome::Kill => lang::tr("battle.outcome_kill"),
+        };
+        layout.add(line(&description, &format!("{:.0}%", chance * 100.0))?);
+    }
+    let expected_damage = format!("{:.1}", distribution.expected_damage());
+    layout.add(line(&lang::tr("battle.expected_damage"), &expected_damage)?);
+    layout.stretch_to_self();
+    let layout = utils::add_offsets_and_bg(layout, utils::OFFSET_SMALL)?;
+    let layout = ui::pack(layout);
+    let anchor = ui::Anchor(ui::HAnchor::Middle, ui::VAnchor::Top);
+    gui.add(&layout, anchor);
+    Ok(layout)
+}
+
 fn build_panel_agent_abilities(
     gui: &mut Gui<Message>,
     state: &State,
@@ -235,7 +309,8 @@ fn build_panel_agent_abilities(
         let texture = *icons.get(&ability.ability).expect("No such icon found");
         let drawable = ui::Drawable::Texture(texture);
         let msg = Message::Ability(ability.ability);
-        let mut button = ui::Button::new(drawable, h, gui.sender(), msg)?;
+        let mut button = ui::Button::new(drawable, h, gui.sender(), msg)?
+            .with_tooltip(tooltip::ability(ability)?);
         if !state::can_agent_use_ability(state, id, &ability.ability) {
             button.set_active(false);
         }
@@ -263,10 +338,22 @@ fn build_panel_agent_abilities(
 }
 
 fn build_panel_end_turn(gui: &mut Gui<Message>) -> ZResult<ui::RcWidget> {
+    let font = assets::get().font;
     let h = line_heights().large;
+    let mut layout = ui::VLayout::new();
+    let mut add_text_button = |id: &str, message| -> ZResult {
+        let text = ui::Drawable::text(lang::tr(id), font);
+        let button = ui::Button::new(text, h / 2.0, gui.sender(), message)?.stretchable(true);
+        layout.add(Box::new(button));
+        layout.add(Box::new(ui::Spacer::new_vertical(h / 8.0)));
+        Ok(())
+    };
+    add_text_button("battle.hint", Message::SuggestCommand)?;
+    add_text_button("battle.auto", Message::AutoTurn)?;
     let tex = ui::Drawable::Texture(textures().icons.end_turn);
     let button = ui::Button::new(tex, h, gui.sender(), Message::EndTurn)?;
-    let layout = ui::VLayout::from_widget(Box::new(button));
+    layout.add(Box::new(button));
+    layout.stretch_to_self();
     let anchor = ui::Anchor(ui::HAnchor::Right, ui::VAnchor::Bottom);
     let packed_layout = ui::pack(layout);
     gui.add(&packed_layout, anchor);
@@ -283,11 +370,12 @@ fn build_panel_ability_description(
     let text = |s: &str| ui::Drawable::text(s, font);
     let h = line_heights().normal;
     let mut layout = Box::new(ui::VLayout::new().stretchable(true));
-    let text_title = text(&format!("~~~ {} ~~~", ability.title()));
+    let title = lang::ability_title(*ability);
+    let text_title = text(&lang::tr_with("common.title", &[("title", &title)]));
     let label_title = ui::Label::new(text_title, h)?.stretchable(true);
     layout.add(Box::new(label_title));
     layout.add(Box::new(ui::Spacer::new_vertical(h / 2.0)));
-    for line in ability.description() {
+    for line in lang::ability_description(*ability) {
         layout.add(Box::new(ui::Label::new(text(&line), h)?));
     }
     let agent_player_id = state.parts().belongs_to.get(id).0;
@@ -295,23 +383,23 @@ fn build_panel_ability_description(
     let r_ability = abilities.iter().find(|r| &r.ability == ability).unwrap();
     let is_enemy_agent = agent_player_id != state.player_id();
     let cooldown = r_ability.ability.base_cooldown();
-    let text_cooldown = text(&format!("Cooldown: {}t", cooldown));
+    let text_cooldown = text(&lang::tr_with("info.cooldown", &[("cooldown", &cooldown)]));
     layout.add(Box::new(ui::Label::new(text_cooldown, h)?));
     if !state::can_agent_use_ability(state, id, ability) {
         layout.add(Box::new(ui::Spacer::new_vertical(h / 2.0)));
         let s = if is_enemy_agent {
-            "Can't be used: enemy agent.".into()
+            lang::tr("battle.cant_use_enemy")
         } else if let ability::Status::Cooldown(n) = r_ability.status {
-            format!("Can't be used: cooldown ({}t).", n)
+            lang::tr_with("battle.cant_use_cooldown", &[("cooldown", &n)])
         } else {
-            "Can't be used: no attacks or jokers.".into()
+            lang::tr("battle.cant_use_no_attacks")
         };
         let color = Color::new(0.5, 0.0, 0.0, 1.0);
         let label = ui::Label::new(text(&s), h)?.with_color(color);
         layout.add(Box::new(label));
     }
     layout.add(Box::new(ui::Spacer::new_vertical(h / 2.0)));
-    let text_cancel = text("Click on an empty tile or the ability icon to cancel.");
+    let text_cancel = text(&lang::tr("battle.cancel_ability"));
     let color_cancel = Color::new(0.4, 0.4, 0.4, 1.0);
     let label_cancel_text = ui::Label::new(text_cancel, h)?.with_color(color_cancel);
     layout.add(Box::new(label_cancel_text));
@@ -323,12 +411,52 @@ fn build_panel_ability_description(
     Ok(layout)
 }
 
+/// `scroll` is the number of the latest entries that are hidden below.
+fn build_panel_log(
+    gui: &mut Gui<Message>,
+    entries: &[Entry],
+    scroll: usize,
+) -> ZResult<ui::RcWidget> {
+    let font = assets::get().font;
+    let text = |s: &str| ui::Drawable::text(s, font);
+    let h = line_heights().small;
+    let mut layout = Box::new(ui::VLayout::new().stretchable(true));
+    let mut header = ui::HLayout::new().stretchable(true);
+    header.add(Box::new(ui::Label::new(text(&lang::tr("battle.log")), h)?));
+    header.add(Box::new(ui::Spacer::new_horizontal(h).stretchable(true)));
+    let button_up = ui::Button::new(text("^"), h, gui.sender(), Message::ScrollLogUp)?;
+    header.add(Box::new(button_up));
+    header.add(Box::new(ui::Spacer::new_horizontal(h / 4.0)));
+    let button_down = ui::Button::new(text("v"), h, gui.sender(), Message::ScrollLogDown)?;
+    header.add(Box::new(button_down));
+    layout.add(Box::new(header));
+    let end = entries.len() - scroll;
+    let begin = end.saturating_sub(LOG_LINES);
+    for (i, entry) in entries.iter().enumerate().take(end).skip(begin) {
+        layout.add(Box::new(ui::Spacer::new_vertical(h / 8.0)));
+        let sender = gui.sender();
+        let button = ui::Button::new(text(&entry.text), h, sender, Message::LogEntry(i))?;
+        layout.add(Box::new(button.stretchable(true)));
+    }
+    layout.stretch_to_self();
+    let layout = utils::add_offsets_and_bg(layout, utils::OFFSET_SMALL)?;
+    let layout = ui::pack(layout);
+    let anchor = ui::Anchor(ui::HAnchor::Right, ui::VAnchor::Top);
+    gui.add(&layout, anchor);
+    Ok(layout)
+}
+
 fn make_gui() -> ZResult<ui::Gui<Message>> {
+    let font = assets::get().font;
     let mut gui = ui::Gui::new();
     let h = line_heights().large;
     let icon = textures().icons.main_menu;
     let button = ui::Button::new(ui::Drawable::Texture(icon), h, gui.sender(), Message::Exit)?;
-    let layout = ui::VLayout::from_widget(Box::new(button));
+    let mut layout = ui::VLayout::from_widget(Box::new(button));
+    layout.add(Box::new(ui::Spacer::new_vertical(h / 8.0)));
+    let text = ui::Drawable::text(lang::tr("battle.threats"), font);
+    let button_threats = ui::Button::new(text, h / 2.0, gui.sender(), Message::ToggleThreats)?;
+    layout.add(Box::new(button_threats));
     let anchor = ui::Anchor(ui::HAnchor::Left, ui::VAnchor::Top);
     gui.add(&ui::pack(layout), anchor);
     Ok(gui)
@@ -348,15 +476,36 @@ pub struct Battle {
     mode: SelectionMode,
     view: BattleView,
     selected_agent_id: Option<Id>,
+    hovered_target_id: Option<Id>,
+    mouse_point: Vec2,
+    previewed_pos: Option<PosHex>,
     pathfinder: Pathfinder,
     block_timer: Option<Duration>,
+    is_threat_overlay_enabled: bool,
     ai: Ai,
+
+    /// Plays for the human player: suggests commands and resolves whole turns.
+    player_ai: Ai,
+
     panel_info: Option<ui::RcWidget>,
     panel_abilities: Option<ui::RcWidget>,
     panel_ability_description: Option<ui::RcWidget>,
     panel_end_turn: Option<ui::RcWidget>,
+    panel_attack_outcomes: Option<ui::RcWidget>,
+    panel_log: Option<ui::RcWidget>,
+
+    /// How many log entries the panel was built from.
+    log_len: usize,
+
+    /// How many of the latest log entries are scrolled out of the panel.
+    log_scroll: usize,
+
+    /// The log entry whose objects are highlighted on the map.
+    highlighted_log_entry: Option<usize>,
+
     sender: Sender<Option<BattleResult>>,
     confirmation_receiver_exit: Option<Receiver<screen::confirm::Message>>,
+    confirmation_receiver_end_turn: Option<Receiver<screen::confirm::Message>>,
 }
 
 impl Battle {
@@ -366,8 +515,8 @@ impl Battle {
         prototypes: Prototypes,
         sender: Sender<Option<BattleResult>>,
     ) -> ZResult<Self> {
-        let radius = scenario.map_radius;
-        let mut view = BattleView::new(radius)?;
+        let map: HexMap<TileType> = scenario.make_map();
+        let mut view = BattleView::new(&map)?;
         let mut gui = make_gui()?;
         let mut actions = Vec::new();
         let state = State::new(prototypes, scenario, &mut |state, event, phase| {
@@ -378,6 +527,7 @@ impl Battle {
         actions.push(make_action_create_map(&state, &view)?);
         view.add_action(action::Sequence::new(actions).boxed());
         let panel_end_turn = Some(build_panel_end_turn(&mut gui)?);
+        let panel_log = Some(build_panel_log(&mut gui, &[], 0)?);
         Ok(Self {
             gui,
             view,
@@ -385,15 +535,26 @@ impl Battle {
             state,
             battle_type,
             selected_agent_id: None,
-            pathfinder: Pathfinder::new(radius),
+            hovered_target_id: None,
+            mouse_point: Vec2::new(0.0, 0.0),
+            previewed_pos: None,
+            pathfinder: Pathfinder::new(&map),
             block_timer: None,
-            ai: Ai::new(PlayerId(1), radius),
+            is_threat_overlay_enabled: false,
+            ai: Ai::new(PlayerId(1), &map).with_difficulty(settings::get().ai_difficulty),
+            player_ai: Ai::new(PlayerId(0), &map),
             panel_info: None,
             panel_abilities: None,
             panel_end_turn,
             panel_ability_description: None,
+            panel_attack_outcomes: None,
+            panel_log,
+            log_len: 0,
+            log_scroll: 0,
+            highlighted_log_entry: None,
             sender,
             confirmation_receiver_exit: None,
+            confirmation_receiver_end_turn: None,
         })
     }
 
@@ -409,6 +570,57 @@ impl Battle {
         Ok(())
     }
 
+    /// Asks for a confirmation first if the settings say so.
+    fn try_end_turn(&mut self) -> ZResult<StackCommand> {
+        if !settings::with(|s| s.confirm_end_turn) {
+            self.end_turn()?;
+            return Ok(StackCommand::None);
+        }
+        let (sender, receiver) = channel();
+        self.confirmation_receiver_end_turn = Some(receiver);
+        let popup = screen::Confirm::from_line(&lang::tr("battle.confirm_end_turn"), sender)?;
+        Ok(StackCommand::PushPopup(Box::new(popup)))
+    }
+
+    /// Lets the player's AI make all the moves and then ends the turn.
+    fn auto_turn(&mut self) -> ZResult {
+        utils::remove_widget(&mut self.gui, &mut self.panel_end_turn)?;
+        self.deselect()?;
+        let mut actions = Vec::new();
+        while let Some(command) = self.player_ai.command(&self.state) {
+            trace!("Player's AI: command = {:?}", command);
+            if let command::Command::EndTurn(_) = command {
+                break;
+            }
+            actions.push(self.do_command_inner(&command, CommandOrigin::Internal));
+            actions.push(action::Sleep::new(time_s(0.2)).boxed());
+        }
+        if self.state.battle_result().is_none() {
+            let command = command::EndTurn.into();
+            actions.push(self.do_command_inner(&command, CommandOrigin::Internal));
+            actions.push(self.do_ai());
+        }
+        self.add_actions(actions);
+        Ok(())
+    }
+
+    fn suggest_command(&mut self) -> ZResult {
+        let id = match self.selected_agent_id {
+            Some(id) => id,
+            None => return Ok(()),
+        };
+        if self.state.parts().belongs_to.get(id).0 != self.state.player_id() {
+            return Ok(());
+        }
+        match self.player_ai.command_for_agent(&self.state, id) {
+            Some(command) => self.view.show_suggestion(&self.state, &command),
+            None => {
+                let pos = self.state.parts().pos.get(id).0;
+                self.view.message(pos, &lang::tr("battle.nothing_to_do"))
+            }
+        }
+    }
+
     fn do_ai(&mut self) -> Box<dyn Action> {
         trace!("AI: <");
         let mut actions = Vec::new();
@@ -435,14 +647,67 @@ impl Battle {
         self.set_mode(id, SelectionMode::Ability(ability))
     }
 
+    /// Selects the player's next agent that still has some actions left.
+    fn select_next_agent(&mut self) -> ZResult {
+        let player_id = self.state.player_id();
+        let mut ids = state::players_agent_ids(&self.state, player_id);
+        ids.retain(|&id| state::has_actions_left(&self.state, id));
+        ids.sort();
+        let next_id = match self.selected_agent_id {
+            Some(selected_id) => ids
+                .iter()
+                .find(|&&id| id > selected_id)
+                .or_else(|| ids.first()),
+            None => ids.first(),
+        };
+        match next_id {
+            Some(&id) => self.set_mode(id, SelectionMode::Normal),
+            None => Ok(()),
+        }
+    }
+
+    /// Picks the selected agent's ability by its number, starting from 1.
+    fn pick_ability(&mut self, number: usize) -> ZResult {
+        let id = match self.selected_agent_id {
+            Some(id) => id,
+            None => return Ok(()),
+        };
+        let abilities = match self.state.parts().abilities.get_opt(id) {
+            Some(abilities) => &abilities.0,
+            None => return Ok(()),
+        };
+        match number.checked_sub(1).and_then(|i| abilities.get(i)) {
+            Some(ability) => self.use_ability(ability.ability),
+            None => Ok(()),
+        }
+    }
+
+    fn cancel(&mut self) -> ZResult {
+        match (self.mode, self.selected_agent_id) {
+            (SelectionMode::Ability(_), Some(id)) => self.set_mode(id, SelectionMode::Normal),
+            _ => self.deselect(),
+        }
+    }
+
+    fn popup_agent_info(&self) -> ZResult<Option<Box<dyn Screen>>> {
+        let id = match self.selected_agent_id {
+            Some(id) => id,
+            None => return Ok(None),
+        };
+        let typename = &self.state.parts().meta.get(id).name;
+        let prototypes = &assets::get().prototypes;
+        let popup = screen::AgentInfo::new_agent_info(prototypes, typename)?;
+        Ok(Some(Box::new(popup)))
+    }
+
     fn popup_confirm_exit(&mut self) -> ZResult<Box<dyn Screen>> {
         let (sender, receiver) = channel();
         self.confirmation_receiver_exit = Some(receiver);
         let message = match self.battle_type {
-            scenario::BattleType::Skirmish => "Abandon this battle?",
-            scenario::BattleType::CampaignNode => "Abandon the whole campaign?",
+            scenario::BattleType::Skirmish => "battle.abandon_battle",
+            scenario::BattleType::CampaignNode => "battle.abandon_campaign",
         };
-        let popup = screen::Confirm::from_line(message, sender)?;
+        let popup = screen::Confirm::from_line(&lang::tr(message), sender)?;
         Ok(Box::new(popup))
     }
 
@@ -471,10 +736,14 @@ impl Battle {
         action::Sequence::new(actions).boxed()
     }
 
-    fn do_command(&mut self, command: &command::Command) {
+    fn do_command(&mut self, command: &command::Command) -> ZResult {
         let action = self.do_command_inner(command, CommandOrigin::Player);
         self.add_action(action);
         self.view.messages_map_mut().clear();
+        // The shown odds were calculated for the old state.
+        utils::remove_widget(&mut self.gui, &mut self.panel_attack_outcomes)?;
+        self.hovered_target_id = None;
+        Ok(())
     }
 
     fn add_actions(&mut self, actions: Vec<Box<dyn Action>>) {
@@ -500,6 +769,9 @@ impl Battle {
         utils::remove_widget(&mut self.gui, &mut self.panel_info)?;
         utils::remove_widget(&mut self.gui, &mut self.panel_abilities)?;
         utils::remove_widget(&mut self.gui, &mut self.panel_ability_description)?;
+        utils::remove_widget(&mut self.gui, &mut self.panel_attack_outcomes)?;
+        self.hovered_target_id = None;
+        self.previewed_pos = None;
         if self.selected_agent_id.is_some() {
             self.view.remove_highlights();
         }
@@ -565,7 +837,7 @@ impl Battle {
             if check(&self.state, &command_attack).is_err() {
                 return Ok(());
             }
-            self.do_command(&command_attack);
+            self.do_command(&command_attack)?;
             self.fill_map();
         } else {
             self.set_mode(id, SelectionMode::Normal)?;
@@ -581,20 +853,21 @@ impl Battle {
         }
     }
 
-    fn try_move_selected_agent(&mut self, pos: PosHex) {
+    fn try_move_selected_agent(&mut self, pos: PosHex) -> ZResult {
         if let Some(id) = self.selected_agent_id {
-            let path = match self.pathfinder.path(pos) {
+            let path = match movement::find_path(&self.state, id, pos, &movement::ActualCost) {
                 Some(path) => path,
-                None => return,
+                None => return Ok(()),
             };
             assert_eq!(path.from(), self.state.parts().pos.get(id).0);
             let command_move = command::MoveTo { id, path }.into();
             if check(&self.state, &command_move).is_err() {
-                return;
+                return Ok(());
             }
-            self.do_command(&command_move);
+            self.do_command(&command_move)?;
             self.fill_map();
         }
+        Ok(())
     }
 
     fn handle_click(&mut self, point: Vec2) -> ZResult {
@@ -607,26 +880,111 @@ impl Battle {
             let id = self.selected_agent_id.unwrap();
             let command = command::UseAbility { id, pos, ability }.into();
             if check(&self.state, &command).is_ok() {
-                self.do_command(&command);
+                self.do_command(&command)?;
             } else {
-                self.view.message(pos, "cancelled")?;
+                self.view.message(pos, &lang::tr("battle.cancelled"))?;
             }
             self.set_mode(id, SelectionMode::Normal)?;
         } else if self.state.map().is_inboard(pos) {
             if let Some(id) = state::agent_id_at_opt(&self.state, pos) {
                 self.handle_agent_click(id)?;
             } else {
-                self.try_move_selected_agent(pos);
+                self.try_move_selected_agent(pos)?;
             }
         }
         self.view.messages_map_mut().clear();
         Ok(())
     }
 
+    fn attack_target_at(&self, pos: PosHex) -> Option<Id> {
+        let attacker_id = self.selected_agent_id?;
+        if self.block_timer.is_some() || self.mode != SelectionMode::Normal {
+            return None;
+        }
+        if !self.state.map().is_inboard(pos) {
+            return None;
+        }
+        let target_id = state::agent_id_at_opt(&self.state, pos)?;
+        let command = command::Attack {
+            attacker_id,
+            target_id,
+        }
+        .into();
+        check(&self.state, &command).ok()?;
+        Some(target_id)
+    }
+
+    fn update_panel_attack_outcomes(&mut self, pos: PosHex) -> ZResult {
+        let target_id = self.attack_target_at(pos);
+        if target_id == self.hovered_target_id {
+            return Ok(());
+        }
+        self.hovered_target_id = target_id;
+        utils::remove_widget(&mut self.gui, &mut self.panel_attack_outcomes)?;
+        if let (Some(attacker_id), Some(target_id)) = (self.selected_agent_id, target_id) {
+            let gui = &mut self.gui;
+            let panel = build_panel_attack_outcomes(gui, &self.state, attacker_id, target_id)?;
+            self.panel_attack_outcomes = Some(panel);
+        }
+        Ok(())
+    }
+
+    fn update_preview(&mut self, pos: PosHex) {
+        let ability = match self.mode {
+            SelectionMode::Ability(ability) => ability,
+            SelectionMode::Normal => return,
+        };
+        if self.block_timer.is_some() || self.previewed_pos == Some(pos) {
+            return;
+        }
+        self.previewed_pos = Some(pos);
+        let id = self.selected_agent_id.unwrap();
+        let command = command::UseAbility { id, pos, ability }.into();
+        match preview::preview(&self.state, &command) {
+            Ok(preview) => self.view.show_preview(&preview),
+            Err(_) => self.view.clean_preview(),
+        }
+    }
+
+    /// Describes the enemy agent or the tile under the mouse cursor.
+    fn show_map_tooltip(&mut self) -> ZResult {
+        let pos = geom::point_to_hex(self.view.tile_size(), self.mouse_point);
+        if !self.state.map().is_inboard(pos) {
+            return Ok(());
+        }
+        let state = &self.state;
+        let tooltip = match state::agent_id_at_opt(state, pos) {
+            Some(id) if state.parts().belongs_to.get(id).0 != state.player_id() => {
+                tooltip::enemy_agent(state, id)?
+            }
+            _ => tooltip::tile(state, pos)?,
+        };
+        self.gui.show_tooltip(tooltip);
+        Ok(())
+    }
+
+    fn toggle_threat_overlay(&mut self) {
+        self.is_threat_overlay_enabled = !self.is_threat_overlay_enabled;
+        if self.is_threat_overlay_enabled {
+            self.update_threat_overlay();
+        } else {
+            self.view.hide_threats();
+        }
+    }
+
+    fn update_threat_overlay(&mut self) {
+        if !self.is_threat_overlay_enabled {
+            return;
+        }
+        let threats = threat::threat_map(&self.state, self.state.player_id());
+        self.view.show_threats(&threats);
+    }
+
     fn update_block_timer(&mut self, dtime: Duration) -> ZResult {
         if let Some(time) = self.block_timer {
-            if time < dtime {
+            if time <= dtime {
                 self.block_timer = None;
+                self.update_threat_overlay();
                 if let Some(id) = self.selected_agent_id {
                     self.set_mode(id, SelectionMode::Normal)?;
                 }
@@ -638,6 +996,55 @@ impl Battle {
         Ok(())
     }
 
+    fn rebuild_panel_log(&mut self) -> ZResult {
+        utils::remove_widget(&mut self.gui, &mut self.panel_log)?;
+        let entries = self.view.log().borrow();
+        self.panel_log = Some(build_panel_log(&mut self.gui, &entries, self.log_scroll)?);
+        Ok(())
+    }
+
+    /// Rebuilds the log panel if the shown events have added some new entries.
+    fn update_panel_log(&mut self) -> ZResult {
+        let len = self.view.log().borrow().len();
+        if len == self.log_len {
+            return Ok(());
+        }
+        if self.log_scroll > 0 {
+            // Keep the same entries in sight while the player reads the history.
+            self.log_scroll += len - self.log_len;
+        }
+        self.log_len = len;
+        self.rebuild_panel_log()
+    }
+
+    fn scroll_log(&mut self, scroll: usize) -> ZResult {
+        let max_scroll = self.log_len.saturating_sub(LOG_LINES);
+        let scroll = scroll.min(max_scroll);
+        if scroll == self.log_scroll {
+            return Ok(());
+        }
+        self.log_scroll = scroll;
+        self.rebuild_panel_log()
+    }
+
+    /// Highlights the objects of the log entry or removes the highlight
+    /// if the same entry was clicked again.
+    fn toggle_log_entry_highlight(&mut self, index: usize) {
+        if self.highlighted_log_entry == Some(index) {
+            self.unhighlight_log_entry();
+            return;
+        }
+        let positions = self.view.log().borrow()[index].positions.clone();
+        self.view.show_log_highlight(&positions);
+        self.highlighted_log_entry = Some(index);
+    }
+
+    fn unhighlight_log_entry(&mut self) {
+        if self.highlighted_log_entry.take().is_some() {
+            self.view.hide_log_highlight();
+        }
+    }
+
     fn send_battle_result(&self, result: Option<BattleResult>) {
         let err_msg = "Can't report back a battle's result";
         self.sender.send(result).expect(err_msg);
@@ -651,8 +1058,33 @@ impl Screen for Battle {
             self.send_battle_result(None);
             return Ok(StackCommand::Pop);
         }
+        if screen::confirm::try_receive_yes(&self.confirmation_receiver_end_turn) {
+            self.confirmation_receiver_end_turn = None;
+            self.end_turn()?;
+        }
+        let (tooltips, animation_speed, skip_animations) =
+            settings::with(|s| (s.tooltips, s.animation_speed, s.skip_animations));
+        if tooltips {
+            self.gui.tick(dtime);
+            if self.gui.needs_tooltip() {
+                self.show_map_tooltip()?;
+            }
+        }
+        let mut time_scale = animation_speed;
+        if assets::get().bindings.is_held(input::Action::FastForward) {
+            time_scale *= FAST_FORWARD_TIME_SCALE;
+        }
+        self.view.set_time_scale(time_scale);
+        self.view.set_instant(skip_animations);
         self.view.tick(dtime);
+        // The blocking timer must run out together with the animations.
+        let dtime = if skip_animations {
+            self.block_timer.unwrap_or_default()
+        } else {
+            dtime.mul_f32(time_scale)
+        };
         self.update_block_timer(dtime)?;
+        self.update_panel_log()?;
         if self.block_timer.is_none() && !self.view.any_unfinished_actions() {
             if let Some(result) = self.state.battle_result().clone() {
                 self.send_battle_result(Some(result));
@@ -680,37 +1112,86 @@ impl Screen for Battle {
             }
             Some(Message::EndTurn) => {
                 assert!(self.block_timer.is_none());
-                self.end_turn()?;
+                return self.try_end_turn();
+            }
+            Some(Message::SuggestCommand) => self.suggest_command()?,
+            Some(Message::ToggleThreats) => self.toggle_threat_overlay(),
+            Some(Message::AutoTurn) => {
+                assert!(self.block_timer.is_none());
+                self.auto_turn()?;
             }
             Some(Message::Ability(ability)) => self.use_ability(ability)?,
             Some(Message::PassiveAbilityInfo(ability)) => {
-                let title = &ability.title();
-                let description = &ability.description();
+                let title = &lang::passive_ability_title(ability);
+                let description = &lang::passive_ability_description(ability);
                 let popup = screen::GeneralInfo::new(title, description)?;
                 return Ok(StackCommand::PushPopup(Box::new(popup)));
             }
             Some(Message::LastingEffectInfo(effect)) => {
-                let title = &effect.title();
-                let description = &effect.description();
+                let title = &lang::effect_title(effect);
+                let description = &lang::effect_description(effect);
                 let popup = screen::GeneralInfo::new(title, description)?;
                 return Ok(StackCommand::PushPopup(Box::new(popup)));
             }
+            Some(Message::ScrollLogUp) => self.scroll_log(self.log_scroll + 1)?,
+            Some(Message::ScrollLogDown) => self.scroll_log(self.log_scroll.saturating_sub(1))?,
+            Some(Message::LogEntry(index)) => self.toggle_log_entry_highlight(index),
             None => self.handle_click(pos)?,
         }
         Ok(StackCommand::None)
     }
 
+    fn input_action(&mut self, action: input::Action) -> ZResult<StackCommand> {
+        info!("Battle: input action: {:?}", action);
+        // Animations can be sped up or skipped while they're being played.
+        if action == input::Action::ToggleSkipAnimations {
+            let mut settings = settings::get();
+            settings.skip_animations = !settings.skip_animations;
+            settings::set(settings);
+            return Ok(StackCommand::None);
+        }
+        if self.block_timer.is_some() {
+            return Ok(StackCommand::None);
+        }
+        match action {
+            input::Action::EndTurn => {
+                // The turn can be ended only when its button is shown.
+                if self.panel_end_turn.is_some() {
+                    return self.try_end_turn();
+                }
+            }
+            input::Action::NextAgent => self.select_next_agent()?,
+            input::Action::Ability(number) => self.pick_ability(number)?,
+            input::Action::Cancel => self.cancel()?,
+            // Handled above.
+            input::Action::ToggleSkipAnimations => {}
+            // It's held, not pressed: see `update`.
+            input::Action::FastForward => {}
+            input::Action::AgentInfo => {
+                if let Some(popup) = self.popup_agent_info()? {
+                    return Ok(StackCommand::PushPopup(popup));
+                }
+            }
+        }
+        Ok(StackCommand::None)
+    }
+
     fn resize(&mut self, aspect_ratio: f32) {
-        self.gui.resize_if_needed(aspect_ratio);
+        utils::resize_gui(&mut self.gui, aspect_ratio);
     }
 
     fn move_mouse(&mut self, point: Vec2) -> ZResult {
+        self.mouse_point = point;
         let pos = geom::point_to_hex(self.view.tile_size(), point);
         if self.state.map().is_inboard(pos) {
             self.view.show_current_tile_marker(pos);
         } else {
             self.view.hide_current_tile_marker();
         }
+        if settings::with(|s| s.tooltips) {
+            self.update_panel_attack_outcomes(pos)?;
+            self.update_preview(pos);
+        }
         self.gui.move_mouse(point);
         Ok(())
     }
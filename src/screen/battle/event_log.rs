@@ -0,0 +1,110 @@
+//! Human-readable lines for the battle log.
+
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{
+    core::{
+        battle::{
+            event::{AttackMode, Event},
+            event_log::{self, AttackOutcome, Kind},
+            Id, PlayerId, State,
+        },
+        map::PosHex,
+    },
+    lang,
+};
+
+/// Shared between the view, that appends the entries as the events are shown,
+/// and the log panel.
+pub type Log = Rc<RefCell<Vec<Entry>>>;
+
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub text: String,
+
+    /// Tiles to highlight: where the event's objects were when it happened.
+    pub positions: Vec<PosHex>,
+}
+
+fn name(state: &State, id: Id) -> String {
+    lang::object_name(&state.parts().meta.get(id).name)
+}
+
+fn attack_outcome(outcome: &AttackOutcome) -> String {
+    match outcome {
+        AttackOutcome::Miss => lang::tr("log.outcome_miss"),
+        AttackOutcome::Wound(damage) => {
+            lang::tr_with("log.outcome_wound", &[("damage", &damage.0)])
+        }
+        AttackOutcome::Kill => lang::tr("log.outcome_kill"),
+    }
+}
+
+fn text(state: &State, kind: &Kind) -> String {
+    match *kind {
+        Kind::EndBattle { winner_id } => match winner_id {
+            PlayerId(0) => lang::tr("log.won"),
+            _ => lang::tr("log.lost"),
+        },
+        Kind::BeginTurn(player_id) => match player_id {
+            PlayerId(0) => lang::tr("log.your_turn"),
+            _ => lang::tr("log.enemy_turn"),
+        },
+        Kind::MoveTo { id } => lang::tr_with("log.move", &[("actor", &name(state, id))]),
+        Kind::Attack {
+            attacker_id,
+            target_id,
+            ref mode,
+            ref outcome,
+        } => {
+            let attacker = name(state, attacker_id);
+            let target = name(state, target_id);
+            let outcome = attack_outcome(outcome);
+            let id = match mode {
+                AttackMode::Active => "log.attack",
+                AttackMode::Reactive => "log.attack_reaction",
+            };
+            let args = [
+                ("attacker", &attacker as _),
+                ("target", &target as _),
+                ("outcome", &outcome as _),
+            ];
+            lang::tr_with(id, &args)
+        }
+        Kind::Explode { id } => lang::tr_with("log.explode", &[("actor", &name(state, id))]),
+        Kind::UseAbility { id, ability } => {
+            let actor = name(state, id);
+            let ability = lang::ability_title(ability);
+            let args = [("actor", &actor as _), ("ability", &ability as _)];
+            lang::tr_with("log.use_ability", &args)
+        }
+        Kind::UsePassiveAbility { id, ability } => {
+            let actor = name(state, id);
+            let ability = lang::passive_ability_title(ability);
+            let args = [("actor", &actor as _), ("ability", &ability as _)];
+            lang::tr_with("log.use_passive_ability", &args)
+        }
+        Kind::EffectTick { id, effect } => {
+            let target = name(state, id);
+            let effect = lang::effect_title(effect);
+            let args = [("target", &target as _), ("effect", &effect as _)];
+            lang::tr_with("log.effect_tick", &args)
+        }
+        Kind::EffectEnd { id, effect } => {
+            let target = name(state, id);
+            let effect = lang::effect_title(effect);
+            let args = [("target", &target as _), ("effect", &effect as _)];
+            lang::tr_with("log.effect_end", &args)
+        }
+    }
+}
+
+/// Must be called before the event is applied, while all its objects still exist.
+/// Returns `None` for the events that aren't worth a line.
+pub fn describe(state: &State, event: &Event) -> Option<Entry> {
+    let record = event_log::record(state, event)?;
+    Some(Entry {
+        text: text(state, &record.kind),
+        positions: record.positions,
+    })
+}
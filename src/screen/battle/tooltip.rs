@@ -0,0 +1,120 @@
+//! Widgets that pop up next to the mouse cursor when it rests
+//! over the battle map or an ability button.
+
+use crate::{
+    assets,
+    core::{
+        battle::{ability, effect, state, Id, State},
+        map::PosHex,
+    },
+    lang,
+    utils::{self, line_heights},
+    ZResult,
+};
+
+fn build(lines: &[String]) -> ZResult<ui::RcWidget> {
+    let font = assets::get().font;
+    let h = line_heights().small;
+    let mut layout = Box::new(ui::VLayout::new().stretchable(true));
+    for line in lines {
+        let text = ui::Drawable::text(line.as_str(), font);
+        layout.add(Box::new(ui::Label::new(text, h)?));
+    }
+    let layout = utils::add_offsets_and_bg(layout, utils::OFFSET_SMALL)?;
+    Ok(ui::pack(layout))
+}
+
+fn title(text: &str) -> String {
+    lang::tr_with("common.title", &[("title", &text)])
+}
+
+fn list_item(text: &str) -> String {
+    lang::tr_with("common.list_item", &[("item", &text)])
+}
+
+fn passive_abilities_lines(state: &State, id: Id, lines: &mut Vec<String>) {
+    let abilities = match state.parts().passive_abilities.get_opt(id) {
+        Some(abilities) if !abilities.0.is_empty() => &abilities.0,
+        _ => return,
+    };
+    lines.push(lang::tr("info.passive_abilities"));
+    for &ability in abilities {
+        lines.push(list_item(&lang::passive_ability_title(ability)));
+    }
+}
+
+fn effects_lines(state: &State, id: Id, lines: &mut Vec<String>) {
+    let effects = match state.parts().effects.get_opt(id) {
+        Some(effects) if !effects.0.is_empty() => &effects.0,
+        _ => return,
+    };
+    lines.push(lang::tr("info.effects"));
+    for effect in effects {
+        let s = lang::effect_title(effect.effect);
+        let text = match effect.duration {
+            effect::Duration::Forever => s,
+            effect::Duration::Rounds(n) => {
+                let args = [("effect", &s as _), ("rounds", &n as _)];
+                lang::tr_with("info.effect_duration", &args)
+            }
+        };
+        lines.push(list_item(&text));
+    }
+}
+
+fn planned_abilities_lines(state: &State, id: Id, lines: &mut Vec<String>) {
+    let planned = match state.parts().schedule.get_opt(id) {
+        Some(schedule) if !schedule.planned.is_empty() => &schedule.planned,
+        _ => return,
+    };
+    lines.push(lang::tr("tooltip.planned_abilities"));
+    for planned in planned {
+        let ability = lang::ability_title(planned.ability);
+        let args = [
+            ("ability", &ability as _),
+            ("rounds", &planned.rounds.0 as _),
+        ];
+        lines.push(list_item(&lang::tr_with("tooltip.planned_ability", &args)));
+    }
+}
+
+/// The terrain and all the objects that are placed on the tile.
+pub fn tile(state: &State, pos: PosHex) -> ZResult<ui::RcWidget> {
+    let mut lines = vec![title(&lang::tile_type(state.map().tile(pos)))];
+    for id in state::ids_at(state, pos) {
+        lines.push(lang::object_name(&state.parts().meta.get(id).name));
+        passive_abilities_lines(state, id, &mut lines);
+        planned_abilities_lines(state, id, &mut lines);
+    }
+    build(&lines)
+}
+
+pub fn enemy_agent(state: &State, id: Id) -> ZResult<ui::RcWidget> {
+    let parts = state.parts();
+    let name = lang::object_name(&parts.meta.get(id).name);
+    let st = parts.strength.get(id);
+    let strength = format!("{}/{}", st.strength.0, st.base_strength.0);
+    let mut lines = vec![
+        title(&name),
+        format!("{} {}", lang::tr("info.strength"), strength),
+    ];
+    let armor = state::get_armor(state, id);
+    if armor.0 != 0 {
+        lines.push(format!("{} {}", lang::tr("info.armor"), armor.0));
+    }
+    effects_lines(state, id, &mut lines);
+    planned_abilities_lines(state, id, &mut lines);
+    passive_abilities_lines(state, id, &mut lines);
+    build(&lines)
+}
+
+pub fn ability(ability: &ability::RechargeableAbility) -> ZResult<ui::RcWidget> {
+    let mut lines = vec![title(&lang::ability_title(ability.ability))];
+    lines.extend(lang::ability_description(ability.ability));
+    let cooldown = ability.ability.base_cooldown();
+    lines.push(lang::tr_with("info.cooldown", &[("cooldown", &cooldown)]));
+    if let ability::Status::Cooldown(n) = ability.status {
+        lines.push(lang::tr_with("tooltip.cooldown_left", &[("rounds", &n)]));
+    }
+    build(&lines)
+}
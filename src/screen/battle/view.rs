@@ -1,4 +1,4 @@
-use std::{collections::HashMap, default::Default, time::Duration};
+use std::{collections::HashMap, default::Default, fmt::Debug, time::Duration};
 
 use mq::{color::Color, math::Vec2};
 
@@ -8,14 +8,16 @@ use crate::{
     assets,
     core::{
         battle::{
-            self, ability::Ability, command, component::ObjType, execute::hit_chance, movement,
-            state, Id, Jokers, Moves, State, TileType, Turns,
+            self, ability::Ability, attack, command, component::ObjType, movement, preview, state,
+            threat, Id, Jokers, Moves, State, TileType, Turns,
         },
-        map::{self, Dir, Distance, HexMap, PosHex},
+        map::{Dir, HexMap, PosHex},
         utils::roll_dice,
     },
     geom::{self, hex_to_point},
-    screen::battle::visualize,
+    lang,
+    screen::battle::{event_log, visualize},
+    settings,
     utils::time_s,
     ZResult,
 };
@@ -26,9 +28,10 @@ pub enum SelectionMode {
     Ability(Ability),
 }
 
-const TILE_COLOR_WALKABLE: Color = Color::new(0.1, 0.6, 0.1, 0.3);
-const TILE_COLOR_ATTACKABLE: Color = Color::new(0.8, 0.0, 0.0, 0.3);
-const TILE_COLOR_ABILITY: Color = Color::new(0.0, 0.0, 0.9, 0.3);
+/// Tile highlight colors are a part of the settings, so they can be changed at any moment.
+fn highlight_colors() -> settings::HighlightColors {
+    settings::with(|s| s.highlight_colors.clone())
+}
 
 #[derive(Debug, Clone, Default)]
 pub struct Layers {
@@ -36,6 +39,7 @@ pub struct Layers {
     pub blood: Layer,
     pub shadows: Layer,
     pub grass: Layer,
+    pub threats: Layer,
     pub highlighted_tiles: Layer,
     pub selection_marker: Layer,
     pub current_tile_marker: Layer,
@@ -53,6 +57,7 @@ impl Layers {
             self.blood,
             self.shadows,
             self.grass,
+            self.threats,
             self.highlighted_tiles,
             self.selection_marker,
             self.current_tile_marker,
@@ -69,10 +74,6 @@ fn textures() -> &'static assets::Textures {
     &assets::get().textures
 }
 
-pub fn tile_size(map_height: Distance) -> f32 {
-    1.0 / (map_height.0 as f32 * 0.75)
-}
-
 #[derive(Debug)]
 struct DisappearingSprite {
     sprite: Sprite,
@@ -87,6 +88,9 @@ struct Sprites {
     selection_marker: Sprite,
     current_tile_marker: Sprite,
     highlighted_tiles: Vec<Sprite>,
+    preview_tiles: Vec<Sprite>,
+    log_tiles: Vec<Sprite>,
+    threat_tiles: Vec<Sprite>,
     labels: Vec<Sprite>,
     id_to_sprite_map: HashMap<Id, Sprite>,
     id_to_shadow_map: HashMap<Id, Sprite>,
@@ -101,9 +105,9 @@ pub struct MessagesMap {
 }
 
 impl MessagesMap {
-    pub fn new(radius: Distance) -> Self {
+    pub fn new<T: Copy + Debug>(map: &HexMap<T>) -> Self {
         Self {
-            map: HexMap::new(radius),
+            map: HexMap::new_like(map),
             total_duration: Duration::from_secs(0),
         }
     }
@@ -167,14 +171,14 @@ pub struct BattleView {
     scene: Scene,
     sprites: Sprites,
     messages_map: MessagesMap,
+    log: event_log::Log,
 }
 
 impl BattleView {
-    pub fn new(map_radius: Distance) -> ZResult<Self> {
+    pub fn new<T: Copy + Debug>(map: &HexMap<T>) -> ZResult<Self> {
         let layers = Layers::default();
         let scene = Scene::new(layers.clone().sorted());
-        let map_diameter = map::radius_to_diameter(map_radius);
-        let tile_size = tile_size(map_diameter);
+        let tile_size = geom::tile_size(map);
         let make_marker_sprite = |color: Color| -> ZResult<Sprite> {
             let h = tile_size * 2.0 * geom::FLATNESS_COEFFICIENT;
             let mut sprite = Sprite::from_texture(textures().map.selection, h);
@@ -188,6 +192,9 @@ impl BattleView {
             selection_marker,
             current_tile_marker,
             highlighted_tiles: Vec::new(),
+            preview_tiles: Vec::new(),
+            log_tiles: Vec::new(),
+            threat_tiles: Vec::new(),
             labels: Vec::new(),
             id_to_sprite_map: HashMap::new(),
             id_to_shadow_map: HashMap::new(),
@@ -199,7 +206,8 @@ impl BattleView {
             scene,
             layers,
             tile_size,
-            messages_map: MessagesMap::new(map_radius),
+            messages_map: MessagesMap::new(map),
+            log: event_log::Log::default(),
         })
     }
 
@@ -212,6 +220,11 @@ impl BattleView {
         sprite
     }
 
+    /// Entries are added when their events are shown, not when they happen.
+    pub fn log(&self) -> &event_log::Log {
+        &self.log
+    }
+
     pub fn messages_map(&self) -> &MessagesMap {
         &self.messages_map
     }
@@ -230,6 +243,15 @@ impl BattleView {
         self.scene.tick(dtime);
     }
 
+    pub fn set_time_scale(&mut self, time_scale: f32) {
+        self.scene.set_time_scale(time_scale);
+    }
+
+    /// In the instant mode all the animations jump to their end.
+    pub fn set_instant(&mut self, value: bool) {
+        self.scene.set_instant(value);
+    }
+
     pub fn draw(&self) -> ZResult {
         self.scene.draw();
         Ok(())
@@ -337,11 +359,81 @@ impl BattleView {
 
     pub fn remove_highlights(&mut self) {
         self.clean_highlighted_tiles();
+        self.clean_preview();
         self.clean_labels();
     }
 
     fn clean_highlighted_tiles(&mut self) {
-        for sprite in self.sprites.highlighted_tiles.split_off(0) {
+        let sprites = self.sprites.highlighted_tiles.split_off(0);
+        self.hide_tiles(sprites);
+    }
+
+    pub fn clean_preview(&mut self) {
+        let sprites = self.sprites.preview_tiles.split_off(0);
+        self.hide_tiles(sprites);
+    }
+
+    /// Highlights tiles affected by a previewed command.
+    /// The more likely a tile is affected, the brighter it is.
+    pub fn show_preview(&mut self, preview: &preview::Preview) {
+        self.clean_preview();
+        let mut chances: HashMap<PosHex, f32> = HashMap::new();
+        for outcome in preview.outcomes() {
+            for &pos in &outcome.affected_positions {
+                *chances.entry(pos).or_insert(0.0) += outcome.chance;
+            }
+        }
+        let base_color = Color::from(highlight_colors().preview);
+        for (pos, chance) in chances {
+            let a = base_color.a * chance.min(1.0);
+            let color = Color { a, ..base_color };
+            let sprite = self.make_highlighted_tile(pos, color);
+            self.sprites.preview_tiles.push(sprite);
+        }
+    }
+
+    /// Highlights the tiles of the objects mentioned in a battle log entry.
+    pub fn show_log_highlight(&mut self, positions: &[PosHex]) {
+        self.hide_log_highlight();
+        let color = Color::from(highlight_colors().suggestion);
+        for &pos in positions {
+            let sprite = self.make_highlighted_tile(pos, color);
+            self.sprites.log_tiles.push(sprite);
+        }
+    }
+
+    pub fn hide_log_highlight(&mut self) {
+        let sprites = self.sprites.log_tiles.split_off(0);
+        self.hide_tiles(sprites);
+    }
+
+    pub fn show_threats(&mut self, threats: &HexMap<bool>) {
+        self.hide_threats();
+        let size = self.tile_size() * 2.0 * geom::FLATNESS_COEFFICIENT;
+        let color = Color::from(highlight_colors().threat);
+        for pos in threats.iter() {
+            if !threats.tile(pos) {
+                continue;
+            }
+            let mut sprite = Sprite::from_texture(textures().map.white_hex, size);
+            sprite.set_centered(true);
+            sprite.set_color(color);
+            sprite.set_pos(hex_to_point(self.tile_size(), pos));
+            let action = action::Show::new(&self.layers.threats, &sprite).boxed();
+            self.scene.add_action(action);
+            self.sprites.threat_tiles.push(sprite);
+        }
+    }
+
+    pub fn hide_threats(&mut self) {
+        for sprite in self.sprites.threat_tiles.split_off(0) {
+            let action = action::Hide::new(&self.layers.threats, &sprite).boxed();
+            self.scene.add_action(action);
+        }
+    }
+
+    fn hide_tiles(&mut self, sprites: Vec<Sprite>) {
+        for sprite in sprites {
             let color = sprite.color();
             let color = Color { a: 0.0, ..color };
             let action = {
@@ -397,16 +489,34 @@ impl BattleView {
     fn select_ability(&mut self, state: &State, selected_id: Id, ability: Ability) -> ZResult {
         self.remove_highlights();
         let positions = state.map().iter();
+        let color = Color::from(highlight_colors().ability);
         for pos in positions {
             let id = selected_id;
             let command = command::UseAbility { id, pos, ability }.into();
             if battle::check(state, &command).is_ok() {
-                self.highlight_tile(pos, TILE_COLOR_ABILITY)?;
+                self.highlight_tile(pos, color)?;
             }
         }
         Ok(())
     }
 
+    /// Highlights the target tile of a command suggested by the AI.
+    pub fn show_suggestion(&mut self, state: &State, command: &command::Command) -> ZResult {
+        let (pos, text) = match command {
+            command::Command::MoveTo(command) => (command.path.to(), lang::tr("battle.move_here")),
+            command::Command::Attack(command) => {
+                let pos = state.parts().pos.get(command.target_id).0;
+                (pos, lang::tr("battle.attack"))
+            }
+            command::Command::UseAbility(command) => {
+                (command.pos, lang::ability_title(command.ability))
+            }
+            command::Command::Create(_) | command::Command::EndTurn(_) => return Ok(()),
+        };
+        self.highlight_tile(pos, highlight_colors().suggestion.into())?;
+        self.message(pos, &text)
+    }
+
     fn show_selection_marker(&mut self, state: &State, id: Id) {
         let pos = state.parts().pos.get(id).0;
         let point = hex_to_point(self.tile_size(), pos);
@@ -429,6 +539,7 @@ impl BattleView {
     fn show_attackable_tiles(&mut self, state: &State, id: Id) -> ZResult {
         let parts = state.parts();
         let selected_agent_player_id = parts.belongs_to.get(id).0;
+        let color = Color::from(highlight_colors().attackable);
         for target_id in parts.agent.ids() {
             let target_pos = parts.pos.get(target_id).0;
             let target_player_id = parts.belongs_to.get(target_id).0;
@@ -444,7 +555,7 @@ impl BattleView {
                 continue;
             }
             self.show_hit_chance_label(state, id, target_id)?;
-            self.highlight_tile(target_pos, TILE_COLOR_ATTACKABLE)?;
+            self.highlight_tile(target_pos, color)?;
         }
         Ok(())
     }
@@ -459,16 +570,32 @@ impl BattleView {
         if agent.moves == Moves(0) && agent.jokers == Jokers(0) {
             return Ok(());
         }
+        let colors = highlight_colors();
         for pos in map.iter() {
-            if map.tile(pos).cost() > agent.move_points {
+            let tile = map.tile(pos);
+            if !tile.can_stop() || tile.cost() > agent.move_points {
                 continue;
             }
-            self.highlight_tile(pos, TILE_COLOR_WALKABLE)?
+            // Warn about the tiles where the agent can be stopped by a reaction attack.
+            let color = if !threat::reaction_attackers(state, id, pos).is_empty() {
+                colors.walkable_reaction
+            } else if !movement::can_move_on_from(state, id, pos) {
+                colors.walkable_zoc
+            } else {
+                colors.walkable
+            };
+            self.highlight_tile(pos, color.into())?
         }
         Ok(())
     }
 
     fn highlight_tile(&mut self, pos: PosHex, color: Color) -> ZResult {
+        let sprite = self.make_highlighted_tile(pos, color);
+        self.sprites.highlighted_tiles.push(sprite);
+        Ok(())
+    }
+
+    fn make_highlighted_tile(&mut self, pos: PosHex, color: Color) -> Sprite {
         let size = self.tile_size() * 2.0 * geom::FLATNESS_COEFFICIENT;
         let mut sprite = Sprite::from_texture(textures().map.white_hex, size);
         let color_from = Color { a: 0.0, ..color };
@@ -482,15 +609,14 @@ impl BattleView {
             action::ChangeColorTo::new(&sprite, color, time).boxed(),
         ];
         self.scene.add_action(visualize::seq(actions));
-        self.sprites.highlighted_tiles.push(sprite);
-        Ok(())
+        sprite
     }
 
     fn show_hit_chance_label(&mut self, state: &State, attacker_id: Id, target_id: Id) -> ZResult {
         let target_pos = state.parts().pos.get(target_id).0;
-        let chances = hit_chance(state, attacker_id, target_id);
+        let distribution = attack::distribution(state, attacker_id, target_id);
         let pos = hex_to_point(self.tile_size(), target_pos);
-        let text = format!("{}%", chances.1 * 10);
+        let text = format!("{:.0}%", distribution.hit_chance() * 100.0);
         let font = assets::get().font;
         let mut sprite = Sprite::from_text((text.as_str(), font), 0.1);
         sprite.set_pos(pos);
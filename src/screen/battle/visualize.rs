@@ -6,24 +6,25 @@ use mq::{
     math::{Mat2, Vec2},
     texture::Texture2D,
 };
-use zscene::{action, Action, Boxed, Facing, Sprite};
+use zscene::{action, Action, Boxed, Easing, Facing, Sprite};
 
 use crate::{
     assets,
     core::{
         battle::{
             ability::Ability,
+            attack,
             component::{Component, WeaponType},
             effect::{self, Effect},
             event::{self, ActiveEvent, Event},
-            execute::{hit_chance, ApplyPhase},
+            execute::ApplyPhase,
             state, Id, PlayerId, State, Turns,
         },
         map::PosHex,
         utils::roll_dice,
     },
-    geom,
-    screen::battle::view::BattleView,
+    geom, lang,
+    screen::battle::{event_log, view::BattleView},
     utils::time_s,
     ZResult,
 };
@@ -61,6 +62,14 @@ fn action_set_z(layer: &zscene::Layer, sprite: &Sprite, z: f32) -> Box<dyn Actio
     action::Custom::new(closure).boxed()
 }
 
+fn action_add_log_entry(view: &BattleView, entry: event_log::Entry) -> Box<dyn Action> {
+    let log = view.log().clone();
+    let closure = Box::new(move || {
+        log.borrow_mut().push(entry.clone());
+    });
+    action::Custom::new(closure).boxed()
+}
+
 fn hex_pos_to_z(pos: PosHex) -> f32 {
     pos.r as _
 }
@@ -330,16 +339,16 @@ fn up_and_down_move(
     height: f32,
     time: Duration,
 ) -> Box<dyn Action> {
-    let duration_0_25 = time.mul_f32(0.25);
-    let up_fast = Vec2::new(0.0, -height * 0.75);
-    let up_slow = Vec2::new(0.0, -height * 0.25);
-    let down_slow = -up_slow;
-    let down_fast = -up_fast;
+    let duration_0_5 = time.mul_f32(0.5);
+    let up = Vec2::new(0.0, -height);
+    let down = -up;
     seq([
-        action::MoveBy::new(sprite, up_fast, duration_0_25).boxed(),
-        action::MoveBy::new(sprite, up_slow, duration_0_25).boxed(),
-        action::MoveBy::new(sprite, down_slow, duration_0_25).boxed(),
-        action::MoveBy::new(sprite, down_fast, duration_0_25).boxed(),
+        action::MoveBy::new(sprite, up, duration_0_5)
+            .with_easing(Easing::OutQuad)
+            .boxed(),
+        action::MoveBy::new(sprite, down, duration_0_5)
+            .with_easing(Easing::InQuad)
+            .boxed(),
     ])
 }
 
@@ -522,7 +531,11 @@ pub fn visualize(
 }
 
 fn visualize_pre(state: &State, view: &mut BattleView, event: &Event) -> ZResult<Box<dyn Action>> {
-    let mut actions = vec![visualize_event(state, view, &event.active_event)?];
+    let mut actions = Vec::new();
+    if let Some(entry) = event_log::describe(state, event) {
+        actions.push(action_add_log_entry(view, entry));
+    }
+    actions.push(visualize_event(state, view, &event.active_event)?);
     for &(id, ref effects) in &event.instant_effects {
         for effect in effects {
             actions.push(visualize_instant_effect(state, view, id, effect)?);
@@ -578,7 +591,7 @@ fn visualize_event_move_to(
     let sprite = view.id_to_sprite(event.id).clone();
     let mut actions = Vec::new();
     if let [pos] = event.path.tiles() {
-        actions.push(message(view, *pos, "move interrupted")?);
+        actions.push(message(view, *pos, &lang::tr("battle.move_interrupted"))?);
     }
     for step in event.path.steps() {
         let from = view.hex_to_point(step.from);
@@ -629,11 +642,11 @@ fn visualize_event_attack(
     let from = view.hex_to_point(map_from);
     let diff = (to - from) / 2.0;
     let mut actions = Vec::new();
-    let chances = hit_chance(state, id, event.target_id);
-    let attack_msg = format!("{}%", chances.1 * 10);
+    let distribution = attack::distribution(state, id, event.target_id);
+    let attack_msg = format!("{:.0}%", distribution.hit_chance() * 100.0);
     actions.push(attack_message(view, from, &attack_msg)?);
     if event.mode == event::AttackMode::Reactive {
-        actions.push(message(view, map_from, "reaction")?);
+        actions.push(message(view, map_from, &lang::tr("battle.reaction"))?);
     }
     let time_to = time_s(TIME_LUNGE_TO);
     let time_from = time_s(TIME_LUNGE_FROM);
@@ -664,13 +677,13 @@ fn visualize_event_end_battle(
     event: &event::EndBattle,
 ) -> ZResult<Box<dyn Action>> {
     let text = match event.result.winner_id {
-        PlayerId(0) => "YOU WON!",
-        PlayerId(1) => "YOU LOSE!",
+        PlayerId(0) => "battle.won",
+        PlayerId(1) => "battle.lost",
         _ => unreachable!(),
     };
     Ok(seq([
         action::Sleep::new(time_s(1.0)).boxed(),
-        announce(view, text, time_s(4.0))?,
+        announce(view, &lang::tr(text), time_s(4.0))?,
         action::Sleep::new(time_s(1.0)).boxed(),
     ]))
 }
@@ -692,11 +705,11 @@ fn visualize_event_begin_turn(
     event: &event::BeginTurn,
 ) -> ZResult<Box<dyn Action>> {
     let text = match event.player_id {
-        PlayerId(0) => "YOUR TURN",
-        PlayerId(1) => "ENEMY TURN",
+        PlayerId(0) => "battle.your_turn",
+        PlayerId(1) => "battle.enemy_turn",
         _ => unreachable!(),
     };
-    announce(view, text, time_s(1.5))
+    announce(view, &lang::tr(text), time_s(1.5))
 }
 
 fn visualize_event_use_ability_jump(
@@ -866,7 +879,7 @@ fn visualize_event_use_ability(
         _ => action::Empty::new().boxed(),
     };
     let pos = state.parts().pos.get(event.id).0;
-    let text = event.ability.title();
+    let text = lang::ability_title(event.ability);
     let mut actions = Vec::new();
     if let Some(facing) = geom::Facing::from_positions(view.tile_size(), pos, event.pos) {
         let sprite = view.id_to_sprite(event.id).clone();
@@ -900,8 +913,12 @@ fn visualize_event_effect_end(
     event: &event::EffectEnd,
 ) -> ZResult<Box<dyn Action>> {
     let pos = state.parts().pos.get(event.id).0;
-    let s = event.effect.title();
-    message(view, pos, &format!("[{}] ended", s))
+    let s = lang::effect_title(event.effect);
+    message(
+        view,
+        pos,
+        &lang::tr_with("battle.effect_ended", &[("effect", &s)]),
+    )
 }
 
 fn visualize_lasting_effect(
@@ -916,10 +933,14 @@ fn visualize_lasting_effect(
         effect::Lasting::Stun => show_flare(view, pos, [1.0, 1.0, 1.0, 0.7].into())?,
         effect::Lasting::Bloodlust => show_flare(view, pos, [1.0, 0.0, 0.0, 0.5].into())?,
     };
-    let s = timed_effect.effect.title();
+    let s = lang::effect_title(timed_effect.effect);
     Ok(seq([
         action_flare,
-        message(view, pos, &format!("[{}]", s))?,
+        message(
+            view,
+            pos,
+            &lang::tr_with("battle.effect", &[("effect", &s)]),
+        )?,
     ]))
 }
 
@@ -1010,7 +1031,7 @@ fn visualize_effect_kill(
     let pos = state.parts().pos.get(target_id).0;
     Ok(fork(seq([
         show_blood_particles(view, pos, effect.attacker_pos, particles_count)?,
-        message(view, pos, "killed")?,
+        message(view, pos, &lang::tr("battle.killed"))?,
         fork(show_blood_spot(view, pos)?),
         vanish_with_duration(view, target_id, time_s(1.5)),
     ])))
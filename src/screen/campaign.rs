@@ -1,9 +1,9 @@
 use std::{
+    fmt,
     sync::mpsc::{channel, Receiver},
     time::Duration,
 };
 
-use heck::TitleCase;
 use log::info;
 use mq::{math::Vec2, text::Font};
 use ui::{self, Drawable, Gui, Widget};
@@ -17,8 +17,9 @@ use crate::{
             state::BattleResult,
             PlayerId,
         },
-        campaign::{Action, Mode, State},
+        campaign::{Action, Mode, Plan, State},
     },
+    lang,
     screen::{self, Screen, StackCommand},
     utils, ZResult,
 };
@@ -61,12 +62,13 @@ fn build_panel_agents(
 ) -> ZResult<Box<dyn ui::Widget>> {
     let font = assets::get().font;
     let mut layout = Box::new(ui::VLayout::new().stretchable(true));
-    layout.add(label(font, "Your group consists of:")?);
+    layout.add(label(font, &lang::tr("campaign.group"))?);
     layout.add(Box::new(ui::Spacer::new_vertical(line_height_small())));
     for agent_type in agents {
         let mut line = ui::HLayout::new().stretchable(true);
-        let title = agent_type.0.to_title_case();
-        line.add(label(font, &format!("- {}", title))?);
+        let title = lang::object_name(agent_type);
+        let text = lang::tr_with("common.list_item", &[("item", &title)]);
+        line.add(label(font, &text)?);
         let spacer = ui::Spacer::new_horizontal(line_height_small()).stretchable(true);
         line.add(Box::new(spacer));
         {
@@ -90,11 +92,12 @@ fn build_panel_casualties(state: &State) -> ZResult<Option<Box<dyn ui::Widget>>>
         return Ok(None);
     }
     let mut layout = Box::new(ui::VLayout::new());
-    let section_title = "In the last battle you have lost:";
-    layout.add(label(font, section_title)?);
+    let section_title = lang::tr("campaign.casualties");
+    layout.add(label(font, &section_title)?);
     for agent_type in casualties {
-        let text = &format!("- {}", agent_type.0.to_title_case());
-        layout.add(label(font, text)?);
+        let title = lang::object_name(agent_type);
+        let text = lang::tr_with("common.list_item", &[("item", &title)]);
+        layout.add(label(font, &text)?);
         layout.add(Box::new(ui::Spacer::new_vertical(line_height_small())));
     }
     let layout = utils::add_offsets_and_bg_big(layout)?.stretchable(true);
@@ -104,8 +107,8 @@ fn build_panel_casualties(state: &State) -> ZResult<Option<Box<dyn ui::Widget>>>
 fn build_panel_renown(state: &State) -> ZResult<Box<dyn ui::Widget>> {
     let font = assets::get().font;
     let mut layout = Box::new(ui::VLayout::new().stretchable(true));
-    let renown_text = &format!("Your renown is: {}r", state.renown().0);
-    layout.add(label(font, renown_text)?);
+    let renown_text = lang::tr_with("campaign.renown", &[("renown", &state.renown().0)]);
+    layout.add(label(font, &renown_text)?);
     let layout = utils::add_offsets_and_bg_big(layout)?.stretchable(true);
     Ok(Box::new(layout))
 }
@@ -114,20 +117,24 @@ fn build_panel_actions(gui: &mut ui::Gui<Message>, state: &State) -> ZResult<Box
     let font = assets::get().font;
     let h = line_height();
     let mut layout = Box::new(ui::VLayout::new().stretchable(true));
-    layout.add(label(font, "Actions:")?);
+    layout.add(label(font, &lang::tr("campaign.actions"))?);
     layout.add(Box::new(ui::Spacer::new_vertical(line_height_small())));
     for action in state.available_actions() {
         let mut line = ui::HLayout::new().stretchable(true);
         let action_cost = state.action_cost(action);
         let text = match action {
             Action::Recruit { agent_type } => {
-                let title = agent_type.0.to_title_case();
-                format!("Recruit {} for {}r", title, action_cost.0)
+                let title = lang::object_name(agent_type);
+                let args: &[(&str, &dyn fmt::Display)] =
+                    &[("agent", &title), ("cost", &action_cost.0)];
+                lang::tr_with("campaign.recruit", args)
             }
             Action::Upgrade { from, to } => {
-                let from = from.0.to_title_case();
-                let to = to.0.to_title_case();
-                format!("Upgrade {} to {} for {}r", from, to, action_cost.0)
+                let from = lang::object_name(from);
+                let to = lang::object_name(to);
+                let args: &[(&str, &dyn fmt::Display)] =
+                    &[("from", &from), ("to", &to), ("cost", &action_cost.0)];
+                lang::tr_with("campaign.upgrade", args)
             }
         };
         {
@@ -155,11 +162,10 @@ fn build_panel_actions(gui: &mut ui::Gui<Message>, state: &State) -> ZResult<Box
         layout.add(Box::new(ui::Spacer::new_vertical(line_height_small())));
     }
     {
-        let text = &format!(
-            "Start battle - {}/{}",
-            state.current_scenario_index() + 1,
-            state.scenarios_count()
-        );
+        let index = state.current_scenario_index() + 1;
+        let count = state.scenarios_count();
+        let args: &[(&str, &dyn fmt::Display)] = &[("index", &index), ("count", &count)];
+        let text = lang::tr_with("campaign.start_battle", args);
         let text = ui::Drawable::text(text, font);
         let command = Message::StartBattle;
         let button = ui::Button::new(text, h, gui.sender(), command)?.stretchable(true);
@@ -186,8 +192,7 @@ pub struct Campaign {
 }
 
 impl Campaign {
-    pub fn new() -> ZResult<Self> {
-        let campaign_plan = assets::get().campaign_plan.clone();
+    pub fn new(campaign_plan: Plan) -> ZResult<Self> {
         let agent_campaign_info = assets::get().agent_campaign_info.clone();
         let state = State::new(campaign_plan, agent_campaign_info);
         let gui = basic_gui()?;
@@ -238,11 +243,11 @@ impl Campaign {
     }
 
     fn set_mode_won(&mut self) -> ZResult {
-        self.add_label_central_message("You have won!")
+        self.add_label_central_message(&lang::tr("campaign.won"))
     }
 
     fn set_mode_failed(&mut self) -> ZResult {
-        self.add_label_central_message("You have failed!")
+        self.add_label_central_message(&lang::tr("campaign.failed"))
     }
 
     fn clean_ui(&mut self) -> ZResult {
@@ -334,7 +339,7 @@ impl Screen for Campaign {
                 if self.state.mode() == Mode::PreparingForBattle {
                     let (sender, receiver) = channel();
                     self.receiver_exit_confirmation = Some(receiver);
-                    let screen = screen::Confirm::from_line("Abandon the campaign?", sender)?;
+                    let screen = screen::Confirm::from_line(&lang::tr("campaign.abandon"), sender)?;
                     Ok(StackCommand::PushPopup(Box::new(screen)))
                 } else {
                     Ok(StackCommand::Pop)
@@ -355,7 +360,7 @@ impl Screen for Campaign {
     }
 
     fn resize(&mut self, aspect_ratio: f32) {
-        self.gui.resize_if_needed(aspect_ratio);
+        utils::resize_gui(&mut self.gui, aspect_ratio);
     }
 
     fn move_mouse(&mut self, pos: Vec2) -> ZResult {
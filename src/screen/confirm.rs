@@ -7,7 +7,7 @@ use mq::math::Vec2;
 use ui::{self, Gui, Widget};
 
 use crate::{
-    assets,
+    assets, input, lang,
     screen::{Screen, StackCommand},
     utils, ZResult,
 };
@@ -52,15 +52,15 @@ impl Confirm {
         let h = utils::line_heights().big;
         let mut layout = Box::new(ui::VLayout::new());
         let spacer = || Box::new(ui::Spacer::new_vertical(h * 0.5));
-        let button = |line, message| -> ZResult<_> {
+        let button = |line: String, message| -> ZResult<_> {
             let text = ui::Drawable::text(line, font);
             let b = ui::Button::new(text, h, gui.sender(), message)?.stretchable(true);
             Ok(b)
         };
         let button_width = widget.rect().w / 3.0;
-        let mut yes = button("yes", Message::Yes)?;
+        let mut yes = button(lang::tr("common.yes"), Message::Yes)?;
         yes.stretch(button_width);
-        let mut no = button("no", Message::No)?;
+        let mut no = button(lang::tr("common.no"), Message::No)?;
         no.stretch(button_width);
         let spacer_width = widget.rect().w - yes.rect().w - no.rect().w;
         let mut line_layout = ui::HLayout::new();
@@ -101,8 +101,20 @@ impl Screen for Confirm {
         }
     }
 
+    fn input_action(&mut self, action: input::Action) -> ZResult<StackCommand> {
+        match action {
+            input::Action::Cancel => {
+                self.sender
+                    .send(Message::No)
+                    .expect("Can't report back the result");
+                Ok(StackCommand::Pop)
+            }
+            _ => Ok(StackCommand::None),
+        }
+    }
+
     fn resize(&mut self, aspect_ratio: f32) {
-        self.gui.resize_if_needed(aspect_ratio);
+        utils::resize_gui(&mut self.gui, aspect_ratio);
     }
 
     fn move_mouse(&mut self, pos: Vec2) -> ZResult {
@@ -0,0 +1,586 @@
+use std::{
+    fmt, fs,
+    sync::mpsc::{channel, Receiver},
+    time::Duration,
+};
+
+use log::info;
+use mq::{color::Color, math::Vec2};
+use ui::{self, Gui, Widget};
+use zscene::Sprite;
+
+use crate::{
+    assets,
+    core::{
+        battle::{
+            component::ObjType,
+            scenario::{self, BattleType, Line, Object, ObjectsGroup, Scenario},
+            state::BattleResult,
+            PlayerId, TileType,
+        },
+        map::{Distance, HexMap, PosHex},
+    },
+    geom::{self, hex_to_point},
+    lang,
+    screen::{self, Screen, StackCommand},
+    utils, ZResult,
+};
+
+/// Where the edited scenario is saved to and loaded from.
+const SCENARIO_PATH: &str = "editor_scenario.ron";
+
+const MAP_RADIUS_MIN: Distance = Distance(3);
+const MAP_RADIUS_MAX: Distance = Distance(10);
+
+const COLOR_OWNER_PLAYER: Color = Color::new(0.0, 0.0, 1.0, 0.4);
+const COLOR_OWNER_ENEMY: Color = Color::new(1.0, 0.0, 0.0, 0.4);
+const COLOR_HOVERED_TILE: Color = Color::new(0.0, 0.0, 0.0, 0.5);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Brush {
+    Tile(TileType),
+    Object,
+    Erase,
+}
+
+#[derive(Clone, Debug)]
+enum Message {
+    Exit,
+    SetBrush(Brush),
+    PrevTypename,
+    NextTypename,
+    ToggleOwner,
+    ToggleLine,
+    AddGroup,
+    ClearGroups,
+    ShrinkMap,
+    GrowMap,
+    Check,
+    Save,
+    Load,
+    Play,
+}
+
+// The main line height of this screen.
+fn line_height() -> f32 {
+    utils::line_heights().normal
+}
+
+fn line_height_small() -> f32 {
+    line_height() / 8.0
+}
+
+fn label(text: &str) -> ZResult<Box<dyn ui::Widget>> {
+    let text = ui::Drawable::text(text, assets::get().font);
+    Ok(Box::new(ui::Label::new(text, line_height())?))
+}
+
+fn button(gui: &Gui<Message>, text: &str, message: Message) -> ZResult<ui::Button<Message>> {
+    let text = ui::Drawable::text(text, assets::get().font);
+    Ok(ui::Button::new(text, line_height(), gui.sender(), message)?)
+}
+
+fn owner_name(owner: Option<PlayerId>) -> String {
+    let id = match owner {
+        None => "editor.owner_nobody",
+        Some(PlayerId(0)) => "editor.owner_player",
+        Some(_) => "editor.owner_enemy",
+    };
+    lang::tr(id)
+}
+
+fn next_owner(owner: Option<PlayerId>) -> Option<PlayerId> {
+    match owner {
+        None => Some(PlayerId(0)),
+        Some(PlayerId(0)) => Some(PlayerId(1)),
+        Some(_) => None,
+    }
+}
+
+fn owner_color(owner: Option<PlayerId>) -> Option<Color> {
+    match owner {
+        None => None,
+        Some(PlayerId(0)) => Some(COLOR_OWNER_PLAYER),
+        Some(_) => Some(COLOR_OWNER_ENEMY),
+    }
+}
+
+fn line_name(line: Option<Line>) -> String {
+    match line {
+        None => lang::tr("editor.line_anywhere"),
+        Some(line) => lang::tr(&format!("editor.line_{:?}", line).to_lowercase()),
+    }
+}
+
+fn next_line(line: Option<Line>) -> Option<Line> {
+    match line {
+        None => Some(Line::Front),
+        Some(Line::Front) => Some(Line::Middle),
+        Some(Line::Middle) => Some(Line::Back),
+        Some(Line::Back) => Some(Line::Any),
+        Some(Line::Any) => None,
+    }
+}
+
+fn tile_sprite(texture: mq::texture::Texture2D, tile_size: f32, pos: PosHex) -> Sprite {
+    let size = tile_size * 2.0 * geom::FLATNESS_COEFFICIENT;
+    let mut sprite = Sprite::from_texture(texture, size);
+    sprite.set_centered(true);
+    sprite.set_pos(hex_to_point(tile_size, pos));
+    sprite
+}
+
+fn object_sprite(typename: &ObjType, tile_size: f32, pos: PosHex) -> Option<Sprite> {
+    let assets = assets::get();
+    let info = assets.sprites_info.get(typename)?;
+    let frames = assets.sprite_frames.get(typename)?;
+    let mut sprite = Sprite::from_textures(frames, tile_size * 2.0);
+    sprite.set_offset(Vec2::new(0.5 - info.offset_x, 1.0 - info.offset_y));
+    sprite.set_pos(hex_to_point(tile_size, pos));
+    Some(sprite)
+}
+
+/// Paints tiles and places objects to make custom battle scenarios.
+#[derive(Debug)]
+pub struct Editor {
+    scenario: Scenario,
+    map: HexMap<TileType>,
+    tile_size: f32,
+    typenames: Vec<ObjType>,
+    typename_index: usize,
+    owner: Option<PlayerId>,
+    line: Option<Line>,
+    brush: Brush,
+    status: String,
+    sprites: Vec<Sprite>,
+    hovered_tile_marker: Option<Sprite>,
+    gui: Gui<Message>,
+    panels: Vec<ui::RcWidget>,
+    receiver_battle_result: Option<Receiver<Option<BattleResult>>>,
+}
+
+impl Editor {
+    pub fn new() -> ZResult<Self> {
+        let assets = assets::get();
+        let mut typenames: Vec<ObjType> = assets
+            .prototypes
+            .0
+            .keys()
+            .filter(|typename| assets.sprites_info.contains_key(typename))
+            .cloned()
+            .collect();
+        typenames.sort_by(|a, b| a.0.cmp(&b.0));
+        let scenario = Scenario::default();
+        let map = scenario.make_map();
+        let mut this = Self {
+            tile_size: geom::tile_size(&map),
+            scenario,
+            map,
+            typenames,
+            typename_index: 0,
+            owner: Some(PlayerId(0)),
+            line: Some(Line::Front),
+            brush: Brush::Object,
+            status: lang::tr("editor.hello"),
+            sprites: Vec::new(),
+            hovered_tile_marker: None,
+            gui: Gui::new(),
+            panels: Vec::new(),
+            receiver_battle_result: None,
+        };
+        this.update_map()?;
+        Ok(this)
+    }
+
+    /// Is `None` if there're no placeable prototypes (e.g., a mod has removed them).
+    fn typename(&self) -> Option<&ObjType> {
+        self.typenames.get(self.typename_index)
+    }
+
+    fn switch_typename(&mut self, step: usize) {
+        let count = self.typenames.len();
+        if count == 0 {
+            return;
+        }
+        self.typename_index = (self.typename_index + step) % count;
+        self.brush = Brush::Object;
+    }
+
+    /// Recreates everything that depends on the scenario.
+    fn update_map(&mut self) -> ZResult {
+        self.map = self.scenario.make_map();
+        self.tile_size = geom::tile_size(&self.map);
+        let map = &self.map;
+        self.scenario
+            .objects
+            .retain(|object| map.is_inboard(object.pos));
+        self.scenario.tiles.retain(|&pos, _| map.is_inboard(pos));
+        self.sprites = self.make_sprites();
+        self.hovered_tile_marker = None;
+        self.update_gui()
+    }
+
+    fn make_sprites(&self) -> Vec<Sprite> {
+        let textures = &assets::get().textures.map;
+        let size = self.tile_size;
+        let mut sprites = Vec::new();
+        for pos in self.map.iter() {
+            let texture = match self.scenario.tiles.get(&pos) {
+                Some(TileType::Rocks) => textures.tile_rocks,
+                Some(TileType::Plain) | None => textures.tile,
+            };
+            sprites.push(tile_sprite(texture, size, pos));
+        }
+        let mut objects = self.scenario.objects.clone();
+        objects.sort_by_key(|object| object.pos.r);
+        for object in &objects {
+            if let Some(color) = owner_color(object.owner) {
+                let mut sprite = tile_sprite(textures.white_hex, size, object.pos);
+                sprite.set_color(color);
+                sprites.push(sprite);
+            }
+            if let Some(sprite) = object_sprite(&object.typename, size, object.pos) {
+                sprites.push(sprite);
+            }
+        }
+        sprites
+    }
+
+    fn update_gui(&mut self) -> ZResult {
+        for mut panel in self.panels.split_off(0).into_iter().map(Some) {
+            utils::remove_widget(&mut self.gui, &mut panel)?;
+        }
+        let panel_tools = self.build_panel_tools()?;
+        let anchor = ui::Anchor(ui::HAnchor::Left, ui::VAnchor::Top);
+        self.add_panel(panel_tools, anchor);
+        let panel_scenario = self.build_panel_scenario()?;
+        let anchor = ui::Anchor(ui::HAnchor::Right, ui::VAnchor::Top);
+        self.add_panel(panel_scenario, anchor);
+        let panel_status = self.build_panel_status()?;
+        let anchor = ui::Anchor(ui::HAnchor::Middle, ui::VAnchor::Bottom);
+        self.add_panel(panel_status, anchor);
+        Ok(())
+    }
+
+    fn add_panel(&mut self, panel: impl ui::Widget + 'static, anchor: ui::Anchor) {
+        let panel = ui::pack(panel);
+        self.gui.add(&panel, anchor);
+        self.panels.push(panel);
+    }
+
+    fn build_panel_tools(&self) -> ZResult<ui::LayersLayout> {
+        let gui = &self.gui;
+        let mut layout = Box::new(ui::VLayout::new().stretchable(true));
+        let space = || Box::new(ui::Spacer::new_vertical(line_height_small()));
+        layout.add(Box::new(
+            button(gui, &lang::tr("editor.menu"), Message::Exit)?.stretchable(true),
+        ));
+        layout.add(space());
+        layout.add(label(&lang::tr("editor.tiles"))?);
+        let tiles = [
+            ("editor.tile_plain", TileType::Plain),
+            ("editor.tile_rocks", TileType::Rocks),
+        ];
+        for &(id, tile) in &tiles {
+            let brush = Brush::Tile(tile);
+            let text = lang::tr(id);
+            let mut button = button(gui, &text, Message::SetBrush(brush))?.stretchable(true);
+            button.set_active(self.brush != brush);
+            layout.add(Box::new(button));
+            layout.add(space());
+        }
+        layout.add(label(&lang::tr("editor.objects"))?);
+        {
+            let mut line = ui::HLayout::new().stretchable(true);
+            line.add(Box::new(button(gui, "<", Message::PrevTypename)?));
+            line.add(Box::new(ui::Spacer::new_horizontal(line_height_small())));
+            let title = match self.typename() {
+                Some(typename) => lang::object_name(typename),
+                None => lang::tr("editor.no_objects"),
+            };
+            let message = Message::SetBrush(Brush::Object);
+            let mut button_place = button(gui, &title, message)?.stretchable(true);
+            button_place.set_active(self.brush != Brush::Object);
+            line.add(Box::new(button_place));
+            line.add(Box::new(ui::Spacer::new_horizontal(line_height_small())));
+            line.add(Box::new(button(gui, ">", Message::NextTypename)?));
+            layout.add(Box::new(line));
+            layout.add(space());
+        }
+        let text = lang::tr_with("editor.owner", &[("owner", &owner_name(self.owner))]);
+        let button_owner = button(gui, &text, Message::ToggleOwner)?.stretchable(true);
+        layout.add(Box::new(button_owner));
+        layout.add(space());
+        let text = lang::tr("editor.erase");
+        let mut button_erase = button(gui, &text, Message::SetBrush(Brush::Erase))?;
+        button_erase.set_active(self.brush != Brush::Erase);
+        layout.add(Box::new(button_erase.stretchable(true)));
+        layout.add(space());
+        layout.add(label(&lang::tr("editor.random_groups"))?);
+        let text = lang::tr_with("editor.line", &[("line", &line_name(self.line))]);
+        let button_line = button(gui, &text, Message::ToggleLine)?.stretchable(true);
+        layout.add(Box::new(button_line));
+        layout.add(space());
+        let text = lang::tr("editor.add_group");
+        let button_add = button(gui, &text, Message::AddGroup)?.stretchable(true);
+        layout.add(Box::new(button_add));
+        layout.stretch_to_self();
+        utils::add_offsets_and_bg_big(layout)
+    }
+
+    fn build_panel_scenario(&self) -> ZResult<ui::LayersLayout> {
+        let gui = &self.gui;
+        let mut layout = Box::new(ui::VLayout::new().stretchable(true));
+        let space = || Box::new(ui::Spacer::new_vertical(line_height_small()));
+        {
+            let is_hexagon = self.scenario.map_shape.is_none();
+            let radius = self.scenario.map_radius;
+            let mut line = ui::HLayout::new().stretchable(true);
+            let mut button_shrink = button(gui, "-", Message::ShrinkMap)?;
+            button_shrink.set_active(is_hexagon && radius > MAP_RADIUS_MIN);
+            line.add(Box::new(button_shrink));
+            line.add(Box::new(ui::Spacer::new_horizontal(line_height_small())));
+            line.add(label(&lang::tr_with(
+                "editor.radius",
+                &[("radius", &radius.0)],
+            ))?);
+            let spacer = ui::Spacer::new_horizontal(line_height_small()).stretchable(true);
+            line.add(Box::new(spacer));
+            let mut button_grow = button(gui, "+", Message::GrowMap)?;
+            button_grow.set_active(is_hexagon && radius < MAP_RADIUS_MAX);
+            line.add(Box::new(button_grow));
+            layout.add(Box::new(line));
+            layout.add(space());
+        }
+        let commands = vec![
+            ("editor.check", Message::Check),
+            ("editor.save", Message::Save),
+            ("editor.load", Message::Load),
+            ("editor.play", Message::Play),
+        ];
+        for (id, message) in commands {
+            let button = button(gui, &lang::tr(id), message)?.stretchable(true);
+            layout.add(Box::new(button));
+            layout.add(space());
+        }
+        let groups = &self.scenario.randomized_objects;
+        if !groups.is_empty() {
+            layout.add(label(&lang::tr("editor.random_groups"))?);
+            for group in groups {
+                let typename = lang::object_name(&group.typename);
+                let owner = owner_name(group.owner);
+                let line = line_name(group.line);
+                let args: &[(&str, &dyn fmt::Display)] = &[
+                    ("count", &group.count),
+                    ("typename", &typename),
+                    ("owner", &owner),
+                    ("line", &line),
+                ];
+                layout.add(label(&lang::tr_with("editor.group", args))?);
+            }
+            layout.add(space());
+            let text = lang::tr("editor.clear_groups");
+            let button_clear = button(gui, &text, Message::ClearGroups)?;
+            layout.add(Box::new(button_clear.stretchable(true)));
+        }
+        layout.stretch_to_self();
+        utils::add_offsets_and_bg_big(layout)
+    }
+
+    fn build_panel_status(&self) -> ZResult<ui::Label> {
+        let text = ui::Drawable::text(self.status.as_str(), assets::get().font);
+        Ok(ui::Label::new_with_bg(text, line_height())?)
+    }
+
+    fn paint(&mut self, pos: PosHex) -> ZResult {
+        if !self.map.is_inboard(pos) {
+            return Ok(());
+        }
+        let typename = self.typename().cloned();
+        let scenario = &mut self.scenario;
+        match self.brush {
+            Brush::Tile(TileType::Plain) => {
+                scenario.tiles.remove(&pos);
+            }
+            Brush::Tile(tile) => {
+                scenario.tiles.insert(pos, tile);
+            }
+            Brush::Object => {
+                let typename = match typename {
+                    Some(typename) => typename,
+                    None => return Ok(()),
+                };
+                scenario.objects.retain(|object| object.pos != pos);
+                scenario.objects.push(Object {
+                    owner: self.owner,
+                    typename,
+                    pos,
+                });
+            }
+            Brush::Erase => {
+                let objects_count = scenario.objects.len();
+                scenario.objects.retain(|object| object.pos != pos);
+                if scenario.objects.len() == objects_count {
+                    scenario.tiles.remove(&pos);
+                }
+            }
+        }
+        self.update_map()
+    }
+
+    fn add_group(&mut self) {
+        let typename = match self.typename() {
+            Some(typename) => typename.clone(),
+            None => return,
+        };
+        let (owner, line) = (self.owner, self.line);
+        let groups = &mut self.scenario.randomized_objects;
+        let same_group = groups
+            .iter_mut()
+            .find(|group| group.typename == typename && group.owner == owner && group.line == line);
+        match same_group {
+            Some(group) => group.count += 1,
+            None => groups.push(ObjectsGroup {
+                owner,
+                typename,
+                line,
+                count: 1,
+            }),
+        }
+    }
+
+    fn resize_map(&mut self, delta: i32) -> ZResult {
+        let radius = self.scenario.map_radius.0 + delta;
+        let radius = radius.clamp(MAP_RADIUS_MIN.0, MAP_RADIUS_MAX.0);
+        self.scenario.map_radius = Distance(radius);
+        self.update_map()
+    }
+
+    fn check(&mut self) -> bool {
+        match self.scenario.check() {
+            Ok(()) => {
+                self.status = lang::tr("editor.valid");
+                true
+            }
+            Err(err) => {
+                let error = format!("{:?}", err);
+                self.status = lang::tr_with("editor.invalid", &[("error", &error)]);
+                false
+            }
+        }
+    }
+
+    fn save(&mut self) {
+        let config = ron::ser::PrettyConfig::new();
+        let result = ron::ser::to_string_pretty(&self.scenario, config)
+            .map_err(|err| err.to_string())
+            .and_then(|s| fs::write(SCENARIO_PATH, s).map_err(|err| err.to_string()));
+        self.status = match result {
+            Ok(()) => lang::tr_with("editor.saved", &[("path", &SCENARIO_PATH)]),
+            Err(err) => lang::tr_with("editor.cant_save", &[("error", &err)]),
+        };
+    }
+
+    fn load(&mut self) -> ZResult {
+        let result = fs::read_to_string(SCENARIO_PATH)
+            .map_err(|err| err.to_string())
+            .and_then(|s| ron::de::from_str(&s).map_err(|err| err.to_string()));
+        // `update_map` can't build a map without tiles.
+        let result = result.and_then(|scenario: Scenario| match scenario.try_make_map::<bool>() {
+            Some(_) => Ok(scenario),
+            None => Err(format!("{:?}", scenario::Error::MapIsTooSmall)),
+        });
+        match result {
+            Ok(scenario) => {
+                self.scenario = scenario;
+                self.status = lang::tr_with("editor.loaded", &[("path", &SCENARIO_PATH)]);
+            }
+            Err(err) => self.status = lang::tr_with("editor.cant_load", &[("error", &err)]),
+        }
+        self.update_map()
+    }
+
+    fn play(&mut self) -> ZResult<StackCommand> {
+        if !self.check() {
+            self.update_gui()?;
+            return Ok(StackCommand::None);
+        }
+        let (sender, receiver) = channel();
+        self.receiver_battle_result = Some(receiver);
+        let scenario = self.scenario.clone();
+        let prototypes = assets::get().prototypes.clone();
+        let battle_type = BattleType::Skirmish;
+        let screen = screen::Battle::new(scenario, battle_type, prototypes, sender)?;
+        Ok(StackCommand::PushScreen(Box::new(screen)))
+    }
+}
+
+impl Screen for Editor {
+    fn update(&mut self, _: Duration) -> ZResult<StackCommand> {
+        if utils::try_receive(&self.receiver_battle_result).is_some() {
+            self.receiver_battle_result = None;
+        }
+        Ok(StackCommand::None)
+    }
+
+    fn draw(&self) -> ZResult {
+        for sprite in &self.sprites {
+            sprite.draw();
+        }
+        if let Some(marker) = &self.hovered_tile_marker {
+            marker.draw();
+        }
+        self.gui.draw();
+        Ok(())
+    }
+
+    fn click(&mut self, point: Vec2) -> ZResult<StackCommand> {
+        let message = self.gui.click(point);
+        info!("Editor: click: pos={:?}, message={:?}", point, message);
+        match message {
+            Some(Message::Exit) => return Ok(StackCommand::Pop),
+            Some(Message::SetBrush(brush)) => self.brush = brush,
+            Some(Message::PrevTypename) => {
+                let step = self.typenames.len().saturating_sub(1);
+                self.switch_typename(step);
+            }
+            Some(Message::NextTypename) => self.switch_typename(1),
+            Some(Message::ToggleOwner) => self.owner = next_owner(self.owner),
+            Some(Message::ToggleLine) => self.line = next_line(self.line),
+            Some(Message::AddGroup) => self.add_group(),
+            Some(Message::ClearGroups) => self.scenario.randomized_objects.clear(),
+            Some(Message::ShrinkMap) => return self.resize_map(-1).map(|_| StackCommand::None),
+            Some(Message::GrowMap) => return self.resize_map(1).map(|_| StackCommand::None),
+            Some(Message::Check) => {
+                self.check();
+            }
+            Some(Message::Save) => self.save(),
+            Some(Message::Load) => return self.load().map(|_| StackCommand::None),
+            Some(Message::Play) => return self.play(),
+            None => {
+                let pos = geom::point_to_hex(self.tile_size, point);
+                return self.paint(pos).map(|_| StackCommand::None);
+            }
+        }
+        self.update_gui()?;
+        Ok(StackCommand::None)
+    }
+
+    fn resize(&mut self, aspect_ratio: f32) {
+        utils::resize_gui(&mut self.gui, aspect_ratio);
+    }
+
+    fn move_mouse(&mut self, point: Vec2) -> ZResult {
+        let pos = geom::point_to_hex(self.tile_size, point);
+        self.hovered_tile_marker = if self.map.is_inboard(pos) {
+            let texture = assets::get().textures.map.selection;
+            let mut sprite = tile_sprite(texture, self.tile_size, pos);
+            sprite.set_color(COLOR_HOVERED_TILE);
+            Some(sprite)
+        } else {
+            None
+        };
+        self.gui.move_mouse(point);
+        Ok(())
+    }
+}
@@ -4,7 +4,7 @@ use mq::math::Vec2;
 use ui::{self, Gui, Widget};
 
 use crate::{
-    assets,
+    assets, input, lang,
     screen::{Screen, StackCommand},
     utils, ZResult,
 };
@@ -30,15 +30,23 @@ impl GeneralInfo {
         let label = |text: &str| -> ZResult<_> { Ok(Box::new(label_(text)?)) };
         let label_s = |text: &str| -> ZResult<_> { Ok(Box::new(label_(text)?.stretchable(true))) };
         let spacer = || Box::new(ui::Spacer::new_vertical(h * 0.5));
-        layout.add(label_s(&format!("~~~ {} ~~~", title))?);
+        layout.add(label_s(&lang::tr_with(
+            "common.title",
+            &[("title", &title)],
+        ))?);
         layout.add(spacer());
         for line in lines {
             layout.add(label(line)?);
         }
         layout.add(spacer());
         {
-            let mut button =
-                ui::Button::new(text_("back"), h, gui.sender(), Message::Back)?.stretchable(true);
+            let mut button = ui::Button::new(
+                text_(&lang::tr("common.back")),
+                h,
+                gui.sender(),
+                Message::Back,
+            )?
+            .stretchable(true);
             button.stretch(layout.rect().w / 3.0);
             button.set_stretchable(false);
             layout.add(Box::new(button));
@@ -69,8 +77,15 @@ impl Screen for GeneralInfo {
         }
     }
 
+    fn input_action(&mut self, action: input::Action) -> ZResult<StackCommand> {
+        match action {
+            input::Action::Cancel => Ok(StackCommand::Pop),
+            _ => Ok(StackCommand::None),
+        }
+    }
+
     fn resize(&mut self, aspect_ratio: f32) {
-        self.gui.resize_if_needed(aspect_ratio);
+        utils::resize_gui(&mut self.gui, aspect_ratio);
     }
 
     fn move_mouse(&mut self, pos: Vec2) -> ZResult {
@@ -10,6 +10,7 @@ use ui::{self, Widget};
 use crate::{
     assets,
     core::battle::{scenario, state},
+    lang,
     screen::{self, Screen, StackCommand},
     utils, ZResult,
 };
@@ -22,26 +23,78 @@ enum Message {
     StartInstant,
 
     StartCampaign,
+
+    StartEditor,
+
+    NextLanguage,
+
+    NextScenario,
+
+    NextCampaign,
+
+    Settings,
+}
+
+/// Mods can add more scenarios and campaigns, so the menu lets a player pick them.
+#[derive(Copy, Clone, Debug, Default)]
+struct Selection {
+    scenario: usize,
+    campaign: usize,
 }
 
-fn make_gui() -> ZResult<ui::Gui<Message>> {
-    let font = assets::get().font;
+fn file_stem(file_name: &str) -> &str {
+    file_name.trim_end_matches(".ron")
+}
+
+fn make_gui(selection: Selection) -> ZResult<ui::Gui<Message>> {
+    let assets = assets::get();
+    let font = assets.font;
     let mut gui = ui::Gui::new();
     let h = utils::line_heights().large;
     let space = || Box::new(ui::Spacer::new_vertical(h / 8.0));
-    let button = &mut |text, message| -> ZResult<_> {
+    let button = &mut |text: String, message| -> ZResult<_> {
         let text = ui::Drawable::text(text, font);
         let b = ui::Button::new(text, h, gui.sender(), message)?.stretchable(true);
         Ok(Box::new(b))
     };
     let mut layout = Box::new(ui::VLayout::new().stretchable(true));
-    layout.add(button("demo battle", Message::StartInstant)?);
+    layout.add(button(lang::tr("menu.demo_battle"), Message::StartInstant)?);
+    layout.add(space());
+    if assets.scenarios.len() > 1 {
+        let name = file_stem(&assets.scenarios[selection.scenario].0);
+        let text = lang::tr_with("menu.scenario", &[("scenario", &name)]);
+        layout.add(button(text, Message::NextScenario)?);
+        layout.add(space());
+    }
+    layout.add(button(lang::tr("menu.campaign"), Message::StartCampaign)?);
+    layout.add(space());
+    if assets.campaigns.len() > 1 {
+        let name = file_stem(&assets.campaigns[selection.campaign].0);
+        let text = lang::tr_with("menu.campaign_plan", &[("campaign", &name)]);
+        layout.add(button(text, Message::NextCampaign)?);
+        layout.add(space());
+    }
+    layout.add(button(lang::tr("menu.editor"), Message::StartEditor)?);
     layout.add(space());
-    layout.add(button("campaign", Message::StartCampaign)?);
+    let language = lang::name(&lang::current());
+    let text = lang::tr_with("menu.language", &[("language", &language)]);
+    layout.add(button(text, Message::NextLanguage)?);
+    layout.add(space());
+    layout.add(button(lang::tr("menu.settings"), Message::Settings)?);
     #[cfg(not(target_arch = "wasm32"))] // can't quit WASM
     {
         layout.add(space());
-        layout.add(button("exit", Message::Exit)?);
+        layout.add(button(lang::tr("menu.exit"), Message::Exit)?);
+    }
+    let mods = &assets.mods;
+    if !mods.is_empty() {
+        let text = lang::tr_with("menu.mods", &[("mods", &mods.join(", "))]);
+        let text = ui::Drawable::text(text, font);
+        layout.add(space());
+        layout.add(Box::new(ui::Label::new(
+            text,
+            utils::line_heights().normal,
+        )?));
     }
     layout.stretch_to_self();
     let layout = utils::add_offsets_and_bg_big(layout)?;
@@ -53,15 +106,18 @@ fn make_gui() -> ZResult<ui::Gui<Message>> {
 #[derive(Debug)]
 pub struct MainMenu {
     gui: ui::Gui<Message>,
+    selection: Selection,
     receiver_battle_result: Option<Receiver<Option<state::BattleResult>>>,
 }
 
 // TODO: add the game's version to one of the corners
 impl MainMenu {
     pub fn new() -> ZResult<Self> {
-        let gui = make_gui()?;
+        let selection = Selection::default();
+        let gui = make_gui(selection)?;
         Ok(Self {
             gui,
+            selection,
             receiver_battle_result: None,
         })
     }
@@ -83,7 +139,7 @@ impl Screen for MainMenu {
         match message {
             Some(Message::StartInstant) => {
                 let prototypes = assets::get().prototypes.clone();
-                let scenario = assets::get().demo_scenario.clone();
+                let scenario = assets::get().scenarios[self.selection.scenario].1.clone();
                 let (sender, receiver) = channel();
                 self.receiver_battle_result = Some(receiver);
                 let battle_type = scenario::BattleType::Skirmish;
@@ -91,7 +147,37 @@ impl Screen for MainMenu {
                 Ok(StackCommand::PushScreen(Box::new(screen)))
             }
             Some(Message::StartCampaign) => {
-                let screen = screen::Campaign::new()?;
+                let plan = assets::get().campaigns[self.selection.campaign].1.clone();
+                let screen = screen::Campaign::new(plan)?;
+                Ok(StackCommand::PushScreen(Box::new(screen)))
+            }
+            Some(Message::StartEditor) => {
+                let screen = screen::Editor::new()?;
+                Ok(StackCommand::PushScreen(Box::new(screen)))
+            }
+            Some(Message::NextLanguage) => {
+                let languages = &assets::get().language_list.languages;
+                let current = lang::current();
+                let index = languages.iter().position(|l| *l == current).unwrap_or(0);
+                lang::set_current(&languages[(index + 1) % languages.len()]);
+                // All the labels have to be rendered again in the new language.
+                self.gui = make_gui(self.selection)?;
+                Ok(StackCommand::None)
+            }
+            Some(Message::NextScenario) => {
+                let count = assets::get().scenarios.len();
+                self.selection.scenario = (self.selection.scenario + 1) % count;
+                self.gui = make_gui(self.selection)?;
+                Ok(StackCommand::None)
+            }
+            Some(Message::NextCampaign) => {
+                let count = assets::get().campaigns.len();
+                self.selection.campaign = (self.selection.campaign + 1) % count;
+                self.gui = make_gui(self.selection)?;
+                Ok(StackCommand::None)
+            }
+            Some(Message::Settings) => {
+                let screen = screen::Settings::new()?;
                 Ok(StackCommand::PushScreen(Box::new(screen)))
             }
             Some(Message::Exit) => Ok(StackCommand::Pop),
@@ -100,7 +186,7 @@ impl Screen for MainMenu {
     }
 
     fn resize(&mut self, aspect_ratio: f32) {
-        self.gui.resize_if_needed(aspect_ratio);
+        utils::resize_gui(&mut self.gui, aspect_ratio);
     }
 
     fn move_mouse(&mut self, pos: Vec2) -> ZResult {
@@ -0,0 +1,211 @@
+use std::time::Duration;
+
+use log::trace;
+use mq::math::Vec2;
+use ui::{self, Widget};
+
+use crate::{
+    assets,
+    core::battle::ai::Difficulty,
+    input, lang,
+    screen::{Screen, StackCommand},
+    settings::{self, HighlightColors},
+    utils, ZResult,
+};
+
+const ANIMATION_SPEEDS: &[f32] = &[0.5, 1.0, 1.5, 2.0, 3.0];
+const UI_SCALES: &[f32] = &[0.75, 1.0, 1.25, 1.5];
+
+#[derive(Copy, Clone, Debug)]
+enum Message {
+    Back,
+    NextAnimationSpeed,
+    ToggleSkipAnimations,
+    NextUiScale,
+    ToggleTooltips,
+    ToggleConfirmEndTurn,
+    NextHighlightColors,
+    NextAiDifficulty,
+}
+
+/// Returns the value that goes after the current one, wrapping around.
+fn next_value(values: &[f32], current: f32) -> f32 {
+    let next = values.iter().find(|&&value| value > current + f32::EPSILON);
+    *next.unwrap_or(&values[0])
+}
+
+fn on_off(value: bool) -> String {
+    lang::tr(if value { "settings.on" } else { "settings.off" })
+}
+
+fn highlight_colors_name(colors: &HighlightColors) -> String {
+    let presets = HighlightColors::presets();
+    match presets.iter().find(|(_, preset)| preset == colors) {
+        Some((id, _)) => lang::tr(id),
+        None => lang::tr("settings.colors_custom"),
+    }
+}
+
+fn next_highlight_colors(colors: &HighlightColors) -> HighlightColors {
+    let presets = HighlightColors::presets();
+    let index = presets.iter().position(|(_, preset)| preset == colors);
+    let next_index = index.map_or(0, |index| (index + 1) % presets.len());
+    presets[next_index].1.clone()
+}
+
+fn difficulty_name(difficulty: Difficulty) -> String {
+    lang::tr(&format!("settings.difficulty_{:?}", difficulty).to_lowercase())
+}
+
+fn next_difficulty(difficulty: Difficulty) -> Difficulty {
+    match difficulty {
+        Difficulty::Easy => Difficulty::Normal,
+        Difficulty::Normal => Difficulty::Hard,
+        Difficulty::Hard => Difficulty::Easy,
+    }
+}
+
+fn make_gui() -> ZResult<ui::Gui<Message>> {
+    let font = assets::get().font;
+    let settings = settings::get();
+    let mut gui = ui::Gui::new();
+    let h = utils::line_heights().big;
+    let space = || Box::new(ui::Spacer::new_vertical(h / 8.0));
+    let button = &mut |text: String, message| -> ZResult<_> {
+        let text = ui::Drawable::text(text, font);
+        let b = ui::Button::new(text, h, gui.sender(), message)?.stretchable(true);
+        Ok(Box::new(b))
+    };
+    let speed = settings.animation_speed;
+    let scale = (settings.ui_scale * 100.0).round();
+    let colors = highlight_colors_name(&settings.highlight_colors);
+    let difficulty = difficulty_name(settings.ai_difficulty);
+    let lines = [
+        (
+            lang::tr_with("settings.animation_speed", &[("speed", &speed)]),
+            Message::NextAnimationSpeed,
+        ),
+        (
+            lang::tr_with(
+                "settings.skip_animations",
+                &[("value", &on_off(settings.skip_animations))],
+            ),
+            Message::ToggleSkipAnimations,
+        ),
+        (
+            lang::tr_with("settings.ui_scale", &[("scale", &scale)]),
+            Message::NextUiScale,
+        ),
+        (
+            lang::tr_with(
+                "settings.tooltips",
+                &[("value", &on_off(settings.tooltips))],
+            ),
+            Message::ToggleTooltips,
+        ),
+        (
+            lang::tr_with(
+                "settings.confirm_end_turn",
+                &[("value", &on_off(settings.confirm_end_turn))],
+            ),
+            Message::ToggleConfirmEndTurn,
+        ),
+        (
+            lang::tr_with("settings.highlight_colors", &[("colors", &colors)]),
+            Message::NextHighlightColors,
+        ),
+        (
+            lang::tr_with("settings.ai_difficulty", &[("difficulty", &difficulty)]),
+            Message::NextAiDifficulty,
+        ),
+    ];
+    let mut layout = Box::new(ui::VLayout::new().stretchable(true));
+    for (text, message) in lines.iter().cloned() {
+        layout.add(button(text, message)?);
+        layout.add(space());
+    }
+    layout.add(space());
+    layout.add(button(lang::tr("common.back"), Message::Back)?);
+    layout.stretch_to_self();
+    let layout = utils::add_offsets_and_bg_big(layout)?;
+    let anchor = ui::Anchor(ui::HAnchor::Middle, ui::VAnchor::Middle);
+    gui.add(&ui::pack(layout), anchor);
+    Ok(gui)
+}
+
+#[derive(Debug)]
+pub struct Settings {
+    gui: ui::Gui<Message>,
+}
+
+impl Settings {
+    pub fn new() -> ZResult<Self> {
+        let gui = make_gui()?;
+        Ok(Self { gui })
+    }
+
+    /// Saves the changed settings and shows their new values.
+    fn change(&mut self, f: impl FnOnce(&mut settings::Settings)) -> ZResult {
+        let mut settings = settings::get();
+        f(&mut settings);
+        settings::set(settings);
+        self.gui = make_gui()?;
+        Ok(())
+    }
+}
+
+impl Screen for Settings {
+    fn update(&mut self, _: Duration) -> ZResult<StackCommand> {
+        Ok(StackCommand::None)
+    }
+
+    fn draw(&self) -> ZResult {
+        self.gui.draw();
+        Ok(())
+    }
+
+    fn click(&mut self, pos: Vec2) -> ZResult<StackCommand> {
+        let message = self.gui.click(pos);
+        trace!("Settings: click: pos={:?}, message={:?}", pos, message);
+        match message {
+            Some(Message::Back) => return Ok(StackCommand::Pop),
+            Some(Message::NextAnimationSpeed) => self.change(|s| {
+                s.animation_speed = next_value(ANIMATION_SPEEDS, s.animation_speed);
+            })?,
+            Some(Message::ToggleSkipAnimations) => {
+                self.change(|s| s.skip_animations = !s.skip_animations)?
+            }
+            Some(Message::NextUiScale) => self.change(|s| {
+                s.ui_scale = next_value(UI_SCALES, s.ui_scale);
+            })?,
+            Some(Message::ToggleTooltips) => self.change(|s| s.tooltips = !s.tooltips)?,
+            Some(Message::ToggleConfirmEndTurn) => {
+                self.change(|s| s.confirm_end_turn = !s.confirm_end_turn)?
+            }
+            Some(Message::NextHighlightColors) => self.change(|s| {
+                s.highlight_colors = next_highlight_colors(&s.highlight_colors);
+            })?,
+            Some(Message::NextAiDifficulty) => self.change(|s| {
+                s.ai_difficulty = next_difficulty(s.ai_difficulty);
+            })?,
+            None => {}
+        }
+        Ok(StackCommand::None)
+    }
+
+    fn input_action(&mut self, action: input::Action) -> ZResult<StackCommand> {
+        match action {
+            input::Action::Cancel => Ok(StackCommand::Pop),
+            _ => Ok(StackCommand::None),
+        }
+    }
+
+    fn resize(&mut self, aspect_ratio: f32) {
+        utils::resize_gui(&mut self.gui, aspect_ratio);
+    }
+
+    fn move_mouse(&mut self, pos: Vec2) -> ZResult {
+        self.gui.move_mouse(pos);
+        Ok(())
+    }
+}
@@ -0,0 +1,220 @@
+//! Player's preferences, `settings.ron`.
+//!
+//! The defaults are taken from the assets and the changed settings
+//! are saved to the working directory, next to the editor's scenario.
+
+use std::{ops::RangeInclusive, sync::Mutex};
+
+use log::{info, warn};
+use mq::color::Color;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+use crate::{core::battle::ai::Difficulty, error::ZError, ZResult};
+
+const SETTINGS_PATH: &str = "settings.ron";
+
+const ANIMATION_SPEED_RANGE: RangeInclusive<f32> = 0.1..=10.0;
+const UI_SCALE_RANGE: RangeInclusive<f32> = 0.5..=2.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Rgba(pub f32, pub f32, pub f32, pub f32);
+
+impl From<Rgba> for Color {
+    fn from(Rgba(r, g, b, a): Rgba) -> Self {
+        Color::new(r, g, b, a)
+    }
+}
+
+/// Colors of the battle map's tile highlights.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HighlightColors {
+    pub walkable: Rgba,
+    pub walkable_reaction: Rgba,
+    pub walkable_zoc: Rgba,
+    pub attackable: Rgba,
+    pub ability: Rgba,
+    pub suggestion: Rgba,
+    pub preview: Rgba,
+    pub threat: Rgba,
+}
+
+impl Default for HighlightColors {
+    fn default() -> Self {
+        Self {
+            walkable: Rgba(0.1, 0.6, 0.1, 0.3),
+            walkable_reaction: Rgba(0.8, 0.5, 0.0, 0.4),
+            walkable_zoc: Rgba(0.1, 0.3, 0.6, 0.4),
+            attackable: Rgba(0.8, 0.0, 0.0, 0.3),
+            ability: Rgba(0.0, 0.0, 0.9, 0.3),
+            suggestion: Rgba(0.9, 0.8, 0.0, 0.5),
+            preview: Rgba(0.9, 0.4, 0.0, 0.5),
+            threat: Rgba(0.6, 0.0, 0.6, 0.25),
+        }
+    }
+}
+
+impl HighlightColors {
+    /// Brighter colors that don't rely on telling red and green apart.
+    pub fn high_contrast() -> Self {
+        Self {
+            walkable: Rgba(0.0, 0.4, 1.0, 0.45),
+            walkable_reaction: Rgba(1.0, 0.8, 0.0, 0.6),
+            walkable_zoc: Rgba(0.0, 0.9, 0.9, 0.55),
+            attackable: Rgba(1.0, 0.0, 0.5, 0.5),
+            ability: Rgba(0.5, 0.0, 1.0, 0.5),
+            suggestion: Rgba(1.0, 1.0, 0.0, 0.7),
+            preview: Rgba(1.0, 0.5, 0.0, 0.7),
+            threat: Rgba(0.3, 0.0, 0.3, 0.4),
+        }
+    }
+
+    /// Built-in color schemes with the ids of their names.
+    pub fn presets() -> Vec<(&'static str, Self)> {
+        vec![
+            ("settings.colors_default", Self::default()),
+            ("settings.colors_high_contrast", Self::high_contrast()),
+        ]
+    }
+}
+
+/// Missing fields keep their default values.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub font: String,
+
+    /// `None` means the fallback language.
+    pub language: Option<String>,
+
+    /// How much faster than normal the battle animations are played.
+    pub animation_speed: f32,
+
+    /// The battle animations jump to their end at once.
+    pub skip_animations: bool,
+
+    pub ui_scale: f32,
+
+    /// Show the info that pops up when the mouse hovers over the battle map.
+    pub tooltips: bool,
+
+    pub confirm_end_turn: bool,
+
+    pub highlight_colors: HighlightColors,
+
+    pub ai_difficulty: Difficulty,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            font: "OpenSans-Regular.ttf".into(),
+            language: None,
+            animation_speed: 1.0,
+            skip_animations: false,
+            ui_scale: 1.0,
+            tooltips: true,
+            confirm_end_turn: false,
+            highlight_colors: HighlightColors::default(),
+            ai_difficulty: Difficulty::default(),
+        }
+    }
+}
+
+impl Settings {
+    /// Fixes the values that the game can't use, e.g. after a manual edit of `settings.ron`.
+    fn sanitize(&mut self) {
+        let defaults = Self::default();
+        self.animation_speed = sanitize_f32(
+            "animation_speed",
+            self.animation_speed,
+            defaults.animation_speed,
+            ANIMATION_SPEED_RANGE,
+        );
+        self.ui_scale = sanitize_f32("ui_scale", self.ui_scale, defaults.ui_scale, UI_SCALE_RANGE);
+    }
+}
+
+/// Replaces NaNs and infinities with the default value and clamps the rest into the range.
+fn sanitize_f32(name: &str, value: f32, default: f32, range: RangeInclusive<f32>) -> f32 {
+    let sanitized = if value.is_finite() {
+        value.clamp(*range.start(), *range.end())
+    } else {
+        default
+    };
+    if sanitized != value {
+        warn!("settings: Bad {} {}, using {}", name, value, sanitized);
+    }
+    sanitized
+}
+
+static SETTINGS: Lazy<Mutex<Settings>> = Lazy::new(|| Mutex::new(Settings::default()));
+
+/// Clones all the settings, e.g. to change some of them and `set` them back.
+pub fn get() -> Settings {
+    SETTINGS.lock().unwrap().clone()
+}
+
+/// Reads the settings without cloning them, e.g. `settings::with(|s| s.ui_scale)`.
+///
+/// Note: `f` must not access the settings itself.
+pub fn with<T>(f: impl FnOnce(&Settings) -> T) -> T {
+    f(&SETTINGS.lock().unwrap())
+}
+
+/// Applies the new settings and saves them to disk.
+pub fn set(settings: Settings) {
+    *SETTINGS.lock().unwrap() = settings;
+    if let Err(err) = save() {
+        warn!("settings: Can't save: {}", err);
+    }
+}
+
+fn parse(s: &str, path: &str) -> ZResult<Settings> {
+    ron::de::from_str(s).map_err(|err| ZError::from_ron_de_error(err, path.into()))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read_saved() -> ZResult<Option<String>> {
+    match std::fs::read_to_string(SETTINGS_PATH) {
+        Ok(s) => Ok(Some(s)),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn read_saved() -> ZResult<Option<String>> {
+    Ok(None)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn save() -> ZResult {
+    let config = ron::ser::PrettyConfig::new();
+    let s = with(|settings| ron::ser::to_string_pretty(settings, config))
+        .expect("Can't serialize the settings");
+    std::fs::write(SETTINGS_PATH, s)?;
+    Ok(())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn save() -> ZResult {
+    Ok(())
+}
+
+pub async fn load() -> ZResult {
+    let mut settings = match read_saved()? {
+        Some(s) => {
+            info!("Loading the saved settings");
+            parse(&s, SETTINGS_PATH)?
+        }
+        None => {
+            let s = mq::file::load_string(SETTINGS_PATH).await?;
+            parse(&s, SETTINGS_PATH)?
+        }
+    };
+    settings.sanitize();
+    *SETTINGS.lock().unwrap() = settings;
+    Ok(())
+}
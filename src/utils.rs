@@ -5,7 +5,7 @@ use mq::{
     math::{Rect, Vec2},
 };
 
-use crate::ZResult;
+use crate::{settings, ZResult};
 
 pub fn time_s(s: f32) -> Duration {
     let ms = s * 1000.0;
@@ -28,6 +28,12 @@ pub fn line_heights() -> LineHeights {
     }
 }
 
+/// Also applies the current UI scale.
+pub fn resize_gui<Message: Clone>(gui: &mut ui::Gui<Message>, aspect_ratio: f32) {
+    gui.set_scale(settings::with(|s| s.ui_scale));
+    gui.resize_if_needed(aspect_ratio);
+}
+
 pub const OFFSET_SMALL: f32 = 0.02;
 pub const OFFSET_BIG: f32 = 0.04;
 
@@ -0,0 +1,228 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use zcomponents::{zcomponents_storage, DenseId};
+
+use crate::parts::{
+    Abilities, Agent, Armor, BelongsTo, Blocker, Effects, Meta, PartsDense, PartsHashed,
+    PartsOrdered, PassiveAbilities, Schedule, Summoner,
+};
+
+const ENTITIES_COUNT: i32 = 200;
+
+/// About as many objects as a battle has.
+const PARTS_ENTITIES_COUNT: i32 = 40;
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug, Hash, Default)]
+pub struct Id(i32);
+
+impl DenseId for Id {
+    fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Pos(i32);
+
+#[derive(Clone, Debug)]
+pub struct Strength(i32);
+
+zcomponents_storage!(Hashed<Id>: {
+    pos: Pos,
+    strength: Strength,
+});
+
+zcomponents_storage!(Ordered<Id>: ordered {
+    pos: Pos,
+    strength: Strength,
+});
+
+zcomponents_storage!(Dense<Id>: ordered {
+    #[dense] pos: Pos,
+    #[dense] strength: Strength,
+});
+
+/// Components that mimic the battle state's `Parts`:
+/// a dozen of them, some with heap-allocated data.
+mod parts {
+    // The values are only cloned, never read.
+    #![allow(dead_code)]
+
+    use zcomponents::zcomponents_storage;
+
+    use super::{Id, Pos, Strength};
+
+    #[derive(Clone, Debug)]
+    pub struct Meta(pub String);
+
+    #[derive(Clone, Debug)]
+    pub struct BelongsTo(pub i32);
+
+    #[derive(Clone, Debug)]
+    pub struct Agent(pub [i32; 12]);
+
+    #[derive(Clone, Debug)]
+    pub struct Armor(pub i32);
+
+    #[derive(Clone, Debug)]
+    pub struct Blocker;
+
+    #[derive(Clone, Debug)]
+    pub struct Abilities(pub Vec<i32>);
+
+    #[derive(Clone, Debug)]
+    pub struct PassiveAbilities(pub Vec<i32>);
+
+    #[derive(Clone, Debug)]
+    pub struct Effects(pub Vec<i32>);
+
+    #[derive(Clone, Debug)]
+    pub struct Schedule(pub Vec<i32>);
+
+    #[derive(Clone, Debug)]
+    pub struct Summoner(pub i32);
+
+    zcomponents_storage!(PartsHashed<Id>: {
+        strength: Strength,
+        armor: Armor,
+        pos: Pos,
+        meta: Meta,
+        belongs_to: BelongsTo,
+        agent: Agent,
+        blocker: Blocker,
+        abilities: Abilities,
+        passive_abilities: PassiveAbilities,
+        effects: Effects,
+        schedule: Schedule,
+        summoner: Summoner,
+    });
+
+    zcomponents_storage!(PartsOrdered<Id>: ordered {
+        strength: Strength,
+        armor: Armor,
+        pos: Pos,
+        meta: Meta,
+        belongs_to: BelongsTo,
+        agent: Agent,
+        blocker: Blocker,
+        abilities: Abilities,
+        passive_abilities: PassiveAbilities,
+        effects: Effects,
+        schedule: Schedule,
+        summoner: Summoner,
+    });
+
+    zcomponents_storage!(PartsDense<Id>: ordered {
+        #[dense] strength: Strength,
+        armor: Armor,
+        #[dense] pos: Pos,
+        #[dense] meta: Meta,
+        #[dense] belongs_to: BelongsTo,
+        #[dense] agent: Agent,
+        blocker: Blocker,
+        abilities: Abilities,
+        passive_abilities: PassiveAbilities,
+        effects: Effects,
+        schedule: Schedule,
+        summoner: Summoner,
+    });
+}
+
+macro_rules! make_storage {
+    ($storage:ident) => {{
+        let mut storage = $storage::new();
+        for i in 0..ENTITIES_COUNT {
+            let id = storage.alloc_id();
+            storage.pos.insert(id, Pos(i));
+            if i % 2 == 0 {
+                storage.strength.insert(id, Strength(i));
+            }
+        }
+        storage
+    }};
+}
+
+macro_rules! bench_storage {
+    ($c:expr, $name:expr, $storage:ident) => {{
+        let make = || make_storage!($storage);
+        let storage = make();
+        let ids = storage.ids_collected();
+        $c.bench_function(&format!("{}/fill", $name), |b| b.iter(make));
+        $c.bench_function(&format!("{}/clone", $name), |b| {
+            b.iter(|| black_box(storage.clone()))
+        });
+        $c.bench_function(&format!("{}/get", $name), |b| {
+            b.iter(|| {
+                let mut sum = 0;
+                for &id in &ids {
+                    sum += storage.pos.get(id).0;
+                    if let Some(strength) = storage.strength.get_opt(id) {
+                        sum += strength.0;
+                    }
+                }
+                black_box(sum)
+            })
+        });
+    }};
+}
+
+/// Only the ordered and dense containers can be queried.
+macro_rules! bench_join {
+    ($c:expr, $name:expr, $storage:ident) => {{
+        let storage = make_storage!($storage);
+        $c.bench_function(&format!("{}/join", $name), |b| {
+            b.iter(|| {
+                let mut sum = 0;
+                for (_, (pos, strength)) in storage.pos.query().join(&storage.strength) {
+                    sum += pos.0 + strength.0;
+                }
+                black_box(sum)
+            })
+        });
+    }};
+}
+
+macro_rules! bench_parts_clone {
+    ($c:expr, $name:expr, $storage:ident) => {{
+        let mut storage = $storage::new();
+        for i in 0..PARTS_ENTITIES_COUNT {
+            let id = storage.alloc_id();
+            storage.strength.insert(id, Strength(i));
+            storage.pos.insert(id, Pos(i));
+            storage.meta.insert(id, Meta(format!("object_{}", i)));
+            storage.belongs_to.insert(id, BelongsTo(i % 2));
+            storage.blocker.insert(id, Blocker);
+            if i % 2 == 0 {
+                storage.agent.insert(id, Agent([i; 12]));
+                storage.abilities.insert(id, Abilities(vec![i; 2]));
+                storage
+                    .passive_abilities
+                    .insert(id, PassiveAbilities(vec![i]));
+                storage.effects.insert(id, Effects(vec![i]));
+                storage.schedule.insert(id, Schedule(Vec::new()));
+            }
+            if i % 4 == 0 {
+                storage.armor.insert(id, Armor(i));
+            }
+            if i % 8 == 0 {
+                storage.summoner.insert(id, Summoner(i));
+            }
+        }
+        $c.bench_function(&format!("{}/parts_clone", $name), |b| {
+            b.iter(|| black_box(storage.clone()))
+        });
+    }};
+}
+
+fn containers(c: &mut Criterion) {
+    bench_storage!(c, "hashed", Hashed);
+    bench_storage!(c, "ordered", Ordered);
+    bench_storage!(c, "dense", Dense);
+    bench_join!(c, "ordered", Ordered);
+    bench_join!(c, "dense", Dense);
+    bench_parts_clone!(c, "hashed", PartsHashed);
+    bench_parts_clone!(c, "ordered", PartsOrdered);
+    bench_parts_clone!(c, "dense", PartsDense);
+}
+
+criterion_group!(benches, containers);
+criterion_main!(benches);
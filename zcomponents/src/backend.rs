@@ -0,0 +1,159 @@
+//! Ways to keep the components of one container.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Debug,
+    hash::Hash,
+};
+
+use crate::changes::{ChangeKind, ChangeLog};
+
+/// Ids that can be used as indices of a `DenseComponentContainer`.
+///
+/// The ids allocated by the storages are small sequential numbers,
+/// so usually it's just `self.0 as usize`.
+pub trait DenseId: Copy {
+    fn index(self) -> usize;
+}
+
+/// Where a `Container` keeps its components.
+///
+/// The backends only store the values: the change tracking
+/// and the rest of the API are implemented once by `Container`.
+pub trait Backend: Default {
+    type Id: Copy + Debug;
+    type Value;
+
+    /// Changes of the components, see `Container::take_changes`.
+    type Log: ChangeLog<Self::Id>
+        + Default
+        + Clone
+        + Debug
+        + IntoIterator<Item = (Self::Id, ChangeKind)>;
+
+    fn get(&self, id: Self::Id) -> Option<&Self::Value>;
+
+    fn get_mut(&mut self, id: Self::Id) -> Option<&mut Self::Value>;
+
+    /// Note: there's no value under this id yet.
+    fn insert(&mut self, id: Self::Id, value: Self::Value);
+
+    /// Note: there's a value under this id.
+    fn remove(&mut self, id: Self::Id);
+
+    fn for_each(&self, f: impl FnMut(Self::Id, &Self::Value));
+}
+
+impl<Id: Hash + Eq + Copy + Debug, V> Backend for HashMap<Id, V> {
+    type Id = Id;
+    type Value = V;
+    type Log = HashMap<Id, ChangeKind>;
+
+    fn get(&self, id: Id) -> Option<&V> {
+        HashMap::get(self, &id)
+    }
+
+    fn get_mut(&mut self, id: Id) -> Option<&mut V> {
+        HashMap::get_mut(self, &id)
+    }
+
+    fn insert(&mut self, id: Id, value: V) {
+        HashMap::insert(self, id, value);
+    }
+
+    fn remove(&mut self, id: Id) {
+        HashMap::remove(self, &id);
+    }
+
+    fn for_each(&self, mut f: impl FnMut(Id, &V)) {
+        for (&id, value) in self {
+            f(id, value);
+        }
+    }
+}
+
+impl<Id: Ord + Copy + Debug, V> Backend for BTreeMap<Id, V> {
+    type Id = Id;
+    type Value = V;
+    type Log = BTreeMap<Id, ChangeKind>;
+
+    fn get(&self, id: Id) -> Option<&V> {
+        BTreeMap::get(self, &id)
+    }
+
+    fn get_mut(&mut self, id: Id) -> Option<&mut V> {
+        BTreeMap::get_mut(self, &id)
+    }
+
+    fn insert(&mut self, id: Id, value: V) {
+        BTreeMap::insert(self, id, value);
+    }
+
+    fn remove(&mut self, id: Id) {
+        BTreeMap::remove(self, &id);
+    }
+
+    fn for_each(&self, mut f: impl FnMut(Id, &V)) {
+        for (&id, value) in self {
+            f(id, value);
+        }
+    }
+}
+
+/// A `Vec` indexed by the ids.
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct Dense<Id, V> {
+    // The ids are stored with the values so that the iterators
+    // can yield references, just like the map-based containers do.
+    pub(crate) slots: Vec<Option<(Id, V)>>,
+}
+
+impl<Id, V> Default for Dense<Id, V> {
+    fn default() -> Self {
+        let slots = Vec::new();
+        Self { slots }
+    }
+}
+
+impl<Id: DenseId + Ord + Debug, V> Backend for Dense<Id, V> {
+    type Id = Id;
+    type Value = V;
+    type Log = BTreeMap<Id, ChangeKind>;
+
+    fn get(&self, id: Id) -> Option<&V> {
+        match self.slots.get(id.index()) {
+            Some(Some((_, value))) => Some(value),
+            _ => None,
+        }
+    }
+
+    fn get_mut(&mut self, id: Id) -> Option<&mut V> {
+        match self.slots.get_mut(id.index()) {
+            Some(Some((_, value))) => Some(value),
+            _ => None,
+        }
+    }
+
+    fn insert(&mut self, id: Id, value: V) {
+        let index = id.index();
+        if index >= self.slots.len() {
+            self.slots.resize_with(index + 1, || None);
+        }
+        self.slots[index] = Some((id, value));
+    }
+
+    fn remove(&mut self, id: Id) {
+        self.slots[id.index()] = None;
+    }
+
+    fn for_each(&self, mut f: impl FnMut(Id, &V)) {
+        for (id, value) in self.slots.iter().flatten() {
+            f(*id, value);
+        }
+    }
+}
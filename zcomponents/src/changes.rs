@@ -0,0 +1,145 @@
+//! Tracking of the components that were inserted, removed or modified.
+
+use std::{
+    collections::{btree_map, hash_map, BTreeMap, HashMap},
+    hash::Hash,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ChangeKind {
+    Inserted,
+    Removed,
+
+    /// The component was replaced or mutably borrowed.
+    /// Note that the value itself may stay the same.
+    Modified,
+}
+
+/// A change of one component of one entity.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Change<Id> {
+    /// Name of the storage's field.
+    pub component: &'static str,
+    pub id: Id,
+    pub kind: ChangeKind,
+}
+
+/// Changes of the whole storage. Every component is listed only once.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Changes<Id> {
+    list: Vec<Change<Id>>,
+}
+
+impl<Id: PartialEq + Copy> Default for Changes<Id> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Id: PartialEq + Copy> Changes<Id> {
+    pub fn new() -> Self {
+        Self { list: Vec::new() }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Change<Id>> {
+        self.list.iter()
+    }
+
+    /// All the changed entities, without duplicates.
+    ///
+    /// Note: Allocates Vec in heap.
+    pub fn ids(&self) -> Vec<Id> {
+        let mut ids = Vec::new();
+        for change in &self.list {
+            if !ids.contains(&change.id) {
+                ids.push(change.id);
+            }
+        }
+        ids
+    }
+
+    pub fn kind(&self, component: &str, id: Id) -> Option<ChangeKind> {
+        self.list
+            .iter()
+            .find(|change| change.component == component && change.id == id)
+            .map(|change| change.kind)
+    }
+
+    /// Adds the changes of one of the storage's containers.
+    pub fn extend(&mut self, component: &'static str, changes: Vec<(Id, ChangeKind)>) {
+        for (id, kind) in changes {
+            self.list.push(Change {
+                component,
+                id,
+                kind,
+            });
+        }
+    }
+}
+
+impl<'a, Id> IntoIterator for &'a Changes<Id> {
+    type Item = &'a Change<Id>;
+    type IntoIter = std::slice::Iter<'a, Change<Id>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.list.iter()
+    }
+}
+
+/// A new change of the component merged with the already recorded one,
+/// so only the difference with the initial state is left.
+/// `None` means that the component is back to its initial state.
+fn merge(old_kind: ChangeKind, kind: ChangeKind) -> Option<ChangeKind> {
+    match (old_kind, kind) {
+        (ChangeKind::Inserted, ChangeKind::Removed) => None,
+        (ChangeKind::Inserted, _) => Some(ChangeKind::Inserted),
+        (ChangeKind::Removed, ChangeKind::Inserted) => Some(ChangeKind::Modified),
+        (_, kind) => Some(kind),
+    }
+}
+
+/// Changes of one container, keyed by the ids.
+pub trait ChangeLog<Id> {
+    /// Merges the change with the one already recorded for this id.
+    fn record(&mut self, id: Id, kind: ChangeKind);
+}
+
+impl<Id: Hash + Eq> ChangeLog<Id> for HashMap<Id, ChangeKind> {
+    fn record(&mut self, id: Id, kind: ChangeKind) {
+        match self.entry(id) {
+            hash_map::Entry::Vacant(entry) => {
+                entry.insert(kind);
+            }
+            hash_map::Entry::Occupied(mut entry) => match merge(*entry.get(), kind) {
+                Some(kind) => {
+                    entry.insert(kind);
+                }
+                None => {
+                    entry.remove();
+                }
+            },
+        }
+    }
+}
+
+impl<Id: Ord> ChangeLog<Id> for BTreeMap<Id, ChangeKind> {
+    fn record(&mut self, id: Id, kind: ChangeKind) {
+        match self.entry(id) {
+            btree_map::Entry::Vacant(entry) => {
+                entry.insert(kind);
+            }
+            btree_map::Entry::Occupied(mut entry) => match merge(*entry.get(), kind) {
+                Some(kind) => {
+                    entry.insert(kind);
+                }
+                None => {
+                    entry.remove();
+                }
+            },
+        }
+    }
+}
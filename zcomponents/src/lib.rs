@@ -84,71 +84,349 @@
 //!
 //! assert!(!storage.is_exist(id0));
 //! ```
+//!
+//! ## Iteration order
+//!
+//! The default storage keeps its components in `HashMap`s,
+//! so `ids()` visits the entities in an unspecified order
+//! that may differ between runs and between two clones of the same storage.
+//!
+//! Mark the storage as `ordered` if the order matters, e.g. for a reproducible
+//! game logic: all its `ids()` iterators will yield the ids in ascending order.
+//! The id type must implement `Ord` then.
+//!
+//! ```rust
+//! use zcomponents::zcomponents_storage;
+//!
+//! #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug, Hash, Default)]
+//! pub struct Id(i32);
+//!
+//! #[derive(Clone, Debug)]
+//! pub struct A;
+//!
+//! zcomponents_storage!(Storage<Id>: ordered {
+//!     a: A,
+//! });
+//!
+//! let mut storage = Storage::new();
+//! let ids: Vec<Id> = (0..100).map(|_| storage.alloc_id()).collect();
+//! for &id in ids.iter().rev() {
+//!     storage.a.insert(id, A);
+//! }
+//! assert_eq!(storage.ids_collected(), ids);
+//! assert_eq!(storage.a.ids_collected(), ids);
+//! ```
+//!
+//! ## Queries
+//!
+//! The containers of the ordered storages can be joined together
+//! to iterate over the entities that have all the listed components.
+//! `left_join` adds an optional component and `query_mut`
+//! gives mutable access to the components of the first container.
+//!
+//! ```rust
+//! use zcomponents::zcomponents_storage;
+//!
+//! #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug, Hash, Default)]
+//! pub struct Id(i32);
+//!
+//! #[derive(Clone, Debug)]
+//! pub struct Pos(i32);
+//!
+//! #[derive(Clone, Debug)]
+//! pub struct Speed(i32);
+//!
+//! #[derive(Clone, Debug)]
+//! pub struct Name(&'static str);
+//!
+//! zcomponents_storage!(Storage<Id>: ordered {
+//!     pos: Pos,
+//!     speed: Speed,
+//!     name: Name,
+//! });
+//!
+//! let mut storage = Storage::new();
+//! let tree = storage.alloc_id();
+//! storage.pos.insert(tree, Pos(0));
+//! storage.name.insert(tree, Name("tree"));
+//! let cat = storage.alloc_id();
+//! storage.pos.insert(cat, Pos(1));
+//! storage.speed.insert(cat, Speed(2));
+//! let ghost = storage.alloc_id();
+//! storage.speed.insert(ghost, Speed(3));
+//!
+//! // Only the cat has both a position and a speed.
+//! let parts = &mut storage;
+//! for (_, (pos, speed)) in parts.pos.query_mut().join(&parts.speed) {
+//!     pos.0 += speed.0;
+//! }
+//! assert_eq!(storage.pos.get(cat).0, 3);
+//!
+//! let names: Vec<_> = storage
+//!     .pos
+//!     .query()
+//!     .left_join(&storage.name)
+//!     .map(|(_, (_, name))| name.map(|name| name.0))
+//!     .collect();
+//! assert_eq!(names, vec![Some("tree"), None]);
+//! ```
+//!
+//! ## Dense components
+//!
+//! By default every component is stored in a map. A component marked with
+//! `#[dense]` is stored in a `Vec` indexed by the ids instead: the lookups
+//! and clones are faster, but it takes memory for every allocated id.
+//! It's a good fit for the components that most of the entities have.
+//! The id type must implement `DenseId` then. The dense containers
+//! iterate in ascending order of the ids and can be joined in queries.
+//!
+//! ```rust
+//! use zcomponents::{zcomponents_storage, DenseId};
+//!
+//! #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug, Hash, Default)]
+//! pub struct Id(i32);
+//!
+//! impl DenseId for Id {
+//!     fn index(self) -> usize {
+//!         self.0 as usize
+//!     }
+//! }
+//!
+//! #[derive(Clone, Debug)]
+//! pub struct Pos(i32);
+//!
+//! #[derive(Clone, Debug)]
+//! pub struct Rare;
+//!
+//! zcomponents_storage!(Storage<Id>: ordered {
+//!     #[dense] pos: Pos,
+//!     rare: Rare,
+//! });
+//!
+//! let mut storage = Storage::new();
+//! let id = storage.alloc_id();
+//! storage.pos.insert(id, Pos(0));
+//! storage.rare.insert(id, Rare);
+//! assert_eq!(storage.pos.query().join(&storage.rare).count(), 1);
+//! ```
+//!
+//! ## Change tracking
+//!
+//! Once `set_change_tracking(true)` is called, the containers remember
+//! the ids of the components that were inserted, removed or mutably
+//! borrowed. `take_changes` returns them and clears the log.
+//! `diff` compares two snapshots of a storage;
+//! it requires all the components to implement `PartialEq`.
+//!
+//! ```rust
+//! use zcomponents::{zcomponents_storage, ChangeKind};
+//!
+//! #[derive(PartialEq, Eq, Clone, Copy, Debug, Hash, Default)]
+//! pub struct Id(i32);
+//!
+//! #[derive(Clone, Debug, PartialEq)]
+//! pub struct Pos(i32);
+//!
+//! zcomponents_storage!(Storage<Id>: {
+//!     pos: Pos,
+//! });
+//!
+//! let mut storage = Storage::new();
+//! let id = storage.alloc_id();
+//! storage.pos.insert(id, Pos(0));
+//! let snapshot = storage.clone();
+//!
+//! storage.set_change_tracking(true);
+//! storage.pos.get_mut(id).0 += 1;
+//! let changes = storage.take_changes();
+//! assert_eq!(changes.ids(), vec![id]);
+//! assert_eq!(changes.kind("pos", id), Some(ChangeKind::Modified));
+//! assert!(storage.take_changes().is_empty());
+//!
+//! assert_eq!(snapshot.diff(&storage).kind("pos", id), Some(ChangeKind::Modified));
+//! ```
+//!
+//! ## Serde
+//!
+//! With the `serde` feature enabled the containers implement
+//! `Serialize` and `Deserialize`, so a storage can derive them too.
+//! The attributes passed before the storage's name are applied to its struct.
+//! The allocated ids and the id counter are serialized as well.
+//!
+//! ```rust,ignore
+//! use serde::{Deserialize, Serialize};
+//! use zcomponents::zcomponents_storage;
+//!
+//! zcomponents_storage!(
+//!     #[derive(Serialize, Deserialize)]
+//!     Storage<Id>: {
+//!         a: A,
+//!     }
+//! );
+//! ```
 
 use std::{
-    collections::{hash_map, HashMap},
+    collections::{btree_map, hash_map, BTreeMap, HashMap},
     default::Default,
     fmt::Debug,
     hash::Hash,
+    slice,
+};
+
+pub use crate::{
+    backend::{Backend, Dense, DenseId},
+    changes::{Change, ChangeKind, ChangeLog, Changes},
+    query::{Append, Components, Join, LeftJoin, Query, Sorted},
 };
 
+mod backend;
+mod changes;
+mod query;
+
+/// Components of one type kept in the backend `B`.
+///
+/// Usually it's used through one of the aliases:
+/// `ComponentContainer`, `OrderedComponentContainer` or `DenseComponentContainer`.
 #[derive(Debug, Clone)]
-pub struct ComponentContainer<Id: Hash + Eq, V> {
-    data: HashMap<Id, V>,
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Container<B: Backend> {
+    data: B,
+
+    #[cfg_attr(feature = "serde", serde(skip))]
+    changes: Option<B::Log>,
 }
 
-impl<Id: Hash + Eq + Copy + Debug, V: Clone> Default for ComponentContainer<Id, V> {
+pub type ComponentContainer<Id, V> = Container<HashMap<Id, V>>;
+
+/// Same as `ComponentContainer`, but keeps the components sorted by their ids.
+///
+/// Iterators returned by `ids` are guaranteed to yield the ids in ascending order.
+pub type OrderedComponentContainer<Id, V> = Container<BTreeMap<Id, V>>;
+
+/// Same as `OrderedComponentContainer`, but keeps the components
+/// in a `Vec` indexed by the ids, so the lookups and clones are cheap.
+///
+/// It takes memory for every id up to the largest one with a component,
+/// so it's good for the components that most of the entities have.
+pub type DenseComponentContainer<Id, V> = Container<Dense<Id, V>>;
+
+impl<B: Backend> Default for Container<B> {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl<Id: Hash + Eq + Copy + Debug, V: Clone> ComponentContainer<Id, V> {
+impl<B: Backend> Container<B> {
     pub fn new() -> Self {
-        let data = HashMap::new();
-        Self { data }
+        let data = B::default();
+        let changes = None;
+        Self { data, changes }
     }
 
-    pub fn get_opt(&self, id: Id) -> Option<&V> {
-        self.data.get(&id)
+    pub fn get_opt(&self, id: B::Id) -> Option<&B::Value> {
+        self.data.get(id)
     }
 
     /// Note: panics if there's no such entity.
-    pub fn get(&self, id: Id) -> &V {
+    pub fn get(&self, id: B::Id) -> &B::Value {
         self.get_opt(id)
             .unwrap_or_else(|| panic!("Can't find {:?} id", id))
     }
 
-    pub fn get_opt_mut(&mut self, id: Id) -> Option<&mut V> {
-        self.data.get_mut(&id)
+    pub fn get_opt_mut(&mut self, id: B::Id) -> Option<&mut B::Value> {
+        if self.changes.is_some() && self.data.get(id).is_some() {
+            self.record(id, ChangeKind::Modified);
+        }
+        self.data.get_mut(id)
     }
 
     /// Note: panics if there's no such entity.
-    pub fn get_mut(&mut self, id: Id) -> &mut V {
+    pub fn get_mut(&mut self, id: B::Id) -> &mut B::Value {
         self.get_opt_mut(id)
             .unwrap_or_else(|| panic!("Can't find {:?} id", id))
     }
 
     /// Store a given data value under a given entity id of a stupid component
     /// if no value is already stored under that entity's id.
-    pub fn insert(&mut self, id: Id, data: V) {
+    pub fn insert(&mut self, id: B::Id, data: B::Value) {
         assert!(self.get_opt(id).is_none());
+        self.record(id, ChangeKind::Inserted);
         self.data.insert(id, data);
     }
 
     /// Note: panics if there's no such entity.
-    pub fn remove(&mut self, id: Id) {
+    pub fn remove(&mut self, id: B::Id) {
         assert!(self.get_opt(id).is_some());
-        self.data.remove(&id);
+        self.record(id, ChangeKind::Removed);
+        self.data.remove(id);
     }
 
-    pub fn ids(&self) -> IdIter<'_, Id, V> {
-        IdIter::new(&self.data)
+    /// Starts or stops recording the inserted, removed
+    /// and mutably borrowed components.
+    pub fn set_change_tracking(&mut self, enabled: bool) {
+        self.changes = if enabled {
+            Some(self.changes.take().unwrap_or_default())
+        } else {
+            None
+        };
+    }
+
+    pub fn is_tracking_changes(&self) -> bool {
+        self.changes.is_some()
+    }
+
+    /// Returns the changes recorded since the previous call.
+    ///
+    /// Every id is listed only once: e.g., a component that was
+    /// inserted and then modified is reported as `Inserted`.
+    /// The ordered and dense containers list the ids in ascending order.
+    pub fn take_changes(&mut self) -> Vec<(B::Id, ChangeKind)> {
+        match &mut self.changes {
+            Some(log) => std::mem::take(log).into_iter().collect(),
+            None => Vec::new(),
+        }
+    }
+
+    fn record(&mut self, id: B::Id, kind: ChangeKind) {
+        if let Some(log) = &mut self.changes {
+            log.record(id, kind);
+        }
     }
 
     /// Note: Allocates Vec in heap.
-    pub fn ids_collected(&self) -> Vec<Id> {
-        self.ids().collect()
+    pub fn ids_collected(&self) -> Vec<B::Id> {
+        let mut ids = Vec::new();
+        self.data.for_each(|id, _| ids.push(id));
+        ids
+    }
+}
+
+impl<B: Backend> Container<B>
+where
+    B::Value: PartialEq,
+{
+    /// Changes that turn this container into the `other` one.
+    ///
+    /// The ordered and dense containers list the ids in ascending order.
+    pub fn diff(&self, other: &Self) -> Vec<(B::Id, ChangeKind)> {
+        let mut log = B::Log::default();
+        self.data.for_each(|id, value| match other.data.get(id) {
+            Some(other_value) if other_value == value => {}
+            Some(_) => log.record(id, ChangeKind::Modified),
+            None => log.record(id, ChangeKind::Removed),
+        });
+        other.data.for_each(|id, _| {
+            if self.data.get(id).is_none() {
+                log.record(id, ChangeKind::Inserted);
+            }
+        });
+        log.into_iter().collect()
+    }
+}
+
+impl<Id: Hash + Eq + Copy + Debug, V> ComponentContainer<Id, V> {
+    pub fn ids(&self) -> IdIter<'_, Id, V> {
+        IdIter::new(&self.data)
     }
 }
 
@@ -175,18 +453,198 @@ impl<'a, Id: Copy + 'a, V> Iterator for IdIter<'a, Id, V> {
     }
 }
 
+impl<Id: Ord + Copy + Debug, V> OrderedComponentContainer<Id, V> {
+    pub fn ids(&self) -> OrderedIdIter<'_, Id, V> {
+        OrderedIdIter::new(&self.data)
+    }
+
+    /// Iterates over the components, see `Query` for the ways to add more of them.
+    pub fn query(&self) -> Query<Components<btree_map::Iter<'_, Id, V>>> {
+        Query::new(self.data.iter())
+    }
+
+    /// Same as `query`, but the components of this container are mutable.
+    ///
+    /// Note: if the changes are tracked, all the components
+    /// of this container are considered modified.
+    pub fn query_mut(&mut self) -> Query<Components<btree_map::IterMut<'_, Id, V>>> {
+        if let Some(log) = &mut self.changes {
+            for &id in self.data.keys() {
+                log.record(id, ChangeKind::Modified);
+            }
+        }
+        Query::new(self.data.iter_mut())
+    }
+}
+
+impl<'a, Id: Ord + 'a, V: 'a> Sorted<'a, Id, V> for OrderedComponentContainer<Id, V>
+where
+    BTreeMap<Id, V>: Backend,
+{
+    type Iter = btree_map::Iter<'a, Id, V>;
+
+    fn sorted_iter(&'a self) -> Self::Iter {
+        self.data.iter()
+    }
+}
+
+/// Yields the ids in ascending order.
+#[derive(Clone, Debug)]
+pub struct OrderedIdIter<'a, Id, V> {
+    iter: btree_map::Keys<'a, Id, V>,
+}
+
+impl<'a, Id: Ord + 'a, V: 'a> OrderedIdIter<'a, Id, V> {
+    pub fn new(map: &'a BTreeMap<Id, V>) -> Self {
+        Self { iter: map.keys() }
+    }
+}
+
+impl<'a, Id: Copy + 'a, V> Iterator for OrderedIdIter<'a, Id, V> {
+    type Item = Id;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().copied()
+    }
+}
+
+impl<Id: DenseId + Ord + Debug, V> DenseComponentContainer<Id, V> {
+    pub fn ids(&self) -> DenseIdIter<'_, Id, V> {
+        DenseIdIter::new(&self.data.slots)
+    }
+
+    /// Iterates over the components, see `Query` for the ways to add more of them.
+    pub fn query(&self) -> Query<Components<DenseIter<'_, Id, V>>> {
+        Query::new(self.sorted_iter())
+    }
+
+    /// Same as `query`, but the components of this container are mutable.
+    ///
+    /// Note: if the changes are tracked, all the components
+    /// of this container are considered modified.
+    pub fn query_mut(&mut self) -> Query<Components<DenseIterMut<'_, Id, V>>> {
+        if let Some(log) = &mut self.changes {
+            for (id, _) in self.data.slots.iter().flatten() {
+                log.record(*id, ChangeKind::Modified);
+            }
+        }
+        Query::new(DenseIterMut {
+            iter: self.data.slots.iter_mut(),
+        })
+    }
+}
+
+impl<'a, Id: 'a, V: 'a> Sorted<'a, Id, V> for DenseComponentContainer<Id, V>
+where
+    Dense<Id, V>: Backend,
+{
+    type Iter = DenseIter<'a, Id, V>;
+
+    fn sorted_iter(&'a self) -> Self::Iter {
+        DenseIter {
+            iter: self.data.slots.iter(),
+        }
+    }
+}
+
+/// Yields the components in ascending order of the ids.
+#[derive(Clone, Debug)]
+pub struct DenseIter<'a, Id, V> {
+    iter: slice::Iter<'a, Option<(Id, V)>>,
+}
+
+impl<'a, Id, V> Iterator for DenseIter<'a, Id, V> {
+    type Item = (&'a Id, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter
+            .find_map(|slot| slot.as_ref().map(|(id, value)| (id, value)))
+    }
+}
+
+/// Same as `DenseIter`, but the components are mutable.
+#[derive(Debug)]
+pub struct DenseIterMut<'a, Id, V> {
+    iter: slice::IterMut<'a, Option<(Id, V)>>,
+}
+
+impl<'a, Id, V> Iterator for DenseIterMut<'a, Id, V> {
+    type Item = (&'a Id, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter
+            .find_map(|slot| slot.as_mut().map(|(id, value)| (&*id, value)))
+    }
+}
+
+/// Yields the ids in ascending order.
+#[derive(Clone, Debug)]
+pub struct DenseIdIter<'a, Id, V> {
+    iter: DenseIter<'a, Id, V>,
+}
+
+impl<'a, Id, V> DenseIdIter<'a, Id, V> {
+    pub fn new(data: &'a [Option<(Id, V)>]) -> Self {
+        let iter = DenseIter { iter: data.iter() };
+        Self { iter }
+    }
+}
+
+impl<'a, Id: Copy + 'a, V> Iterator for DenseIdIter<'a, Id, V> {
+    type Item = Id;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(&id, _)| id)
+    }
+}
+
 #[macro_export]
 macro_rules! zcomponents_storage {
-    ($struct_name:ident<$id_type:ty>: { $($component:ident: $t:ty,)* } ) => {
-        use std::collections::HashMap;
-
+    (
+        $(#[$attr:meta])*
+        $struct_name:ident<$id_type:ty>: {
+            $($(#[$backend:ident])? $component:ident: $t:ty,)*
+        }
+    ) => {
+        $crate::zcomponents_storage!(
+            @impl ComponentContainer, IdIter,
+            $(#[$attr])* $struct_name<$id_type>: { $($(#[$backend])? $component: $t,)* }
+        );
+    };
+    (
+        $(#[$attr:meta])*
+        $struct_name:ident<$id_type:ty>: ordered {
+            $($(#[$backend:ident])? $component:ident: $t:ty,)*
+        }
+    ) => {
+        $crate::zcomponents_storage!(
+            @impl OrderedComponentContainer, OrderedIdIter,
+            $(#[$attr])* $struct_name<$id_type>: { $($(#[$backend])? $component: $t,)* }
+        );
+    };
+    (@container $container:ident, $id_type:ty, $t:ty) => {
+        $crate::$container<$id_type, $t>
+    };
+    (@container $container:ident, $id_type:ty, $t:ty, dense) => {
+        $crate::DenseComponentContainer<$id_type, $t>
+    };
+    (
+        @impl $container:ident, $id_iter:ident,
+        $(#[$attr:meta])*
+        $struct_name:ident<$id_type:ty>: {
+            $($(#[$backend:ident])? $component:ident: $t:ty,)*
+        }
+    ) => {
         #[derive(Clone, Debug)]
+        $(#[$attr])*
         pub struct $struct_name {
             $(
-                pub $component: $crate::ComponentContainer<$id_type, $t>,
+                pub $component: $crate::zcomponents_storage!(
+                    @container $container, $id_type, $t $(, $backend)?
+                ),
             )*
             next_obj_id: $id_type,
-            ids: HashMap<$id_type, ()>,
+            ids: $crate::$container<$id_type, ()>,
         }
 
         #[allow(dead_code)]
@@ -194,10 +652,10 @@ macro_rules! zcomponents_storage {
             pub fn new() -> Self {
                 Self {
                     $(
-                        $component: $crate::ComponentContainer::new(),
+                        $component: Default::default(),
                     )*
                     next_obj_id: Default::default(),
-                    ids: HashMap::new(),
+                    ids: $crate::$container::new(),
                 }
             }
 
@@ -208,8 +666,8 @@ macro_rules! zcomponents_storage {
                 id
             }
 
-            pub fn ids(&self) -> $crate::IdIter<$id_type, ()> {
-                $crate::IdIter::new(&self.ids)
+            pub fn ids(&self) -> $crate::$id_iter<$id_type, ()> {
+                self.ids.ids()
             }
 
             pub fn ids_collected(&self) -> Vec<$id_type> {
@@ -233,6 +691,38 @@ macro_rules! zcomponents_storage {
                 )*
             }
 
+            /// Starts or stops recording the changes of all the components.
+            pub fn set_change_tracking(&mut self, enabled: bool) {
+                $(
+                    self.$component.set_change_tracking(enabled);
+                )*
+            }
+
+            /// Returns the changes recorded since the previous call.
+            pub fn take_changes(&mut self) -> $crate::Changes<$id_type> {
+                let mut changes = $crate::Changes::new();
+                $(
+                    changes.extend(stringify!($component), self.$component.take_changes());
+                )*
+                changes
+            }
+
+            /// Changes that turn this storage into the `other` one.
+            ///
+            /// All the components must implement `PartialEq`.
+            // The `for<'a>` bounds are checked only when the method is used,
+            // so the storages of non-comparable components still compile.
+            pub fn diff(&self, other: &Self) -> $crate::Changes<$id_type>
+            where
+                $(for<'a> $t: PartialEq,)*
+            {
+                let mut changes = $crate::Changes::new();
+                $(
+                    changes.extend(stringify!($component), self.$component.diff(&other.$component));
+                )*
+                changes
+            }
+
             pub fn debug_string(&self, id: $id_type) -> String {
                 let mut s = String::new();
                 $(
@@ -0,0 +1,180 @@
+//! Iteration over the entities that have several components at once.
+//!
+//! Only the ordered and dense containers can be queried: all the joined
+//! containers are walked side by side in one pass, so there're no lookups by id.
+
+use std::{cmp::Ordering, iter::Peekable};
+
+/// Containers that iterate over their components in ascending order of the ids.
+pub trait Sorted<'a, Id: 'a, V: 'a> {
+    type Iter: Iterator<Item = (&'a Id, &'a V)>;
+
+    fn sorted_iter(&'a self) -> Self::Iter;
+}
+
+/// Adds a value to the end of a tuple: `(a, b).append(c) == (a, b, c)`.
+pub trait Append<T> {
+    type Output;
+
+    fn append(self, value: T) -> Self::Output;
+}
+
+macro_rules! impl_append {
+    ($($name:ident),*) => {
+        impl<$($name,)* T> Append<T> for ($($name,)*) {
+            type Output = ($($name,)* T,);
+
+            #[allow(non_snake_case)]
+            fn append(self, value: T) -> Self::Output {
+                let ($($name,)*) = self;
+                ($($name,)* value,)
+            }
+        }
+    };
+}
+
+impl_append!(A);
+impl_append!(A, B);
+impl_append!(A, B, C);
+impl_append!(A, B, C, D);
+impl_append!(A, B, C, D, E);
+impl_append!(A, B, C, D, E, F);
+impl_append!(A, B, C, D, E, F, G);
+
+/// Components of the container that has started the query.
+#[derive(Clone, Debug)]
+pub struct Components<I> {
+    iter: I,
+}
+
+impl<'a, Id: Copy + 'a, V, I: Iterator<Item = (&'a Id, V)>> Iterator for Components<I> {
+    type Item = (Id, (V,));
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(&id, value)| (id, (value,)))
+    }
+}
+
+/// Yields `(id, (component_a, component_b, ...))` tuples
+/// in ascending order of the ids.
+///
+/// Created by `OrderedComponentContainer::query` or `query_mut`.
+#[derive(Clone, Debug)]
+pub struct Query<I> {
+    iter: I,
+}
+
+impl<'a, Id: Ord + Copy + 'a, V, I: Iterator<Item = (&'a Id, V)>> Query<Components<I>> {
+    pub(crate) fn new(iter: I) -> Self {
+        let iter = Components { iter };
+        Self { iter }
+    }
+}
+
+impl<Id: Ord + Copy, T, I: Iterator<Item = (Id, T)>> Query<I> {
+    /// Skips the entities that don't have this component.
+    pub fn join<'a, V: 'a, C: Sorted<'a, Id, V>>(self, container: &'a C) -> Query<Join<C::Iter, I>>
+    where
+        Id: 'a,
+        T: Append<&'a V>,
+    {
+        let iter = Join {
+            left: self.iter,
+            right: container.sorted_iter().peekable(),
+        };
+        Query { iter }
+    }
+
+    /// Yields `None` for the entities that don't have this component.
+    pub fn left_join<'a, V: 'a, C: Sorted<'a, Id, V>>(
+        self,
+        container: &'a C,
+    ) -> Query<LeftJoin<C::Iter, I>>
+    where
+        Id: 'a,
+        T: Append<Option<&'a V>>,
+    {
+        let iter = LeftJoin {
+            left: self.iter,
+            right: container.sorted_iter().peekable(),
+        };
+        Query { iter }
+    }
+}
+
+impl<I: Iterator> Iterator for Query<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+}
+
+/// Skips everything before the `id` and takes its component if there's one.
+///
+/// Note: the ids must be requested in ascending order.
+fn seek<'a, Id: Ord + Copy + 'a, V: 'a, R: Iterator<Item = (&'a Id, &'a V)>>(
+    iter: &mut Peekable<R>,
+    id: Id,
+) -> Option<&'a V> {
+    loop {
+        let &(&next_id, value) = iter.peek()?;
+        match next_id.cmp(&id) {
+            Ordering::Less => {
+                iter.next();
+            }
+            Ordering::Equal => {
+                iter.next();
+                return Some(value);
+            }
+            Ordering::Greater => return None,
+        }
+    }
+}
+
+pub struct Join<R: Iterator, I> {
+    left: I,
+    right: Peekable<R>,
+}
+
+impl<'a, Id, V, T, I, R> Iterator for Join<R, I>
+where
+    Id: Ord + Copy + 'a,
+    V: 'a,
+    T: Append<&'a V>,
+    I: Iterator<Item = (Id, T)>,
+    R: Iterator<Item = (&'a Id, &'a V)>,
+{
+    type Item = (Id, T::Output);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (id, values) = self.left.next()?;
+            if let Some(value) = seek(&mut self.right, id) {
+                return Some((id, values.append(value)));
+            }
+        }
+    }
+}
+
+pub struct LeftJoin<R: Iterator, I> {
+    left: I,
+    right: Peekable<R>,
+}
+
+impl<'a, Id, V, T, I, R> Iterator for LeftJoin<R, I>
+where
+    Id: Ord + Copy + 'a,
+    V: 'a,
+    T: Append<Option<&'a V>>,
+    I: Iterator<Item = (Id, T)>,
+    R: Iterator<Item = (&'a Id, &'a V)>,
+{
+    type Item = (Id, T::Output);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (id, values) = self.left.next()?;
+        let value = seek(&mut self.right, id);
+        Some((id, values.append(value)))
+    }
+}
@@ -0,0 +1,105 @@
+use zcomponents::{zcomponents_storage, ChangeKind};
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug, Hash, Default)]
+pub struct Id(i32);
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pos(i32);
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Name(&'static str);
+
+zcomponents_storage!(Storage<Id>: ordered {
+    pos: Pos,
+    name: Name,
+});
+
+#[test]
+fn not_tracked_by_default() {
+    let mut storage = Storage::new();
+    let id = storage.alloc_id();
+    storage.pos.insert(id, Pos(0));
+    assert!(!storage.pos.is_tracking_changes());
+    assert!(storage.take_changes().is_empty());
+}
+
+#[test]
+fn take_changes() {
+    let mut storage = Storage::new();
+    let a = storage.alloc_id();
+    storage.pos.insert(a, Pos(0));
+    storage.name.insert(a, Name("a"));
+    storage.set_change_tracking(true);
+    let b = storage.alloc_id();
+    storage.pos.insert(b, Pos(1));
+    storage.pos.get_mut(b).0 += 1;
+    storage.pos.get_mut(a).0 += 1;
+    storage.name.remove(a);
+    let changes = storage.take_changes();
+    assert_eq!(changes.kind("pos", a), Some(ChangeKind::Modified));
+    assert_eq!(changes.kind("pos", b), Some(ChangeKind::Inserted));
+    assert_eq!(changes.kind("name", a), Some(ChangeKind::Removed));
+    assert_eq!(changes.kind("name", b), None);
+    let mut ids = changes.ids();
+    ids.sort();
+    assert_eq!(ids, vec![a, b]);
+    assert_eq!(changes.iter().count(), 3);
+    // The log is cleared, but the tracking goes on.
+    assert!(storage.take_changes().is_empty());
+    storage.pos.get_mut(b).0 += 1;
+    assert_eq!(storage.take_changes().ids(), vec![b]);
+}
+
+#[test]
+fn changes_are_merged() {
+    let mut storage = Storage::new();
+    let a = storage.alloc_id();
+    storage.pos.insert(a, Pos(0));
+    storage.set_change_tracking(true);
+    let b = storage.alloc_id();
+    storage.pos.insert(b, Pos(1));
+    storage.remove(b);
+    storage.pos.remove(a);
+    storage.pos.insert(a, Pos(2));
+    let changes = storage.take_changes();
+    assert_eq!(changes.kind("pos", a), Some(ChangeKind::Modified));
+    assert_eq!(changes.ids(), vec![a]);
+}
+
+#[test]
+fn query_mut_marks_everything() {
+    let mut storage = Storage::new();
+    let a = storage.alloc_id();
+    storage.pos.insert(a, Pos(0));
+    let b = storage.alloc_id();
+    storage.pos.insert(b, Pos(1));
+    storage.name.insert(b, Name("b"));
+    storage.set_change_tracking(true);
+    for (_, (_pos, _name)) in storage.pos.query_mut().join(&storage.name) {}
+    let changes = storage.take_changes();
+    assert_eq!(changes.kind("pos", a), Some(ChangeKind::Modified));
+    assert_eq!(changes.kind("pos", b), Some(ChangeKind::Modified));
+    assert_eq!(changes.kind("name", b), None);
+}
+
+#[test]
+fn diff() {
+    let mut old = Storage::new();
+    let a = old.alloc_id();
+    old.pos.insert(a, Pos(0));
+    old.name.insert(a, Name("a"));
+    let b = old.alloc_id();
+    old.pos.insert(b, Pos(1));
+    let mut new = old.clone();
+    new.pos.get_mut(a).0 += 1;
+    new.pos.get_mut(b).0 += 0;
+    new.name.remove(a);
+    new.name.insert(b, Name("b"));
+    let changes = old.diff(&new);
+    assert_eq!(changes.kind("pos", a), Some(ChangeKind::Modified));
+    assert_eq!(changes.kind("pos", b), None);
+    assert_eq!(changes.kind("name", a), Some(ChangeKind::Removed));
+    assert_eq!(changes.kind("name", b), Some(ChangeKind::Inserted));
+    assert_eq!(changes.iter().count(), 3);
+    assert!(new.diff(&new).is_empty());
+}
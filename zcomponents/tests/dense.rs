@@ -0,0 +1,95 @@
+use zcomponents::{zcomponents_storage, ChangeKind, DenseId};
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug, Hash, Default)]
+pub struct Id(i32);
+
+impl DenseId for Id {
+    fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pos(i32);
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Name(&'static str);
+
+zcomponents_storage!(Storage<Id>: ordered {
+    #[dense] pos: Pos,
+    name: Name,
+});
+
+#[test]
+fn basic() {
+    let mut storage = Storage::new();
+    let ids: Vec<Id> = (0..10).map(|_| storage.alloc_id()).collect();
+    for &id in ids.iter().rev().step_by(2) {
+        storage.pos.insert(id, Pos(id.0));
+    }
+    assert_eq!(
+        storage.pos.ids_collected(),
+        vec![ids[1], ids[3], ids[5], ids[7], ids[9]]
+    );
+    assert!(storage.pos.get_opt(ids[0]).is_none());
+    assert!(storage.pos.get_opt(Id(100)).is_none());
+    storage.pos.get_mut(ids[3]).0 = 30;
+    assert_eq!(storage.pos.get(ids[3]), &Pos(30));
+    storage.remove(ids[3]);
+    assert!(!storage.is_exist(ids[3]));
+    assert_eq!(storage.pos.ids().count(), 4);
+}
+
+#[test]
+fn join_with_ordered() {
+    let mut storage = Storage::new();
+    for i in 0..6 {
+        let id = storage.alloc_id();
+        if i % 2 == 0 {
+            storage.pos.insert(id, Pos(i));
+        }
+        if i % 3 == 0 {
+            storage.name.insert(id, Name("x"));
+        }
+    }
+    let joined: Vec<_> = storage
+        .name
+        .query()
+        .join(&storage.pos)
+        .map(|(id, _)| id)
+        .collect();
+    assert_eq!(joined, vec![Id(0)]);
+    let left_joined: Vec<_> = storage
+        .pos
+        .query()
+        .left_join(&storage.name)
+        .map(|(id, (_, name))| (id, name.is_some()))
+        .collect();
+    assert_eq!(
+        left_joined,
+        vec![(Id(0), true), (Id(2), false), (Id(4), false)]
+    );
+    for (_, (pos,)) in storage.pos.query_mut() {
+        pos.0 += 1;
+    }
+    assert_eq!(storage.pos.get(Id(4)), &Pos(5));
+}
+
+#[test]
+fn changes_and_diff() {
+    let mut storage = Storage::new();
+    let a = storage.alloc_id();
+    storage.pos.insert(a, Pos(0));
+    let snapshot = storage.clone();
+    storage.set_change_tracking(true);
+    let b = storage.alloc_id();
+    storage.pos.insert(b, Pos(1));
+    storage.pos.get_mut(a).0 += 1;
+    let changes = storage.take_changes();
+    assert_eq!(changes.kind("pos", a), Some(ChangeKind::Modified));
+    assert_eq!(changes.kind("pos", b), Some(ChangeKind::Inserted));
+    let diff = snapshot.diff(&storage);
+    assert_eq!(diff.kind("pos", a), Some(ChangeKind::Modified));
+    assert_eq!(diff.kind("pos", b), Some(ChangeKind::Inserted));
+    assert_eq!(diff.iter().count(), 2);
+}
@@ -0,0 +1,88 @@
+#![cfg(feature = "serde")]
+
+use serde::{Deserialize, Serialize};
+
+#[derive(
+    Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug, Hash, Default,
+)]
+pub struct Id(i32);
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Pos(i32, i32);
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Name(String);
+
+mod hashed {
+    use serde::{Deserialize, Serialize};
+    use zcomponents::zcomponents_storage;
+
+    use super::{Id, Name, Pos};
+
+    zcomponents_storage!(
+        #[derive(Serialize, Deserialize)]
+        Storage<Id>: {
+            pos: Pos,
+            name: Name,
+        }
+    );
+}
+
+mod ordered {
+    use serde::{Deserialize, Serialize};
+    use zcomponents::zcomponents_storage;
+
+    use super::{Id, Name, Pos};
+
+    zcomponents_storage!(
+        #[derive(Serialize, Deserialize)]
+        Storage<Id>: ordered {
+            pos: Pos,
+            name: Name,
+        }
+    );
+}
+
+#[test]
+fn round_trip() {
+    let mut storage = hashed::Storage::new();
+    let id0 = storage.alloc_id();
+    storage.pos.insert(id0, Pos(0, 1));
+    storage.name.insert(id0, Name("first".into()));
+    let id1 = storage.alloc_id();
+    storage.pos.insert(id1, Pos(2, 3));
+    let id_without_components = storage.alloc_id();
+    let s = ron::ser::to_string(&storage).unwrap();
+    let mut loaded: hashed::Storage = ron::de::from_str(&s).unwrap();
+    let mut ids = loaded.ids_collected();
+    ids.sort();
+    assert_eq!(ids, vec![id0, id1, id_without_components]);
+    assert_eq!(loaded.pos.get(id0), &Pos(0, 1));
+    assert_eq!(loaded.pos.get(id1), &Pos(2, 3));
+    assert_eq!(loaded.name.get(id0), &Name("first".into()));
+    assert!(loaded.name.get_opt(id1).is_none());
+    // The id counter is restored too, so the old ids aren't reused.
+    assert_eq!(loaded.alloc_id(), Id(3));
+}
+
+#[test]
+fn round_trip_ordered() {
+    let mut storage = ordered::Storage::new();
+    for i in 0..10 {
+        let id = storage.alloc_id();
+        storage.pos.insert(id, Pos(i, -i));
+        if i % 2 == 0 {
+            storage.name.insert(id, Name(format!("name {}", i)));
+        }
+    }
+    let s = ron::ser::to_string(&storage).unwrap();
+    let loaded: ordered::Storage = ron::de::from_str(&s).unwrap();
+    assert_eq!(loaded.ids_collected(), storage.ids_collected());
+    assert_eq!(loaded.pos.ids_collected(), storage.pos.ids_collected());
+    assert_eq!(loaded.name.ids_collected(), storage.name.ids_collected());
+    for id in storage.ids() {
+        assert_eq!(loaded.debug_string(id), storage.debug_string(id));
+    }
+    // The ordered storages are always serialized the same way.
+    assert_eq!(ron::ser::to_string(&loaded).unwrap(), s);
+}
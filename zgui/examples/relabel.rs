@@ -0,0 +1,80 @@
+use std::{cell::RefCell, rc::Rc};
+
+use mq::{color::WHITE, text::Font};
+use zgui as ui;
+
+mod common;
+
+#[derive(Clone, Copy, Debug)]
+enum Message {
+    NextLanguage,
+}
+
+const LANGUAGES: &[(&str, &str)] = &[
+    ("Hello!", "Switch the language"),
+    ("Hallo!", "Sprache wechseln"),
+    ("Hola!", "Cambiar el idioma"),
+];
+
+struct Gui {
+    gui: ui::Gui<Message>,
+    // Concrete types are kept to be able to re-render the widgets later.
+    label: Rc<RefCell<ui::Label>>,
+    button: Rc<RefCell<ui::Button<Message>>>,
+}
+
+impl Gui {
+    fn new(font: Font) -> ui::Result<Self> {
+        let mut gui = ui::Gui::new();
+        let (label_text, button_text) = LANGUAGES[0];
+        let text = ui::Drawable::text(label_text, font);
+        let label = Rc::new(RefCell::new(ui::Label::new_with_bg(text, 0.2)?));
+        let anchor = ui::Anchor(ui::HAnchor::Middle, ui::VAnchor::Middle);
+        gui.add(&(label.clone() as ui::RcWidget), anchor);
+        let text = ui::Drawable::text(button_text, font);
+        let button = ui::Button::new(text, 0.2, gui.sender(), Message::NextLanguage)?;
+        let button = Rc::new(RefCell::new(button));
+        let anchor = ui::Anchor(ui::HAnchor::Right, ui::VAnchor::Bottom);
+        gui.add(&(button.clone() as ui::RcWidget), anchor);
+        Ok(Self { gui, label, button })
+    }
+
+    fn set_language(&mut self, font: Font, language: usize) {
+        let (label_text, button_text) = LANGUAGES[language];
+        let text = ui::Drawable::text(label_text, font);
+        self.label.borrow_mut().set_drawable(text);
+        let text = ui::Drawable::text(button_text, font);
+        self.button.borrow_mut().set_drawable(text);
+        // The widgets' sizes have changed, so they must be re-anchored.
+        self.gui.update_layout();
+    }
+}
+
+#[mq::main("ZGui: Relabel Demo")]
+#[macroquad(crate_rename = "mq")]
+async fn main() {
+    let assets = common::Assets::load().await.expect("Can't load assets");
+    let mut gui = Gui::new(assets.font).expect("Can't create the gui");
+    let mut language = 0;
+    loop {
+        // Update the camera and the GUI.
+        let aspect_ratio = common::aspect_ratio();
+        let camera = common::make_and_set_camera(aspect_ratio);
+        gui.gui.resize_if_needed(aspect_ratio);
+        // Handle cursor updates.
+        let pos = common::get_world_mouse_pos(&camera);
+        gui.gui.move_mouse(pos);
+        if mq::input::is_mouse_button_pressed(mq::input::MouseButton::Left) {
+            let message = gui.gui.click(pos);
+            println!("{:?}", message);
+            if let Some(Message::NextLanguage) = message {
+                language = (language + 1) % LANGUAGES.len();
+                gui.set_language(assets.font, language);
+            }
+        }
+        // Draw the GUI.
+        mq::window::clear_background(WHITE);
+        gui.gui.draw();
+        mq::window::next_frame().await;
+    }
+}
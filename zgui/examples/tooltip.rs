@@ -0,0 +1,58 @@
+use std::time::Duration;
+
+use mq::color::WHITE;
+use zgui as ui;
+
+mod common;
+
+#[derive(Clone, Copy, Debug)]
+enum Message {
+    Command,
+}
+
+fn make_tooltip(font: mq::text::Font, text: &str) -> ui::Result<ui::RcWidget> {
+    let text = ui::Drawable::text(text, font);
+    let label = ui::Label::new_with_bg(text, 0.1)?;
+    Ok(ui::pack(label))
+}
+
+fn make_gui(font: mq::text::Font) -> ui::Result<ui::Gui<Message>> {
+    let mut gui = ui::Gui::new();
+    let anchor = ui::Anchor(ui::HAnchor::Right, ui::VAnchor::Bottom);
+    let text = ui::Drawable::text("Button", font);
+    let tooltip = make_tooltip(font, "Does nothing useful")?;
+    let button = ui::Button::new(text, 0.2, gui.sender(), Message::Command)?.with_tooltip(tooltip);
+    gui.add(&ui::pack(button), anchor);
+    Ok(gui)
+}
+
+#[mq::main("ZGui: Tooltip Demo")]
+#[macroquad(crate_rename = "mq")]
+async fn main() {
+    let assets = common::Assets::load().await.expect("Can't load assets");
+    let mut gui = make_gui(assets.font).expect("Can't create the gui");
+    loop {
+        // Update the camera and the GUI.
+        let aspect_ratio = common::aspect_ratio();
+        let camera = common::make_and_set_camera(aspect_ratio);
+        gui.resize_if_needed(aspect_ratio);
+        // Handle cursor updates.
+        let pos = common::get_world_mouse_pos(&camera);
+        gui.move_mouse(pos);
+        if mq::input::is_mouse_button_pressed(mq::input::MouseButton::Left) {
+            let message = gui.click(pos);
+            println!("{:?}", message);
+        }
+        // Show the button's tooltip or a custom one over the empty space.
+        gui.tick(Duration::from_secs_f32(mq::time::get_frame_time()));
+        if gui.needs_tooltip() {
+            let text = format!("Nothing here: ({:.2}, {:.2})", pos.x, pos.y);
+            let tooltip = make_tooltip(assets.font, &text).expect("Can't create the tooltip");
+            gui.show_tooltip(tooltip);
+        }
+        // Draw the GUI.
+        mq::window::clear_background(WHITE);
+        gui.draw();
+        mq::window::next_frame().await;
+    }
+}
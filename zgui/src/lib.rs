@@ -6,6 +6,7 @@ use std::{
     fmt::{self, Debug},
     rc::Rc,
     sync::mpsc::{channel, Receiver, Sender},
+    time::Duration,
 };
 
 use log::{info, trace};
@@ -25,6 +26,12 @@ pub const SPRITE_COLOR_BG: Color = Color::new(0.8, 0.8, 0.8, 0.5);
 pub const SPRITE_COLOR_BG_HIGHLIGHTED: Color = Color::new(0.9, 0.9, 0.9, 1.0);
 pub const SPRITE_COLOR_BUTTON_BORDER: Color = Color::new(0.0, 0.0, 0.0, 0.9);
 
+/// How long the mouse must rest on one spot before a tooltip is shown.
+pub const TOOLTIP_DELAY: Duration = Duration::from_millis(600);
+
+/// The gap between the mouse cursor and its tooltip.
+const TOOLTIP_OFFSET: f32 = 0.04;
+
 // TODO: Add ScrollArea widget
 
 pub type Result<T = ()> = std::result::Result<T, Error>;
@@ -247,6 +254,11 @@ pub trait Widget: Debug {
     fn rect(&self) -> Rect;
     fn set_pos(&mut self, pos: Vec2);
 
+    /// A widget to show next to the mouse cursor when it rests at `pos`.
+    fn tooltip(&self, _pos: Vec2) -> Option<RcWidget> {
+        None
+    }
+
     fn can_stretch(&self) -> bool {
         false
     }
@@ -284,9 +296,19 @@ pub struct AnchoredWidget {
 #[derive(Debug)]
 pub struct Gui<Message: Clone> {
     aspect_ratio: f32,
+
+    /// All the widgets are drawn this times bigger.
+    scale: f32,
+
     anchored_widgets: Vec<AnchoredWidget>,
     receiver: Receiver<Message>,
     sender: Sender<Message>,
+    mouse_pos: Vec2,
+
+    /// How long the mouse has been resting at `mouse_pos`.
+    hover_time: Duration,
+
+    tooltip: Option<RcWidget>,
 }
 
 impl<Message: Clone> Gui<Message> {
@@ -299,6 +321,10 @@ impl<Message: Clone> Gui<Message> {
             receiver,
             sender,
             aspect_ratio,
+            scale: 1.0,
+            mouse_pos: Vec2::new(0.0, 0.0),
+            hover_time: Duration::from_secs(0),
+            tooltip: None,
         }
     }
 
@@ -326,14 +352,20 @@ impl<Message: Clone> Gui<Message> {
     }
 
     pub fn draw(&self) {
-        let ui_coordinates = Rect::new(-self.aspect_ratio, -1.0, self.aspect_ratio * 2.0, 2.0);
+        let (w, h) = (self.aspect_ratio / self.scale, 1.0 / self.scale);
+        let ui_coordinates = Rect::new(-w, -h, w * 2.0, h * 2.0);
         set_camera(&Camera2D::from_display_rect(ui_coordinates));
         for AnchoredWidget { widget, .. } in &self.anchored_widgets {
             widget.borrow().draw();
         }
+        if let Some(tooltip) = &self.tooltip {
+            tooltip.borrow().draw();
+        }
     }
 
     pub fn click(&mut self, pos: Vec2) -> Option<Message> {
+        let pos = pos / self.scale;
+        self.reset_hover();
         for AnchoredWidget { widget, .. } in &self.anchored_widgets {
             widget.borrow_mut().click(pos);
         }
@@ -341,33 +373,118 @@ impl<Message: Clone> Gui<Message> {
     }
 
     pub fn move_mouse(&mut self, pos: Vec2) {
+        let pos = pos / self.scale;
+        if pos != self.mouse_pos {
+            self.mouse_pos = pos;
+            self.reset_hover();
+        }
         for AnchoredWidget { widget, .. } in &self.anchored_widgets {
             widget.borrow_mut().move_mouse(pos);
         }
     }
 
+    fn reset_hover(&mut self) {
+        self.hover_time = Duration::from_secs(0);
+        self.tooltip = None;
+    }
+
+    fn is_hover_long_enough(&self) -> bool {
+        self.hover_time >= TOOLTIP_DELAY
+    }
+
+    fn is_mouse_over_widget(&self) -> bool {
+        self.anchored_widgets
+            .iter()
+            .any(|w| w.widget.borrow().rect().contains(self.mouse_pos))
+    }
+
+    /// Shows the tooltip of the widget under the mouse
+    /// once the mouse has been resting long enough.
+    pub fn tick(&mut self, dtime: Duration) {
+        let was_long_enough = self.is_hover_long_enough();
+        self.hover_time += dtime;
+        if was_long_enough || !self.is_hover_long_enough() {
+            return;
+        }
+        let pos = self.mouse_pos;
+        let tooltip = self
+            .anchored_widgets
+            .iter()
+            .find_map(|w| w.widget.borrow().tooltip(pos));
+        if let Some(tooltip) = tooltip {
+            self.show_tooltip(tooltip);
+        }
+    }
+
+    /// The mouse has been resting long enough over no widget
+    /// and there's no tooltip yet, so the app may show its own one.
+    pub fn needs_tooltip(&self) -> bool {
+        self.is_hover_long_enough() && self.tooltip.is_none() && !self.is_mouse_over_widget()
+    }
+
+    /// Shows the widget next to the mouse cursor until the mouse moves or clicks.
+    pub fn show_tooltip(&mut self, tooltip: RcWidget) {
+        let (w, h) = (self.aspect_ratio / self.scale, 1.0 / self.scale);
+        {
+            let mut widget = tooltip.borrow_mut();
+            let rect = widget.rect();
+            let mut pos = self.mouse_pos + Vec2::new(TOOLTIP_OFFSET, TOOLTIP_OFFSET);
+            // Move the tooltip to the other side of the cursor if it doesn't fit.
+            if pos.x + rect.w > w {
+                pos.x = self.mouse_pos.x - TOOLTIP_OFFSET - rect.w;
+            }
+            if pos.y + rect.h > h {
+                pos.y = self.mouse_pos.y - TOOLTIP_OFFSET - rect.h;
+            }
+            widget.set_pos(pos);
+        }
+        self.tooltip = Some(tooltip);
+    }
+
+    pub fn hide_tooltip(&mut self) {
+        self.tooltip = None;
+    }
+
     pub fn resize(&mut self, ratio: f32) {
         self.aspect_ratio = ratio;
         trace!("Gui::resize: {}", ratio);
         let offset = 0.02; // TODO: make configurable
+        let (w, h) = (ratio / self.scale, 1.0 / self.scale);
         for AnchoredWidget { widget, anchor } in &mut self.anchored_widgets {
             let mut widget = widget.borrow_mut();
             let rect = widget.rect();
             let mut pos = rect.point();
             match anchor.0 {
-                HAnchor::Left => pos.x = (-ratio) + offset,
+                HAnchor::Left => pos.x = (-w) + offset,
                 HAnchor::Middle => pos.x = -rect.w / 2.0,
-                HAnchor::Right => pos.x = (ratio - rect.w) - offset,
+                HAnchor::Right => pos.x = (w - rect.w) - offset,
             }
             match anchor.1 {
-                VAnchor::Top => pos.y = (-1.0) + offset,
+                VAnchor::Top => pos.y = (-h) + offset,
                 VAnchor::Middle => pos.y = -rect.h / 2.0,
-                VAnchor::Bottom => pos.y = (1.0 - rect.h) - offset,
+                VAnchor::Bottom => pos.y = (h - rect.h) - offset,
             }
             widget.set_pos(pos);
         }
     }
 
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    /// Makes all the widgets bigger or smaller without rebuilding them.
+    pub fn set_scale(&mut self, scale: f32) {
+        if (self.scale - scale).abs() > f32::EPSILON {
+            self.scale = scale;
+            self.update_layout();
+        }
+    }
+
+    /// Re-anchors the widgets, e.g. after some of them changed their size.
+    pub fn update_layout(&mut self) {
+        self.resize(self.aspect_ratio);
+    }
+
     pub fn resize_if_needed(&mut self, aspect_ratio: f32) {
         if (self.aspect_ratio - aspect_ratio).abs() > f32::EPSILON {
             self.resize(aspect_ratio);
@@ -417,6 +534,7 @@ pub struct Label {
     param: LabelParam,
     rect: Rect,
     height: f32,
+    tooltip: Option<RcWidget>,
 }
 
 impl Label {
@@ -448,6 +566,7 @@ impl Label {
             param,
             rect,
             height,
+            tooltip: None,
         })
     }
 
@@ -468,6 +587,32 @@ impl Label {
     pub fn set_color(&mut self, color: Color) {
         self.sprite.color = color;
     }
+
+    pub fn with_tooltip(mut self, tooltip: RcWidget) -> Self {
+        self.tooltip = Some(tooltip);
+        self
+    }
+
+    /// Replaces the content, e.g. re-renders the text in another language.
+    ///
+    /// The label keeps its position and its stretched width,
+    /// but its natural width may change - see `Gui::update_layout`.
+    pub fn set_drawable(&mut self, drawable: Drawable) {
+        let pos = self.rect.point();
+        let color = self.sprite.color;
+        self.sprite = Sprite::new(drawable, self.height * self.param.drawable_k);
+        self.sprite.color = color;
+        let w = self.sprite.rect().w;
+        self.rect.w = if self.param.is_stretchable {
+            self.rect.w.max(w)
+        } else {
+            w
+        };
+        if self.param.bg {
+            self.bg = Some(make_bg(self.rect));
+        }
+        self.set_pos(pos);
+    }
 }
 
 impl Widget for Label {
@@ -493,6 +638,14 @@ impl Widget for Label {
         self.rect.y = pos.y;
     }
 
+    fn tooltip(&self, pos: Vec2) -> Option<RcWidget> {
+        if self.rect.contains(pos) {
+            self.tooltip.clone()
+        } else {
+            None
+        }
+    }
+
     fn can_stretch(&self) -> bool {
         self.param.is_stretchable
     }
@@ -690,6 +843,7 @@ pub struct Button<Message: Clone> {
     sender: Sender<Message>,
     message: Message,
     color: Color,
+    tooltip: Option<RcWidget>,
 }
 
 impl<Message: Clone + Debug> Button<Message> {
@@ -724,6 +878,7 @@ impl<Message: Clone + Debug> Button<Message> {
             sender,
             message,
             color: SPRITE_COLOR,
+            tooltip: None,
         })
     }
 
@@ -746,6 +901,11 @@ impl<Message: Clone + Debug> Button<Message> {
         self.is_active
     }
 
+    pub fn with_tooltip(mut self, tooltip: RcWidget) -> Self {
+        self.tooltip = Some(tooltip);
+        self
+    }
+
     pub fn stretchable(mut self, value: bool) -> Self {
         self.set_stretchable(value);
         self
@@ -755,6 +915,24 @@ impl<Message: Clone + Debug> Button<Message> {
         self.param.is_stretchable = value;
     }
 
+    /// Replaces the content, e.g. re-renders the text in another language.
+    ///
+    /// The button keeps its position and its stretched width,
+    /// but its natural width may change - see `Gui::update_layout`.
+    pub fn set_drawable(&mut self, drawable: Drawable) {
+        let pos = self.rect().point();
+        let height = self.bg.dimensions.h;
+        self.sprite = Sprite::new(drawable, height * self.param.drawable_k);
+        self.sprite.color = self.color;
+        let mut outer = Self::outer_rect(&self.sprite, height, &self.param);
+        if self.param.is_stretchable {
+            outer.w = outer.w.max(self.rect().w);
+        }
+        self.border = Self::make_border(height, outer, self.param.border_k);
+        self.bg = Self::make_bg_mesh(height, outer);
+        self.set_pos(pos);
+    }
+
     fn outer_rect(sprite: &Sprite, height: f32, param: &ButtonParam) -> Rect {
         let free_area_k = 1.0 - param.drawable_k - param.border_k * 2.0;
         let free_area = height * free_area_k;
@@ -807,6 +985,14 @@ impl<Message: Clone + Debug> Widget for Button<Message> {
         self.border.rect()
     }
 
+    fn tooltip(&self, pos: Vec2) -> Option<RcWidget> {
+        if self.border.rect().contains(pos) {
+            self.tooltip.clone()
+        } else {
+            None
+        }
+    }
+
     fn set_pos(&mut self, pos: Vec2) {
         let h = self.border.rect().h - self.sprite.rect().h;
         let w = self.border.rect().w - self.sprite.rect().w;
@@ -877,6 +1063,11 @@ impl Widget for Layout {
         }
     }
 
+    fn tooltip(&self, pos: Vec2) -> Option<RcWidget> {
+        // The widgets that are drawn later are on top.
+        self.widgets.iter().rev().find_map(|w| w.tooltip(pos))
+    }
+
     fn rect(&self) -> Rect {
         self.rect
     }
@@ -960,6 +1151,10 @@ impl Widget for VLayout {
         self.internal.move_mouse(pos);
     }
 
+    fn tooltip(&self, pos: Vec2) -> Option<RcWidget> {
+        self.internal.tooltip(pos)
+    }
+
     fn rect(&self) -> Rect {
         self.internal.rect()
     }
@@ -1023,6 +1218,10 @@ impl Widget for HLayout {
         self.internal.move_mouse(pos);
     }
 
+    fn tooltip(&self, pos: Vec2) -> Option<RcWidget> {
+        self.internal.tooltip(pos)
+    }
+
     fn rect(&self) -> Rect {
         self.internal.rect()
     }
@@ -1101,6 +1300,10 @@ impl Widget for LayersLayout {
         self.internal.move_mouse(pos);
     }
 
+    fn tooltip(&self, pos: Vec2) -> Option<RcWidget> {
+        self.internal.tooltip(pos)
+    }
+
     fn rect(&self) -> Rect {
         self.internal.rect()
     }
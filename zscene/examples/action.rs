@@ -8,7 +8,7 @@ use mq::{
     texture::{self, Texture2D},
     time, window,
 };
-use zscene::{self, action, Action, Boxed, Layer, Scene, Sprite};
+use zscene::{self, action, Action, Boxed, Easing, Layer, Scene, Sprite};
 
 #[derive(Debug)]
 pub enum Err {
@@ -78,7 +78,26 @@ impl State {
         let move_duration = Duration::from_millis(2_000);
         let action = action::Sequence::new(vec![
             action::Show::new(&self.layers.fg, &sprite).boxed(),
-            action::MoveBy::new(&sprite, delta, move_duration).boxed(),
+            action::MoveBy::new(&sprite, delta, move_duration)
+                .with_easing(Easing::OutBounce)
+                .boxed(),
+        ]);
+        action.boxed()
+    }
+
+    fn action_demo_tween(&self) -> Box<dyn Action> {
+        let mut sprite = Sprite::from_texture(self.assets.texture, 0.25);
+        sprite.set_pos(Vec2::new(1.0, 0.0));
+        let scale = {
+            let mut sprite = sprite.clone();
+            Box::new(move |k| sprite.set_scale(1.0 + k))
+        };
+        let duration = Duration::from_millis(1_500);
+        let action = action::Sequence::new(vec![
+            action::Show::new(&self.layers.fg, &sprite).boxed(),
+            action::Tween::new(scale, duration)
+                .with_easing(Easing::OutElastic)
+                .boxed(),
         ]);
         action.boxed()
     }
@@ -119,9 +138,10 @@ async fn main() {
     let assets = Assets::load().await.expect("Can't load assets");
     let mut state = State::new(assets);
     {
-        // Run two demo demo actions in parallel.
+        // Run three demo actions in parallel.
         state.scene.add_action(state.action_demo_move());
         state.scene.add_action(state.action_demo_show_hide());
+        state.scene.add_action(state.action_demo_tween());
     }
     loop {
         window::clear_background(BLACK);
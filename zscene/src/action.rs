@@ -3,7 +3,7 @@ use std::{fmt::Debug, time::Duration};
 pub use crate::action::{
     change_color_to::ChangeColorTo, custom::Custom, empty::Empty, fork::Fork, hide::Hide,
     move_by::MoveBy, sequence::Sequence, set_color::SetColor, set_facing::SetFacing,
-    set_frame::SetFrame, show::Show, sleep::Sleep,
+    set_frame::SetFrame, show::Show, sleep::Sleep, tween::Tween,
 };
 
 mod change_color_to;
@@ -18,6 +18,7 @@ mod set_facing;
 mod set_frame;
 mod show;
 mod sleep;
+mod tween;
 
 pub trait Action: Debug {
     fn begin(&mut self) {}
@@ -39,6 +40,14 @@ pub trait Action: Debug {
     }
 }
 
+/// How much of the action is done, from `0.0` to `1.0`.
+pub(crate) fn fraction(progress: Duration, duration: Duration) -> f32 {
+    if duration.as_nanos() == 0 {
+        return 1.0;
+    }
+    progress.as_secs_f32() / duration.as_secs_f32()
+}
+
 /// Just a helper trait to replace
 /// `Box::new(action::Empty::new())`
 /// with
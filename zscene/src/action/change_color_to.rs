@@ -2,7 +2,7 @@ use std::time::Duration;
 
 use mq::color::Color;
 
-use crate::{Action, Sprite};
+use crate::{action, Action, Easing, Sprite};
 
 #[derive(Debug)]
 pub struct ChangeColorTo {
@@ -10,6 +10,7 @@ pub struct ChangeColorTo {
     from: Color,
     to: Color,
     duration: Duration,
+    easing: Easing,
     progress: Duration,
 }
 
@@ -20,9 +21,15 @@ impl ChangeColorTo {
             from: sprite.color(),
             to,
             duration,
+            easing: Easing::Linear,
             progress: Duration::new(0, 0),
         }
     }
+
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
 }
 
 impl Action for ChangeColorTo {
@@ -34,9 +41,9 @@ impl Action for ChangeColorTo {
         if dtime + self.progress > self.duration {
             dtime = self.duration - self.progress;
         }
-        let progress_f = self.progress.as_secs_f32();
-        let duration_f = self.duration.as_secs_f32();
-        let k = progress_f / duration_f;
+        let k = self
+            .easing
+            .apply(action::fraction(self.progress, self.duration));
         self.sprite.set_color(interpolate(self.from, self.to, k));
         self.progress += dtime;
     }
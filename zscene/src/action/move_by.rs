@@ -2,14 +2,18 @@ use std::time::Duration;
 
 use mq::math::Vec2;
 
-use crate::{Action, Sprite};
+use crate::{action, Action, Easing, Sprite};
 
 #[derive(Debug)]
 pub struct MoveBy {
     sprite: Sprite,
     duration: Duration,
     delta: Vec2,
+    easing: Easing,
     progress: Duration,
+
+    /// The eased fraction of the delta that is already applied.
+    applied_k: f32,
 }
 
 impl MoveBy {
@@ -18,22 +22,41 @@ impl MoveBy {
             sprite: sprite.clone(),
             delta,
             duration,
+            easing: Easing::Linear,
             progress: Duration::new(0, 0),
+            applied_k: 0.0,
         }
     }
+
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    fn shift_to(&mut self, k: f32) {
+        // Only the difference is applied, so several moves
+        // of the same sprite can run at the same time.
+        let new_pos = self.sprite.pos() + self.delta * (k - self.applied_k);
+        self.sprite.set_pos(new_pos);
+        self.applied_k = k;
+    }
 }
 
 impl Action for MoveBy {
     fn update(&mut self, mut dtime: Duration) {
-        let old_pos = self.sprite.pos();
         if dtime + self.progress > self.duration {
             dtime = self.duration - self.progress;
         }
-        let dtime_f = dtime.as_secs_f32();
-        let duration_f = self.duration.as_secs_f32();
-        let new_pos = old_pos + self.delta * (dtime_f / duration_f);
-        self.sprite.set_pos(new_pos);
         self.progress += dtime;
+        let k = self
+            .easing
+            .apply(action::fraction(self.progress, self.duration));
+        self.shift_to(k);
+    }
+
+    /// A sequence may end an instant move without updating it.
+    fn end(&mut self) {
+        self.shift_to(1.0);
     }
 
     fn duration(&self) -> Duration {
@@ -0,0 +1,79 @@
+use std::{fmt, time::Duration};
+
+use crate::{action, Action, Easing};
+
+/// Calls the function every frame with the eased progress of the action,
+/// from `0.0` to `1.0`, so it can animate any property of a sprite.
+///
+/// ```ignore
+/// let mut sprite = sprite.clone();
+/// let f = Box::new(move |k| sprite.set_scale(1.0 + k));
+/// action::Tween::new(f, duration).with_easing(Easing::OutBack)
+/// ```
+pub struct Tween {
+    f: Box<dyn FnMut(f32)>,
+    duration: Duration,
+    easing: Easing,
+    progress: Duration,
+}
+
+impl fmt::Debug for Tween {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Tween")
+            .field("f", &"")
+            .field("duration", &self.duration)
+            .field("easing", &self.easing)
+            .field("progress", &self.progress)
+            .finish()
+    }
+}
+
+impl Tween {
+    pub fn new(f: Box<dyn FnMut(f32)>, duration: Duration) -> Self {
+        Self {
+            f,
+            duration,
+            easing: Easing::Linear,
+            progress: Duration::new(0, 0),
+        }
+    }
+
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    fn call(&mut self) {
+        let k = self
+            .easing
+            .apply(action::fraction(self.progress, self.duration));
+        (self.f)(k);
+    }
+}
+
+impl Action for Tween {
+    fn begin(&mut self) {
+        self.call();
+    }
+
+    fn update(&mut self, mut dtime: Duration) {
+        if dtime + self.progress > self.duration {
+            dtime = self.duration - self.progress;
+        }
+        self.progress += dtime;
+        self.call();
+    }
+
+    fn end(&mut self) {
+        self.progress = self.duration;
+        self.call();
+    }
+
+    fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    fn is_finished(&self) -> bool {
+        self.progress >= self.duration
+    }
+}
@@ -0,0 +1,158 @@
+use std::f32::consts::PI;
+
+/// Maps the linear progress of a tween action to the shape of its curve.
+///
+/// See <https://easings.net> for the plots.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Easing {
+    #[default]
+    Linear,
+
+    InQuad,
+    OutQuad,
+    InOutQuad,
+
+    InCubic,
+    OutCubic,
+    InOutCubic,
+
+    /// Pulls back a little before moving forward.
+    InBack,
+
+    /// Overshoots the target a little and comes back.
+    OutBack,
+
+    InOutBack,
+
+    InBounce,
+
+    /// Bounces off the target like a dropped ball.
+    OutBounce,
+
+    InOutBounce,
+
+    InElastic,
+
+    /// Wobbles around the target like a spring.
+    OutElastic,
+
+    InOutElastic,
+}
+
+impl Easing {
+    /// Takes and returns values from `0.0` (the start) to `1.0` (the end).
+    ///
+    /// Note: the back and elastic curves go a little outside of this range
+    /// in the middle of the action.
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        // The actions rely on the exact ends and the curves may miss them a bit.
+        if t == 0.0 || t == 1.0 {
+            return t;
+        }
+        match self {
+            Easing::Linear => t,
+            Easing::InQuad => t * t,
+            Easing::OutQuad => 1.0 - (1.0 - t).powi(2),
+            Easing::InOutQuad => in_out(t, |t| t * t),
+            Easing::InCubic => t.powi(3),
+            Easing::OutCubic => 1.0 - (1.0 - t).powi(3),
+            Easing::InOutCubic => in_out(t, |t| t.powi(3)),
+            Easing::InBack => in_back(t),
+            Easing::OutBack => 1.0 - in_back(1.0 - t),
+            Easing::InOutBack => in_out(t, in_back),
+            Easing::InBounce => in_bounce(t),
+            Easing::OutBounce => out_bounce(t),
+            Easing::InOutBounce => in_out(t, in_bounce),
+            Easing::InElastic => in_elastic(t),
+            Easing::OutElastic => out_elastic(t),
+            Easing::InOutElastic => in_out(t, in_elastic),
+        }
+    }
+}
+
+/// Runs the `ease_in` curve for the first half and its mirror for the second.
+fn in_out(t: f32, ease_in: fn(f32) -> f32) -> f32 {
+    if t < 0.5 {
+        ease_in(t * 2.0) / 2.0
+    } else {
+        1.0 - ease_in((1.0 - t) * 2.0) / 2.0
+    }
+}
+
+fn in_back(t: f32) -> f32 {
+    let overshoot = 1.70158;
+    t * t * ((overshoot + 1.0) * t - overshoot)
+}
+
+fn out_bounce(t: f32) -> f32 {
+    let n = 7.5625;
+    let d = 2.75;
+    if t < 1.0 / d {
+        n * t * t
+    } else if t < 2.0 / d {
+        let t = t - 1.5 / d;
+        n * t * t + 0.75
+    } else if t < 2.5 / d {
+        let t = t - 2.25 / d;
+        n * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / d;
+        n * t * t + 0.984_375
+    }
+}
+
+fn in_bounce(t: f32) -> f32 {
+    1.0 - out_bounce(1.0 - t)
+}
+
+fn in_elastic(t: f32) -> f32 {
+    1.0 - out_elastic(1.0 - t)
+}
+
+fn out_elastic(t: f32) -> f32 {
+    let period = 2.0 * PI / 3.0;
+    2.0_f32.powf(-10.0 * t) * ((t * 10.0 - 0.75) * period).sin() + 1.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Easing;
+
+    const ALL: [Easing; 16] = [
+        Easing::Linear,
+        Easing::InQuad,
+        Easing::OutQuad,
+        Easing::InOutQuad,
+        Easing::InCubic,
+        Easing::OutCubic,
+        Easing::InOutCubic,
+        Easing::InBack,
+        Easing::OutBack,
+        Easing::InOutBack,
+        Easing::InBounce,
+        Easing::OutBounce,
+        Easing::InOutBounce,
+        Easing::InElastic,
+        Easing::OutElastic,
+        Easing::InOutElastic,
+    ];
+
+    #[test]
+    fn exact_ends() {
+        for &easing in &ALL {
+            assert_eq!(easing.apply(0.0), 0.0, "{:?}", easing);
+            assert_eq!(easing.apply(1.0), 1.0, "{:?}", easing);
+        }
+    }
+
+    #[test]
+    fn in_out_is_symmetric() {
+        for &easing in &[Easing::InOutBack, Easing::InOutBounce, Easing::InOutElastic] {
+            assert!((easing.apply(0.5) - 0.5).abs() < 1e-5, "{:?}", easing);
+            let a = easing.apply(0.2);
+            let b = easing.apply(0.8);
+            assert!((a + b - 1.0).abs() < 1e-5, "{:?}", easing);
+        }
+    }
+}
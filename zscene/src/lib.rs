@@ -2,11 +2,13 @@ use std::{cell::RefCell, fmt, rc::Rc, time::Duration};
 
 pub use crate::{
     action::{Action, Boxed},
+    easing::Easing,
     sprite::{Facing, Sprite},
 };
 
 pub mod action;
 
+mod easing;
 mod sprite;
 
 pub type Result<T = ()> = std::result::Result<T, Error>;
@@ -105,6 +107,12 @@ impl Default for Layer {
 pub struct Scene {
     layers: Vec<Layer>,
     interpreter: ActionInterpreter,
+
+    /// How much faster than the real time the actions are played.
+    time_scale: f32,
+
+    /// All the actions jump to their final state on the next tick.
+    is_instant: bool,
 }
 
 impl Scene {
@@ -112,9 +120,30 @@ impl Scene {
         Self {
             layers,
             interpreter: ActionInterpreter::new(),
+            time_scale: 1.0,
+            is_instant: false,
+        }
+    }
+
+    pub fn time_scale(&self) -> f32 {
+        self.time_scale
+    }
+
+    /// Ignores the scales that aren't positive finite numbers.
+    pub fn set_time_scale(&mut self, time_scale: f32) {
+        if time_scale.is_finite() && time_scale > 0.0 {
+            self.time_scale = time_scale;
         }
     }
 
+    pub fn is_instant(&self) -> bool {
+        self.is_instant
+    }
+
+    pub fn set_instant(&mut self, value: bool) {
+        self.is_instant = value;
+    }
+
     pub fn draw(&self) {
         for layer in &self.layers {
             for z_sprite in &layer.data.borrow().sprites {
@@ -128,7 +157,11 @@ impl Scene {
     }
 
     pub fn tick(&mut self, dtime: Duration) {
-        self.interpreter.tick(dtime);
+        if self.is_instant {
+            self.interpreter.finish_all();
+        } else {
+            self.interpreter.tick(dtime.mul_f32(self.time_scale));
+        }
     }
 
     pub fn any_unfinished_actions(&self) -> bool {
@@ -169,4 +202,100 @@ impl ActionInterpreter {
         }
         self.actions.retain(|action| !action.is_finished());
     }
+
+    /// Plays all the actions, including the forked ones, to their end at once.
+    ///
+    /// Every action is updated with a big enough time step, so they all end up
+    /// in the same state as if they were played at the normal speed.
+    pub fn finish_all(&mut self) {
+        while !self.actions.is_empty() {
+            let durations = self.actions.iter().map(|action| action.duration());
+            let max_duration = durations.max().unwrap_or_default();
+            // A sequence starts only one new action per tick, so this may take several steps.
+            self.tick(max_duration + Duration::from_millis(1));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use mq::{color::Color, math::Vec2, texture::Texture2D};
+
+    use crate::{action, ActionInterpreter, Boxed, Easing, Scene, Sprite};
+
+    fn sprite() -> Sprite {
+        Sprite::from_texture(Texture2D::empty(), 1.0)
+    }
+
+    fn assert_near(a: Vec2, b: Vec2) {
+        assert!((a - b).length() < 1e-5, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn finish_all_sequence_with_fork() {
+        let a = sprite();
+        let b = sprite();
+        let time = Duration::from_millis(300);
+        let red = Color::new(1.0, 0.0, 0.0, 1.0);
+        let blue = Color::new(0.0, 0.0, 1.0, 0.5);
+        let forked = action::Sequence::new(vec![
+            action::Sleep::new(time).boxed(),
+            action::MoveBy::new(&b, Vec2::new(0.0, 2.0), time)
+                .with_easing(Easing::OutBack)
+                .boxed(),
+            action::ChangeColorTo::new(&b, blue, time).boxed(),
+        ]);
+        let mut interpreter = ActionInterpreter::new();
+        interpreter.add(
+            action::Sequence::new(vec![
+                action::MoveBy::new(&a, Vec2::new(1.0, 0.0), time).boxed(),
+                action::Fork::new(forked.boxed()).boxed(),
+                action::MoveBy::new(&a, Vec2::new(0.0, -1.0), time)
+                    .with_easing(Easing::InOutCubic)
+                    .boxed(),
+                action::ChangeColorTo::new(&a, red, time).boxed(),
+            ])
+            .boxed(),
+        );
+        interpreter.finish_all();
+        assert!(interpreter.actions.is_empty());
+        assert_near(a.pos(), Vec2::new(1.0, -1.0));
+        assert_eq!(a.color(), red);
+        assert_near(b.pos(), Vec2::new(0.0, 2.0));
+        assert_eq!(b.color(), blue);
+    }
+
+    #[test]
+    fn instant_moves() {
+        let a = sprite();
+        let b = sprite();
+        let instant = Duration::new(0, 0);
+        let mut interpreter = ActionInterpreter::new();
+        interpreter.add(action::MoveBy::new(&a, Vec2::new(1.0, 0.0), instant).boxed());
+        interpreter.add(
+            action::Sequence::new(vec![
+                action::MoveBy::new(&b, Vec2::new(1.0, 0.0), instant).boxed(),
+                action::MoveBy::new(&b, Vec2::new(0.0, 1.0), instant)
+                    .with_easing(Easing::OutBack)
+                    .boxed(),
+                action::Sleep::new(Duration::from_millis(100)).boxed(),
+            ])
+            .boxed(),
+        );
+        interpreter.tick(Duration::from_millis(10));
+        assert_near(a.pos(), Vec2::new(1.0, 0.0));
+        assert_near(b.pos(), Vec2::new(1.0, 1.0));
+    }
+
+    #[test]
+    fn bad_time_scale_is_ignored() {
+        let mut scene = Scene::new(vec![]);
+        scene.set_time_scale(2.0);
+        scene.set_time_scale(0.0);
+        scene.set_time_scale(-1.0);
+        scene.set_time_scale(f32::NAN);
+        assert_eq!(scene.time_scale(), 2.0);
+    }
 }